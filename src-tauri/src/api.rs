@@ -0,0 +1,853 @@
+//! Opt-in HTTP/JSON API on the loopback interface, for scripts and internal
+//! tools that read or add history without the GUI.
+//!
+//! Every request must carry `Authorization: Bearer <api_token>`. Clips come
+//! back in the shape the Tauri commands return (`FrontendClipItem`, content
+//! in base64):
+//!
+//! - `GET /clips?limit=N`: recent unpinned clips, newest first;
+//!   `?pinned=true` lists the pinned clips in slot order instead
+//! - `GET /clips/search?q=...&limit=N`
+//! - `GET /clips/{id}`: one clip with its full text
+//! - `POST /clips` with `{"text": "..."}`: adds a text clip to history as
+//!   if it had been copied, so normalizers, secret detection, capture rules,
+//!   plugins and hooks apply; `422` when one of them dropped it
+//! - `PUT /clips/{id}/pin` and `DELETE /clips/{id}/pin`
+//! - `DELETE /clips/{id}`
+//! - `GET /events`: server-sent `clipboard-changed` events, carrying the
+//!   same payload the QuickBar receives
+//!
+//! The server reads and edits storage directly. New clips go through
+//! `ApiHost` to the capture pipeline, and `ApiHost` tells the rest of the app
+//! about the other changes the server makes.
+
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+use uuid::Uuid;
+
+use crate::storage::{ClipStorage, FrontendClipItem};
+
+pub const DEFAULT_API_PORT: u16 = 17321;
+/// Shortest `api_token` accepted on save; generated tokens are 64 hex digits.
+pub const MIN_API_TOKEN_LEN: usize = 16;
+const DEFAULT_LIST_LIMIT: usize = 50;
+const MAX_LIST_LIMIT: usize = 1000;
+/// How often an idle `/events` stream gets a comment line. Writing is the
+/// only way to notice a client that went away.
+const EVENTS_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// What the API needs from the running app beyond storage.
+pub trait ApiHost: Send + Sync + 'static {
+    fn max_text_bytes(&self) -> usize;
+    /// Runs `text` through the capture pipeline and stores it, announcing
+    /// the clip like any other capture. `None` when the pipeline dropped it.
+    fn capture_text(&self, text: String) -> Option<FrontendClipItem>;
+    /// A clip was (un)pinned; `item` is its new preview.
+    fn clip_changed(&self, item: &FrontendClipItem);
+    fn clip_deleted(&self, id: &str);
+}
+
+pub fn generate_api_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Fans `clipboard-changed` payloads out to the open `/events` streams.
+#[derive(Default)]
+pub struct ApiEvents {
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+impl ApiEvents {
+    pub fn publish(&self, payload: &str) {
+        crate::safe_lock(&self.subscribers)
+            .retain(|subscriber| subscriber.send(payload.to_string()).is_ok());
+    }
+
+    fn subscribe(&self) -> Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        crate::safe_lock(&self.subscribers).push(sender);
+        receiver
+    }
+
+    /// Ends every open stream, so a stopped server (or a replaced token)
+    /// keeps no client attached.
+    fn disconnect_all(&self) {
+        crate::safe_lock(&self.subscribers).clear();
+    }
+}
+
+/// A listening API server; dropping it stops the server and ends its
+/// `/events` streams.
+pub struct ApiServer {
+    server: Arc<Server>,
+    events: Arc<ApiEvents>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ApiServer {
+    /// Listens on `127.0.0.1:port` (`0` picks a free port).
+    pub fn start(
+        port: u16,
+        token: String,
+        storage: Arc<Mutex<ClipStorage>>,
+        events: Arc<ApiEvents>,
+        host: Arc<dyn ApiHost>,
+    ) -> Result<Self, String> {
+        let server = Server::http(SocketAddr::from(([127, 0, 0, 1], port)))
+            .map_err(|e| format!("Failed to listen on 127.0.0.1:{}: {}", port, e))?;
+        let server = Arc::new(server);
+        let api = Arc::new(Api {
+            token,
+            storage,
+            events: events.clone(),
+            host,
+        });
+
+        let accepting = server.clone();
+        let thread = std::thread::Builder::new()
+            .name("clipman-api".to_string())
+            .spawn(move || {
+                for request in accepting.incoming_requests() {
+                    let api = api.clone();
+                    // `/events` holds its connection open, so each request
+                    // gets a thread of its own.
+                    let spawned = std::thread::Builder::new()
+                        .name("clipman-api-request".to_string())
+                        .spawn(move || api.handle(request));
+                    if let Err(e) = spawned {
+                        log::warn!("Failed to spawn an API request thread: {}", e);
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start the API thread: {}", e))?;
+
+        let server = Self {
+            server,
+            events,
+            thread: Some(thread),
+        };
+        log::info!("Automation API listening on 127.0.0.1:{}", server.port());
+        Ok(server)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.server
+            .server_addr()
+            .to_ip()
+            .map_or(0, |address| address.port())
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::warn!("The API thread panicked");
+            }
+        }
+        self.events.disconnect_all();
+    }
+}
+
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(id: &str) -> Self {
+        Self::new(404, format!("No clip with id {}", id))
+    }
+
+    fn storage(error: rusqlite::Error) -> Self {
+        Self::new(500, error.to_string())
+    }
+}
+
+/// Status and JSON body of a successful request.
+type ApiResult = Result<(u16, String), ApiError>;
+
+fn json_reply<T: Serialize>(status: u16, value: &T) -> ApiResult {
+    serde_json::to_string(value)
+        .map(|body| (status, body))
+        .map_err(|e| ApiError::new(500, e.to_string()))
+}
+
+#[derive(Deserialize)]
+struct NewClip {
+    text: String,
+}
+
+struct Api {
+    token: String,
+    storage: Arc<Mutex<ClipStorage>>,
+    events: Arc<ApiEvents>,
+    host: Arc<dyn ApiHost>,
+}
+
+impl Api {
+    fn handle(&self, mut request: Request) {
+        // The listener is bound to 127.0.0.1; this only guards against that
+        // ever changing.
+        let is_local = request
+            .remote_addr()
+            .is_some_and(|address| address.ip().is_loopback());
+        if !is_local {
+            respond_error(
+                request,
+                ApiError::new(403, "Only local clients may use the API"),
+            );
+            return;
+        }
+        if !self.is_authorized(&request) {
+            respond_error(request, ApiError::new(401, "Missing or wrong API token"));
+            return;
+        }
+
+        let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| percent_decode(segment, false))
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        let query = Query::parse(query);
+        let method = request.method().clone();
+
+        let result = match (&method, segments.as_slice()) {
+            (Method::Get, ["events"]) => {
+                self.stream_events(request);
+                return;
+            }
+            (Method::Get, ["clips"]) => self.list(&query),
+            (Method::Get, ["clips", "search"]) => self.search(&query),
+            (Method::Post, ["clips"]) => self.insert(&mut request),
+            (Method::Get, ["clips", id]) => self.get(id),
+            (Method::Delete, ["clips", id]) => self.delete(id),
+            (Method::Put, ["clips", id, "pin"]) => self.set_pinned(id, true),
+            (Method::Delete, ["clips", id, "pin"]) => self.set_pinned(id, false),
+            (_, ["events"] | ["clips"] | ["clips", _] | ["clips", _, "pin"]) => {
+                Err(ApiError::new(405, "Method not allowed"))
+            }
+            _ => Err(ApiError::new(404, "Not found")),
+        };
+
+        match result {
+            Ok((status, body)) => respond(request, status, body),
+            Err(error) => respond_error(request, error),
+        }
+    }
+
+    fn is_authorized(&self, request: &Request) -> bool {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"))
+            .and_then(|header| header.value.as_str().strip_prefix("Bearer "))
+            .is_some_and(|token| tokens_match(token.trim(), &self.token))
+    }
+
+    fn list(&self, query: &Query) -> ApiResult {
+        let limit = query.limit()?;
+        let storage = crate::safe_lock(&self.storage);
+        let items = if query.get("pinned") == Some("true") {
+            storage.get_pinned_clip_previews_with_limit(limit)
+        } else {
+            storage.get_recent_clip_previews(limit)
+        }
+        .map_err(ApiError::storage)?;
+        drop(storage);
+
+        let items: Vec<FrontendClipItem> = items
+            .into_iter()
+            .map(FrontendClipItem::from_preview)
+            .collect();
+        json_reply(200, &items)
+    }
+
+    fn search(&self, query: &Query) -> ApiResult {
+        let limit = query.limit()?;
+        let text = query.get("q").unwrap_or_default();
+        let items = crate::safe_lock(&self.storage)
+            .search_clip_previews(text)
+            .map_err(ApiError::storage)?;
+
+        let items: Vec<FrontendClipItem> = items
+            .into_iter()
+            .take(limit)
+            .map(FrontendClipItem::from_preview)
+            .collect();
+        json_reply(200, &items)
+    }
+
+    fn get(&self, id: &str) -> ApiResult {
+        let storage = crate::safe_lock(&self.storage);
+        let item = storage
+            .get_by_id(id)
+            .map_err(ApiError::storage)?
+            .ok_or_else(|| ApiError::not_found(id))?;
+        // Images come back as their thumbnail, as the QuickBar shows them.
+        let item = match FrontendClipItem::from_full_text(item) {
+            Some(item) => item,
+            None => storage
+                .get_preview_by_id(id)
+                .map_err(ApiError::storage)?
+                .map(FrontendClipItem::from_preview)
+                .ok_or_else(|| ApiError::not_found(id))?,
+        };
+        json_reply(200, &item)
+    }
+
+    fn insert(&self, request: &mut Request) -> ApiResult {
+        let max_text_bytes = self.host.max_text_bytes();
+        // JSON escapes take at most six bytes per byte of text.
+        let max_body_bytes = max_text_bytes.saturating_mul(6).saturating_add(1024);
+        let mut body = Vec::new();
+        request
+            .as_reader()
+            .take(max_body_bytes as u64 + 1)
+            .read_to_end(&mut body)
+            .map_err(|e| ApiError::new(400, format!("Failed to read the request: {}", e)))?;
+        if body.len() > max_body_bytes {
+            return Err(ApiError::new(413, "Request body is too large"));
+        }
+
+        let NewClip { text } = serde_json::from_slice(&body)
+            .map_err(|e| ApiError::new(400, format!("Expected {{\"text\": \"...\"}}: {}", e)))?;
+        if text.is_empty() {
+            return Err(ApiError::new(400, "Text cannot be empty"));
+        }
+        if text.len() > max_text_bytes {
+            return Err(ApiError::new(
+                413,
+                format!("Text is longer than {} bytes", max_text_bytes),
+            ));
+        }
+
+        // An identical clip already in history is refreshed instead.
+        let preview = self.host.capture_text(text).ok_or_else(|| {
+            ApiError::new(
+                422,
+                "The clip was dropped by secret detection, a capture rule or a plugin",
+            )
+        })?;
+        json_reply(201, &preview)
+    }
+
+    fn set_pinned(&self, id: &str, is_pinned: bool) -> ApiResult {
+        let preview = {
+            let storage = crate::safe_lock(&self.storage);
+            let current = storage
+                .get_preview_by_id(id)
+                .map_err(ApiError::storage)?
+                .ok_or_else(|| ApiError::not_found(id))?;
+            // Re-pinning would move the clip to the last slot.
+            if current.is_pinned == is_pinned {
+                return json_reply(200, &FrontendClipItem::from_preview(current));
+            }
+            storage
+                .update_pin(id, is_pinned)
+                .map_err(ApiError::storage)?;
+            storage
+                .get_preview_by_id(id)
+                .map_err(ApiError::storage)?
+                .ok_or_else(|| ApiError::not_found(id))?
+        };
+        let preview = FrontendClipItem::from_preview(preview);
+        self.host.clip_changed(&preview);
+        json_reply(200, &preview)
+    }
+
+    fn delete(&self, id: &str) -> ApiResult {
+        {
+            let storage = crate::safe_lock(&self.storage);
+            if storage
+                .get_preview_by_id(id)
+                .map_err(ApiError::storage)?
+                .is_none()
+            {
+                return Err(ApiError::not_found(id));
+            }
+            storage.delete(id).map_err(ApiError::storage)?;
+        }
+        self.host.clip_deleted(id);
+        Ok((204, String::new()))
+    }
+
+    fn stream_events(&self, request: Request) {
+        // Subscribe before the response head goes out, so a client that has
+        // read the head is sure to get every later event.
+        let receiver = self.events.subscribe();
+        let mut writer = request.into_writer();
+        let head = "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/event-stream\r\n\
+                    Cache-Control: no-cache\r\n\
+                    Connection: close\r\n\r\n";
+        if write_flushed(&mut writer, head).is_err() {
+            return;
+        }
+
+        loop {
+            let chunk = match receiver.recv_timeout(EVENTS_KEEP_ALIVE) {
+                Ok(payload) => format!("event: clipboard-changed\ndata: {}\n\n", payload),
+                Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+            if write_flushed(&mut writer, &chunk).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn write_flushed(writer: &mut impl Write, text: &str) -> std::io::Result<()> {
+    writer.write_all(text.as_bytes())?;
+    writer.flush()
+}
+
+fn respond(request: Request, status: u16, body: String) {
+    let mut response = Response::from_string(body).with_status_code(StatusCode(status));
+    if status != 204 {
+        let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+            .expect("static header is valid");
+        response.add_header(content_type);
+    }
+    if let Err(e) = request.respond(response) {
+        log::debug!("Failed to send an API response: {}", e);
+    }
+}
+
+fn respond_error(request: Request, error: ApiError) {
+    let body = serde_json::json!({ "error": error.message }).to_string();
+    respond(request, error.status, body);
+}
+
+/// Compares in time independent of where the tokens differ.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Decoded `key=value` pairs of a query string.
+struct Query(Vec<(String, String)>);
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        Self(
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    (percent_decode(key, true), percent_decode(value, true))
+                })
+                .collect(),
+        )
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn limit(&self) -> Result<usize, ApiError> {
+        match self.get("limit") {
+            None => Ok(DEFAULT_LIST_LIMIT),
+            Some(limit) => limit
+                .parse()
+                .ok()
+                .filter(|limit| (1..=MAX_LIST_LIMIT).contains(limit))
+                .ok_or_else(|| {
+                    ApiError::new(400, format!("limit must be 1 to {}", MAX_LIST_LIMIT))
+                }),
+        }
+    }
+}
+
+/// `%XX` escapes, and `+` as a space in query strings. Malformed escapes are
+/// kept as they are.
+fn percent_decode(text: &str, plus_is_space: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| bytes.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (escaped, bytes[index]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (None, b'+') if plus_is_space => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_encoding::BASE64;
+    use serde_json::Value;
+    use std::io::BufRead;
+    use std::net::TcpStream;
+    use std::path::{Path, PathBuf};
+
+    const TOKEN: &str = "test-token-0123456789";
+
+    /// Text containing this is dropped, as a capture rule or plugin would.
+    const DROPPED_MARKER: &str = "drop-me";
+
+    struct TestHost {
+        storage: Arc<Mutex<ClipStorage>>,
+        events: Arc<ApiEvents>,
+        changed: Mutex<Vec<String>>,
+        deleted: Mutex<Vec<String>>,
+    }
+
+    impl ApiHost for TestHost {
+        fn max_text_bytes(&self) -> usize {
+            64
+        }
+
+        // Stands in for the capture pipeline: stores the text as is.
+        fn capture_text(&self, text: String) -> Option<FrontendClipItem> {
+            if text.contains(DROPPED_MARKER) {
+                return None;
+            }
+            let item = crate::storage::ClipItem {
+                id: Uuid::new_v4().to_string(),
+                content: text.into_bytes(),
+                thumbnail: None,
+                content_type: crate::storage::ContentType::Text,
+                timestamp: 1,
+                is_pinned: false,
+                pin_order: None,
+                label: None,
+                group_name: None,
+                source_app: None,
+                html: None,
+                rtf: None,
+                perceptual_hash: None,
+                text_kind: None,
+                code_language: None,
+                is_redacted: false,
+                expires_at: None,
+                capture_marker: None,
+                formats: Vec::new(),
+            };
+            let preview = {
+                let storage = crate::safe_lock(&self.storage);
+                let id = storage.insert(&item, 100).unwrap().unwrap_or(item.id);
+                storage.get_preview_by_id(&id).unwrap()?
+            };
+            let preview = FrontendClipItem::from_preview(preview);
+            self.clip_changed(&preview);
+            Some(preview)
+        }
+
+        // Like the app: a change reaches `/events` as `clipboard-changed`.
+        fn clip_changed(&self, item: &FrontendClipItem) {
+            crate::safe_lock(&self.changed).push(item.id.clone());
+            self.events
+                .publish(&serde_json::to_string(item).expect("clip serializes"));
+        }
+
+        fn clip_deleted(&self, id: &str) {
+            crate::safe_lock(&self.deleted).push(id.to_string());
+        }
+    }
+
+    struct TestServer {
+        server: ApiServer,
+        host: Arc<TestHost>,
+        db_path: PathBuf,
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let db_path = self.db_path.clone();
+            let _ = std::fs::remove_file(&db_path);
+            let _ = std::fs::remove_file(format!("{}-shm", db_path.display()));
+            let _ = std::fs::remove_file(format!("{}-wal", db_path.display()));
+        }
+    }
+
+    fn start_server() -> TestServer {
+        let db_path =
+            std::env::temp_dir().join(format!("clipman_api_{}.db", Uuid::new_v4().simple()));
+        let storage = Arc::new(Mutex::new(ClipStorage::new(Path::new(&db_path)).unwrap()));
+        let host = Arc::new(TestHost {
+            storage: storage.clone(),
+            events: Arc::default(),
+            changed: Mutex::default(),
+            deleted: Mutex::default(),
+        });
+        let server = ApiServer::start(
+            0,
+            TOKEN.to_string(),
+            storage,
+            host.events.clone(),
+            host.clone(),
+        )
+        .unwrap();
+        TestServer {
+            server,
+            host,
+            db_path,
+        }
+    }
+
+    fn send(stream: &mut TcpStream, method: &str, path: &str, token: Option<&str>, body: &str) {
+        let auth = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\n{auth}Connection: close\r\n\
+             Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+    }
+
+    /// Status and body of one request made with a plain local client.
+    fn request(
+        server: &TestServer,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> (u16, String) {
+        let mut stream = TcpStream::connect(("127.0.0.1", server.server.port())).unwrap();
+        send(&mut stream, method, path, token, body);
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    fn json(body: &str) -> Value {
+        serde_json::from_str(body).unwrap()
+    }
+
+    fn ids(body: &str) -> Vec<String> {
+        json(body)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn requests_need_the_bearer_token() {
+        let server = start_server();
+
+        assert_eq!(401, request(&server, "GET", "/clips", None, "").0);
+        assert_eq!(
+            401,
+            request(&server, "GET", "/clips", Some("test-token-0123456788"), "").0
+        );
+        assert_eq!(401, request(&server, "GET", "/events", Some("short"), "").0);
+        let (status, body) = request(&server, "GET", "/clips", Some(TOKEN), "");
+        assert_eq!(200, status);
+        assert_eq!(Value::Array(Vec::new()), json(&body));
+    }
+
+    #[test]
+    fn clips_can_be_added_listed_searched_pinned_and_deleted() {
+        let server = start_server();
+
+        let (status, body) = request(
+            &server,
+            "POST",
+            "/clips",
+            Some(TOKEN),
+            r#"{"text": "hello from a script"}"#,
+        );
+        assert_eq!(201, status);
+        let added = json(&body);
+        let id = added["id"].as_str().unwrap().to_string();
+        assert_eq!(
+            BASE64.encode(b"hello from a script"),
+            added["content"].as_str().unwrap()
+        );
+
+        let (_, body) = request(&server, "GET", "/clips?limit=10", Some(TOKEN), "");
+        assert_eq!(vec![id.clone()], ids(&body));
+        let (_, body) = request(
+            &server,
+            "GET",
+            "/clips/search?q=from+a%20script",
+            Some(TOKEN),
+            "",
+        );
+        assert_eq!(vec![id.clone()], ids(&body));
+        let (_, body) = request(&server, "GET", "/clips/search?q=nothing", Some(TOKEN), "");
+        assert!(ids(&body).is_empty());
+        let (status, body) = request(&server, "GET", &format!("/clips/{id}"), Some(TOKEN), "");
+        assert_eq!(200, status);
+        assert_eq!("text", json(&body)["contentType"]);
+
+        let pin_path = format!("/clips/{id}/pin");
+        let (status, body) = request(&server, "PUT", &pin_path, Some(TOKEN), "");
+        assert_eq!(200, status);
+        assert_eq!(Value::Bool(true), json(&body)["isPinned"]);
+        let (_, body) = request(&server, "GET", "/clips?pinned=true", Some(TOKEN), "");
+        assert_eq!(vec![id.clone()], ids(&body));
+        let (_, body) = request(&server, "DELETE", &pin_path, Some(TOKEN), "");
+        assert_eq!(Value::Bool(false), json(&body)["isPinned"]);
+
+        let clip_path = format!("/clips/{id}");
+        assert_eq!(
+            204,
+            request(&server, "DELETE", &clip_path, Some(TOKEN), "").0
+        );
+        assert_eq!(404, request(&server, "GET", &clip_path, Some(TOKEN), "").0);
+        assert_eq!(
+            404,
+            request(&server, "DELETE", &clip_path, Some(TOKEN), "").0
+        );
+
+        assert_eq!(
+            vec![id.clone(), id.clone(), id.clone()],
+            *crate::safe_lock(&server.host.changed)
+        );
+        assert_eq!(vec![id], *crate::safe_lock(&server.host.deleted));
+    }
+
+    #[test]
+    fn bad_requests_are_rejected() {
+        let server = start_server();
+        let post = |body: &str| request(&server, "POST", "/clips", Some(TOKEN), body).0;
+
+        assert_eq!(400, post(r#"{"text": ""}"#));
+        assert_eq!(400, post("not json"));
+        assert_eq!(413, post(&format!(r#"{{"text": "{}"}}"#, "x".repeat(65))));
+        assert_eq!(201, post(&format!(r#"{{"text": "{}"}}"#, "x".repeat(64))));
+        assert_eq!(422, post(&format!(r#"{{"text": "{}"}}"#, DROPPED_MARKER)));
+        assert_eq!(
+            400,
+            request(&server, "GET", "/clips?limit=0", Some(TOKEN), "").0
+        );
+        assert_eq!(
+            400,
+            request(&server, "GET", "/clips?limit=many", Some(TOKEN), "").0
+        );
+        assert_eq!(405, request(&server, "PUT", "/clips", Some(TOKEN), "").0);
+        assert_eq!(404, request(&server, "GET", "/history", Some(TOKEN), "").0);
+        assert_eq!(
+            404,
+            request(&server, "PUT", "/clips/missing/pin", Some(TOKEN), "").0
+        );
+    }
+
+    #[test]
+    fn events_stream_clipboard_changes() {
+        let server = start_server();
+        let mut stream = TcpStream::connect(("127.0.0.1", server.server.port())).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        send(&mut stream, "GET", "/events", Some(TOKEN), "");
+        let mut reader = std::io::BufReader::new(stream);
+
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert!(line.starts_with("HTTP/1.1 200"), "{line}");
+        while line != "\r\n" {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+
+        let (_, body) = request(
+            &server,
+            "POST",
+            "/clips",
+            Some(TOKEN),
+            r#"{"text": "live"}"#,
+        );
+        let id = json(&body)["id"].as_str().unwrap().to_string();
+
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!("event: clipboard-changed\n", line);
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        let payload = json(line.strip_prefix("data: ").unwrap());
+        assert_eq!(id, payload["id"].as_str().unwrap());
+    }
+
+    #[test]
+    fn stopping_the_server_ends_event_streams() {
+        let server = start_server();
+        let mut stream = TcpStream::connect(("127.0.0.1", server.server.port())).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        send(&mut stream, "GET", "/events", Some(TOKEN), "");
+        let mut reader = std::io::BufReader::new(stream);
+        let mut head = String::new();
+        reader.read_line(&mut head).unwrap();
+
+        drop(server);
+
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert!(!rest.contains("event:"));
+    }
+
+    #[test]
+    fn query_strings_are_percent_decoded() {
+        let query = Query::parse("q=caf%C3%A9+au%20lait&limit=5&flag&bad=%zz");
+        assert_eq!(Some("café au lait"), query.get("q"));
+        assert_eq!(5, query.limit().unwrap());
+        assert_eq!(Some(""), query.get("flag"));
+        assert_eq!(Some("%zz"), query.get("bad"));
+        assert_eq!(None, query.get("missing"));
+        assert_eq!("a+b", percent_decode("a+b", false));
+    }
+
+    #[test]
+    fn generated_tokens_are_long_and_distinct() {
+        let token = generate_api_token();
+        assert_eq!(64, token.len());
+        assert!(token.len() >= MIN_API_TOKEN_LEN);
+        assert_ne!(token, generate_api_token());
+    }
+}
@@ -0,0 +1,556 @@
+//! Content classification for captured text clips.
+//!
+//! Every text clip is stored as `ContentType::Text`; this module refines it
+//! into a kind (URL, email, colour, JSON, code, ...) at capture time so the
+//! kind can drive search filters and kind-specific UI. Detection is
+//! deliberately conservative: single-value kinds must match the *whole*
+//! trimmed clip, and anything ambiguous stays `Plain`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TextKind {
+    Url,
+    Email,
+    Phone,
+    Color,
+    Path,
+    Json,
+    Code,
+    Number,
+    Date,
+    Plain,
+}
+
+impl TextKind {
+    pub fn as_db_value(self) -> &'static str {
+        match self {
+            TextKind::Url => "url",
+            TextKind::Email => "email",
+            TextKind::Phone => "phone",
+            TextKind::Color => "color",
+            TextKind::Path => "path",
+            TextKind::Json => "json",
+            TextKind::Code => "code",
+            TextKind::Number => "number",
+            TextKind::Date => "date",
+            TextKind::Plain => "plain",
+        }
+    }
+
+    pub fn from_db_value(value: &str) -> Option<Self> {
+        Some(match value {
+            "url" => TextKind::Url,
+            "email" => TextKind::Email,
+            "phone" => TextKind::Phone,
+            "color" => TextKind::Color,
+            "path" => TextKind::Path,
+            "json" => TextKind::Json,
+            "code" => TextKind::Code,
+            "number" => TextKind::Number,
+            "date" => TextKind::Date,
+            "plain" => TextKind::Plain,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    pub kind: TextKind,
+    /// Best-guess language for `TextKind::Code`, when one stood out.
+    pub code_language: Option<&'static str>,
+}
+
+impl Classification {
+    fn of(kind: TextKind) -> Self {
+        Self {
+            kind,
+            code_language: None,
+        }
+    }
+}
+
+static URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?i)((https?|ftp)://[^\s/$.?#][^\s]*|www\.[^\s/]+\.[a-z]{2,}[^\s]*)$").unwrap()
+});
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?i)(mailto:)?[a-z0-9._%+-]+@[a-z0-9-]+(\.[a-z0-9-]+)*\.[a-z]{2,}$").unwrap()
+});
+
+static PHONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\+?[0-9][0-9 ().-]{5,}[0-9]$").unwrap());
+
+static HEX_COLOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^#([0-9a-fA-F]{3}|[0-9a-fA-F]{4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap()
+});
+
+static FUNCTIONAL_COLOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?i)(rgb|hsl)a?\(\s*\d{1,3}(\.\d+)?%?\s*[, ]\s*\d{1,3}(\.\d+)?%?\s*[, ]\s*\d{1,3}(\.\d+)?%?\s*([,/]\s*(0|1|0?\.\d+|\d{1,3}%)\s*)?\)$",
+    )
+    .unwrap()
+});
+
+static UNIX_PATH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^~?/[^/\n]+(/[^/\n]*)*$").unwrap());
+
+static WINDOWS_PATH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^([A-Za-z]:\\|\\\\[^\\\s]+\\)[^<>:"|?*\n]*$"#).unwrap());
+
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[+-]?(\d{1,3}(,\d{3})+|\d+)(\.\d+)?([eE][+-]?\d+)?%?$").unwrap()
+});
+
+static ISO_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4})-(\d{2})-(\d{2})([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:?\d{2})?)?$")
+        .unwrap()
+});
+
+static SLASH_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{4})/(\d{1,2})/(\d{1,2})$").unwrap());
+
+static CJK_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{4})年(\d{1,2})月(\d{1,2})日$").unwrap());
+
+/// Per-language signatures; a clip scores one point per signature it
+/// contains. Kept to tokens that rarely appear in prose.
+const CODE_SIGNATURES: &[(&str, &[&str])] = &[
+    (
+        "rust",
+        &[
+            "fn ",
+            "let mut ",
+            "impl ",
+            "pub fn ",
+            "#[derive",
+            "&mut ",
+            "-> Result<",
+            "::new(",
+        ],
+    ),
+    (
+        "python",
+        &[
+            "def ", "import ", "elif ", "self.", "print(", "__init__", "from ", "None:",
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            "interface ",
+            ": string",
+            ": number",
+            "export type ",
+            "readonly ",
+            "as const",
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            "const ",
+            "function ",
+            "=> {",
+            "console.log(",
+            "require(",
+            "document.",
+            "===",
+            "let ",
+        ],
+    ),
+    (
+        "go",
+        &[
+            "func ",
+            "package ",
+            ":= ",
+            "fmt.",
+            "go func",
+            "chan ",
+            "err != nil",
+        ],
+    ),
+    (
+        "java",
+        &[
+            "public class ",
+            "System.out.",
+            "private ",
+            "public static void",
+            "@Override",
+            "new ",
+        ],
+    ),
+    (
+        "cpp",
+        &[
+            "#include ",
+            "std::",
+            "int main(",
+            "nullptr",
+            "cout <<",
+            "template<",
+        ],
+    ),
+    (
+        "sql",
+        &[
+            "SELECT ",
+            "FROM ",
+            "WHERE ",
+            "INSERT INTO ",
+            "CREATE TABLE ",
+            "JOIN ",
+            "GROUP BY ",
+        ],
+    ),
+    (
+        "shell",
+        &[
+            "#!/bin/", "sudo ", "echo ", "export ", " | grep ", "&& ", "$HOME", "fi\n",
+        ],
+    ),
+    (
+        "html",
+        &[
+            "<!DOCTYPE",
+            "<div",
+            "</div>",
+            "<span",
+            "<html",
+            "href=\"",
+            "class=\"",
+        ],
+    ),
+    (
+        "css",
+        &[
+            "{\n",
+            "px;",
+            "color:",
+            "margin:",
+            "padding:",
+            "display:",
+            "font-size:",
+        ],
+    ),
+];
+
+/// Classify a captured text clip.
+pub fn classify_text(text: &str) -> Classification {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Classification::of(TextKind::Plain);
+    }
+
+    if !trimmed.contains('\n') {
+        if let Some(kind) = classify_single_value(trimmed) {
+            return Classification::of(kind);
+        }
+    }
+
+    if is_json(trimmed) {
+        return Classification::of(TextKind::Json);
+    }
+
+    if let Some(code_language) = detect_code(trimmed) {
+        return Classification {
+            kind: TextKind::Code,
+            code_language,
+        };
+    }
+
+    Classification::of(TextKind::Plain)
+}
+
+/// Kinds that only apply when the whole (single-line) clip is one value.
+/// Order matters: dates are checked before phone numbers so `2024-01-02` is
+/// not read as a phone, and a phone must start with `+` to win over an
+/// equally valid number such as `3.14159265`.
+fn classify_single_value(value: &str) -> Option<TextKind> {
+    if URL.is_match(value) {
+        return Some(TextKind::Url);
+    }
+    if EMAIL.is_match(value) {
+        return Some(TextKind::Email);
+    }
+    if HEX_COLOR.is_match(value) || FUNCTIONAL_COLOR.is_match(value) {
+        return Some(TextKind::Color);
+    }
+    if is_date(value) {
+        return Some(TextKind::Date);
+    }
+    let is_number = NUMBER.is_match(value);
+    if is_phone(value) && (value.starts_with('+') || !is_number) {
+        return Some(TextKind::Phone);
+    }
+    if is_number {
+        return Some(TextKind::Number);
+    }
+    if UNIX_PATH.is_match(value) || WINDOWS_PATH.is_match(value) {
+        return Some(TextKind::Path);
+    }
+    None
+}
+
+fn is_date(value: &str) -> bool {
+    let captures = ISO_DATE
+        .captures(value)
+        .or_else(|| SLASH_DATE.captures(value))
+        .or_else(|| CJK_DATE.captures(value));
+    let Some(captures) = captures else {
+        return false;
+    };
+
+    let part = |index: usize| captures[index].parse::<u32>().ok();
+    match (part(1), part(2), part(3)) {
+        (Some(year), Some(month), Some(day)) => {
+            chrono::NaiveDate::from_ymd_opt(year as i32, month, day).is_some()
+        }
+        _ => false,
+    }
+}
+
+/// A phone number needs 7-15 digits (E.164) and either a leading `+` or a
+/// separator; a bare digit run is a number.
+fn is_phone(value: &str) -> bool {
+    if !PHONE.is_match(value) {
+        return false;
+    }
+    let digits = value.chars().filter(char::is_ascii_digit).count();
+    let has_separator = value.chars().any(|ch| " ().-".contains(ch));
+    (7..=15).contains(&digits) && (value.starts_with('+') || has_separator)
+}
+
+fn is_json(value: &str) -> bool {
+    let is_container = (value.starts_with('{') && value.ends_with('}'))
+        || (value.starts_with('[') && value.ends_with(']'));
+    is_container && serde_json::from_str::<serde_json::Value>(value).is_ok()
+}
+
+/// `Some(language)` when `text` looks like source code; the inner `None`
+/// means code whose language did not stand out.
+fn detect_code(text: &str) -> Option<Option<&'static str>> {
+    let (language, score) = CODE_SIGNATURES
+        .iter()
+        .map(|(language, signatures)| {
+            let score = signatures
+                .iter()
+                .filter(|signature| text.contains(*signature))
+                .count();
+            (*language, score)
+        })
+        // `max_by_key` keeps the last of equal maxima; reverse so the first
+        // listed (more specific) language wins ties.
+        .rev()
+        .max_by_key(|(_, score)| *score)?;
+
+    let structure = code_structure_ratio(text);
+    if score >= 3 || (score >= 2 && structure >= 0.3) {
+        return Some(Some(language));
+    }
+    if structure >= 0.6 && text.lines().filter(|line| !line.trim().is_empty()).count() >= 3 {
+        return Some(None);
+    }
+    None
+}
+
+/// Fraction of non-blank lines that look structural: ending in `;`, `{`,
+/// `}`, `)` or `:`, or indented.
+fn code_structure_ratio(text: &str) -> f64 {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return 0.0;
+    }
+
+    let structural = lines
+        .iter()
+        .filter(|line| {
+            let trimmed = line.trim_end();
+            trimmed.ends_with([';', '{', '}', ')', ':'])
+                || line.starts_with("    ")
+                || line.starts_with('\t')
+        })
+        .count();
+    structural as f64 / lines.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(text: &str) -> TextKind {
+        classify_text(text).kind
+    }
+
+    #[test]
+    fn urls_and_emails_must_be_the_whole_clip() {
+        assert_eq!(TextKind::Url, kind("https://example.com/a?b=c#d"));
+        assert_eq!(TextKind::Url, kind("  www.example.org/path\n"));
+        assert_eq!(TextKind::Url, kind("FTP://files.example.com"));
+        assert_eq!(TextKind::Email, kind("first.last+tag@mail.example.co.uk"));
+        assert_eq!(TextKind::Email, kind("mailto:me@example.com"));
+
+        assert_eq!(TextKind::Plain, kind("see https://example.com for details"));
+        assert_eq!(TextKind::Plain, kind("reach me at me@example.com"));
+    }
+
+    #[test]
+    fn phones_need_a_plus_or_separators_and_e164_length() {
+        assert_eq!(TextKind::Phone, kind("+1 (555) 123-4567"));
+        assert_eq!(TextKind::Phone, kind("+8613812345678"));
+        assert_eq!(TextKind::Phone, kind("030 1234 5678"));
+
+        assert_eq!(TextKind::Number, kind("13812345678"));
+        assert_eq!(TextKind::Plain, kind("+1 23"));
+    }
+
+    #[test]
+    fn hex_and_functional_colors() {
+        for color in [
+            "#fff",
+            "#FFAA00",
+            "#ffaa0080",
+            "rgb(255, 128, 0)",
+            "rgba(0,0,0,0.5)",
+            "hsl(210 40% 50%)",
+        ] {
+            assert_eq!(TextKind::Color, kind(color), "{color}");
+        }
+        assert_eq!(TextKind::Plain, kind("#ffff0"));
+        assert_eq!(TextKind::Plain, kind("#issue"));
+    }
+
+    #[test]
+    fn unix_windows_and_unc_paths() {
+        assert_eq!(TextKind::Path, kind("/usr/local/bin/tool"));
+        assert_eq!(TextKind::Path, kind("~/Documents/My Notes.txt"));
+        assert_eq!(TextKind::Path, kind(r"C:\Users\bob\file.txt"));
+        assert_eq!(TextKind::Path, kind(r"\\server\share\dir"));
+
+        assert_eq!(TextKind::Plain, kind("/"));
+        assert_eq!(TextKind::Plain, kind("and/or"));
+    }
+
+    #[test]
+    fn numbers_and_valid_dates() {
+        for number in [
+            "42",
+            "-3.14",
+            "3.14159265",
+            "1,234,567.89",
+            "6.02e23",
+            "15%",
+        ] {
+            assert_eq!(TextKind::Number, kind(number), "{number}");
+        }
+        for date in [
+            "2024-02-29",
+            "2024-03-05T14:30:00Z",
+            "2024-03-05 14:30",
+            "2024/3/5",
+            "2024年3月5日",
+        ] {
+            assert_eq!(TextKind::Date, kind(date), "{date}");
+        }
+        // Date-shaped but not a real calendar date.
+        assert_ne!(TextKind::Date, kind("2023-02-29"));
+        assert_eq!(TextKind::Plain, kind("2024/13/45"));
+    }
+
+    #[test]
+    fn json_objects_and_arrays_must_parse() {
+        assert_eq!(TextKind::Json, kind("{\"a\": [1, 2, {\"b\": null}]}"));
+        assert_eq!(TextKind::Json, kind("[\n  1,\n  2\n]"));
+        assert_eq!(TextKind::Plain, kind("{not json}"));
+        assert_eq!(TextKind::Plain, kind("[TODO] finish the report"));
+    }
+
+    #[test]
+    fn code_is_detected_with_a_language_guess() {
+        let cases = [
+            (
+                "rust",
+                "pub fn main() {\n    let mut v = Vec::new();\n    v.push(1);\n}",
+            ),
+            (
+                "python",
+                "import os\n\ndef walk(root):\n    for entry in os.scandir(root):\n        print(entry)",
+            ),
+            (
+                "javascript",
+                "const total = items.reduce((a, b) => a + b, 0);\nconsole.log(total);\nfunction x() {}",
+            ),
+            (
+                "go",
+                "package main\n\nfunc main() {\n\tx := 1\n\tfmt.Println(x)\n}",
+            ),
+            (
+                "sql",
+                "SELECT id, name\nFROM users u\nJOIN orders o ON o.user_id = u.id\nWHERE o.total > 10;",
+            ),
+        ];
+        for (language, source) in cases {
+            assert_eq!(
+                Classification {
+                    kind: TextKind::Code,
+                    code_language: Some(language),
+                },
+                classify_text(source),
+                "{language}"
+            );
+        }
+    }
+
+    #[test]
+    fn structured_code_without_a_clear_language_is_still_code() {
+        let source = "if (ready) {\n    start();\n}\nelse {\n    wait();\n}";
+        assert_eq!(
+            Classification {
+                kind: TextKind::Code,
+                code_language: None,
+            },
+            classify_text(source)
+        );
+    }
+
+    #[test]
+    fn prose_stays_plain_even_with_stray_keywords() {
+        for prose in [
+            "Let me know if the import from the old system worked.",
+            "今天天气不错，我们去公园散步吧。",
+            "Meeting notes:\nWe will select a vendor from the shortlist.\nBudget is fine.",
+            "",
+            "   \n  ",
+        ] {
+            assert_eq!(TextKind::Plain, kind(prose), "{prose:?}");
+        }
+    }
+
+    #[test]
+    fn db_values_round_trip() {
+        for kind in [
+            TextKind::Url,
+            TextKind::Email,
+            TextKind::Phone,
+            TextKind::Color,
+            TextKind::Path,
+            TextKind::Json,
+            TextKind::Code,
+            TextKind::Number,
+            TextKind::Date,
+            TextKind::Plain,
+        ] {
+            assert_eq!(Some(kind), TextKind::from_db_value(kind.as_db_value()));
+        }
+        assert_eq!(None, TextKind::from_db_value("unknown"));
+    }
+}
@@ -0,0 +1,431 @@
+//! Raw, platform-native clipboard format access for full-fidelity capture.
+//!
+//! arboard only speaks text, html, images and file lists — exactly what the
+//! single representative format of D1 needs. Full-fidelity mode instead keeps
+//! every representation the source offered (a spreadsheet's cell image, an
+//! editor's RTF, private app types), so this module reads and writes them by
+//! the platform's own format identifiers.
+//!
+//! Linux has no implementation: X11/Wayland selections are served lazily by
+//! their owner through a conversion protocol arboard keeps private, so full
+//! fidelity degrades to the D1 representative format there.
+
+use crate::storage::ClipFormat;
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GlobalAlloc(uflags: u32, dwbytes: usize) -> windows::Win32::Foundation::HANDLE;
+    fn GlobalFree(hmem: windows::Win32::Foundation::HANDLE) -> windows::Win32::Foundation::HANDLE;
+    fn GlobalLock(hmem: windows::Win32::Foundation::HANDLE) -> *mut std::ffi::c_void;
+    fn GlobalSize(hmem: windows::Win32::Foundation::HANDLE) -> usize;
+    fn GlobalUnlock(hmem: windows::Win32::Foundation::HANDLE) -> i32;
+}
+
+/// Whether this platform can both capture and restore a multi-format
+/// snapshot. Callers fall back to the representative format when not.
+pub fn is_supported() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+}
+
+/// Every format currently on the clipboard, in the order the owner offered
+/// them (richest first by platform convention). Formats with no byte
+/// payload are left out.
+#[cfg(target_os = "macos")]
+pub fn read_all_formats() -> Vec<ClipFormat> {
+    use objc2_app_kit::NSPasteboard;
+
+    let pasteboard = NSPasteboard::generalPasteboard();
+    let Some(types) = pasteboard.types() else {
+        return Vec::new();
+    };
+
+    types
+        .iter()
+        .filter_map(|pasteboard_type| {
+            let data = pasteboard.dataForType(&pasteboard_type)?;
+            Some(ClipFormat {
+                format: pasteboard_type.to_string(),
+                data: data.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Replace the clipboard with exactly `formats`, written as one pasteboard
+/// item so a target app sees them as alternative views of the same content.
+#[cfg(target_os = "macos")]
+pub fn write_all_formats(formats: &[ClipFormat]) -> Result<(), String> {
+    use objc2::rc::Retained;
+    use objc2::runtime::ProtocolObject;
+    use objc2_app_kit::{NSPasteboard, NSPasteboardItem, NSPasteboardWriting};
+    use objc2_foundation::{NSArray, NSData, NSString};
+
+    if formats.is_empty() {
+        return Err("No clipboard formats to write".to_string());
+    }
+
+    let item = NSPasteboardItem::new();
+    for format in formats {
+        let data = NSData::with_bytes(&format.data);
+        if !item.setData_forType(&data, &NSString::from_str(&format.format)) {
+            log::warn!("Pasteboard item rejected format {}", format.format);
+        }
+    }
+
+    let writer: Retained<ProtocolObject<dyn NSPasteboardWriting>> =
+        ProtocolObject::from_retained(item);
+    let objects = NSArray::from_retained_slice(&[writer]);
+    let pasteboard = NSPasteboard::generalPasteboard();
+    pasteboard.clearContents();
+    if !pasteboard.writeObjects(&objects) {
+        return Err("NSPasteboard writeObjects returned false".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_all_formats() -> Vec<ClipFormat> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EnumClipboardFormats, OpenClipboard,
+    };
+
+    unsafe {
+        if let Err(e) = OpenClipboard(HWND(std::ptr::null_mut())) {
+            log::warn!("Failed to open clipboard for full-fidelity capture: {}", e);
+            return Vec::new();
+        }
+
+        let mut formats = Vec::new();
+        let mut format = EnumClipboardFormats(0);
+        while format != 0 {
+            if windows_format_is_memory_backed(format) {
+                if let Some(data) = windows_clipboard_bytes(format) {
+                    formats.push(ClipFormat {
+                        format: windows_format_name(format),
+                        data,
+                    });
+                }
+            }
+            format = EnumClipboardFormats(format);
+        }
+
+        if let Err(e) = CloseClipboard() {
+            log::warn!(
+                "Failed to close clipboard after full-fidelity capture: {}",
+                e
+            );
+        }
+
+        formats
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn write_all_formats(formats: &[ClipFormat]) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::DataExchange::{CloseClipboard, EmptyClipboard, OpenClipboard};
+
+    if formats.is_empty() {
+        return Err("No clipboard formats to write".to_string());
+    }
+
+    unsafe {
+        OpenClipboard(HWND(std::ptr::null_mut()))
+            .map_err(|e| format!("Failed to open clipboard: {e}"))?;
+
+        let result = EmptyClipboard()
+            .map_err(|e| format!("Failed to empty clipboard: {e}"))
+            .map(|()| {
+                for format in formats {
+                    if let Err(e) = windows_set_clipboard_bytes(format) {
+                        log::warn!("Skipping clipboard format {}: {}", format.format, e);
+                    }
+                }
+            });
+
+        if let Err(e) = CloseClipboard() {
+            log::warn!("Failed to close clipboard after full-fidelity write: {}", e);
+        }
+
+        result
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn read_all_formats() -> Vec<ClipFormat> {
+    Vec::new()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn write_all_formats(_formats: &[ClipFormat]) -> Result<(), String> {
+    Err("Full-fidelity clipboard restore is not supported on this platform".to_string())
+}
+
+/// The RTF flavour on the clipboard, if the owner offered one. arboard has
+/// no RTF accessor, so this reads the platform's RTF type directly.
+#[cfg(target_os = "macos")]
+pub fn read_rtf() -> Option<String> {
+    use objc2_app_kit::NSPasteboard;
+    use objc2_foundation::NSString;
+
+    let data =
+        NSPasteboard::generalPasteboard().dataForType(&NSString::from_str(MACOS_RTF_TYPE))?;
+    crate::rtf::rtf_from_bytes(&data.to_vec())
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_rtf() -> Option<String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::DataExchange::{CloseClipboard, OpenClipboard};
+
+    let format = windows_format_id(WINDOWS_RTF_FORMAT)?;
+    unsafe {
+        if let Err(e) = OpenClipboard(HWND(std::ptr::null_mut())) {
+            log::warn!("Failed to open clipboard for RTF capture: {}", e);
+            return None;
+        }
+        let bytes = windows_clipboard_bytes(format);
+        if let Err(e) = CloseClipboard() {
+            log::warn!("Failed to close clipboard after RTF capture: {}", e);
+        }
+        crate::rtf::rtf_from_bytes(&bytes?)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn read_rtf() -> Option<String> {
+    None
+}
+
+/// Replace the clipboard with a text clip's rich and plain representations,
+/// richest first, so native apps pick RTF and everything else falls back to
+/// html or plain text.
+pub fn write_text_formats(text: &str, html: Option<&str>, rtf: &str) -> Result<(), String> {
+    write_all_formats(&native_text_formats(text, html, rtf))
+}
+
+#[cfg(target_os = "macos")]
+const MACOS_RTF_TYPE: &str = "public.rtf";
+
+#[cfg(target_os = "macos")]
+fn native_text_formats(text: &str, html: Option<&str>, rtf: &str) -> Vec<ClipFormat> {
+    let mut formats = vec![ClipFormat {
+        format: MACOS_RTF_TYPE.to_string(),
+        data: rtf.as_bytes().to_vec(),
+    }];
+    if let Some(html) = html {
+        formats.push(ClipFormat {
+            format: "public.html".to_string(),
+            data: html.as_bytes().to_vec(),
+        });
+    }
+    formats.push(ClipFormat {
+        format: "public.utf8-plain-text".to_string(),
+        data: text.as_bytes().to_vec(),
+    });
+    formats
+}
+
+#[cfg(target_os = "windows")]
+const WINDOWS_RTF_FORMAT: &str = "Rich Text Format";
+
+/// Windows clipboard text formats are NUL-terminated; CF_UNICODETEXT (13) is
+/// UTF-16LE.
+#[cfg(target_os = "windows")]
+fn native_text_formats(text: &str, html: Option<&str>, rtf: &str) -> Vec<ClipFormat> {
+    let nul_terminated = |bytes: &[u8]| {
+        let mut data = bytes.to_vec();
+        data.push(0);
+        data
+    };
+
+    let mut formats = vec![ClipFormat {
+        format: WINDOWS_RTF_FORMAT.to_string(),
+        data: nul_terminated(rtf.as_bytes()),
+    }];
+    if let Some(html) = html {
+        formats.push(ClipFormat {
+            format: "HTML Format".to_string(),
+            data: nul_terminated(cf_html(html).as_bytes()),
+        });
+    }
+    formats.push(ClipFormat {
+        format: "#13".to_string(),
+        data: text
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .flat_map(u16::to_le_bytes)
+            .collect(),
+    });
+    formats
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn native_text_formats(_text: &str, _html: Option<&str>, _rtf: &str) -> Vec<ClipFormat> {
+    Vec::new()
+}
+
+/// Wrap an html fragment in the CF_HTML envelope Windows apps expect: a
+/// header of byte offsets locating the document and the fragment within it.
+#[cfg(any(target_os = "windows", test))]
+fn cf_html(fragment: &str) -> String {
+    const HEADER_TEMPLATE_LEN: usize = "Version:0.9\r\n\
+        StartHTML:0000000000\r\n\
+        EndHTML:0000000000\r\n\
+        StartFragment:0000000000\r\n\
+        EndFragment:0000000000\r\n"
+        .len();
+    const PREFIX: &str = "<html><body>\r\n<!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment-->\r\n</body></html>";
+
+    let start_html = HEADER_TEMPLATE_LEN;
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + SUFFIX.len();
+
+    format!(
+        "Version:0.9\r\n\
+         StartHTML:{start_html:010}\r\n\
+         EndHTML:{end_html:010}\r\n\
+         StartFragment:{start_fragment:010}\r\n\
+         EndFragment:{end_fragment:010}\r\n\
+         {PREFIX}{fragment}{SUFFIX}"
+    )
+}
+
+/// Standard formats whose clipboard "data" is a GDI/owner handle rather than
+/// a movable memory block, plus the private/GDI-object ranges. None of them
+/// can be copied out as bytes and replayed later.
+#[cfg(target_os = "windows")]
+fn windows_format_is_memory_backed(format: u32) -> bool {
+    const CF_BITMAP: u32 = 2;
+    const CF_METAFILEPICT: u32 = 3;
+    const CF_PALETTE: u32 = 9;
+    const CF_ENHMETAFILE: u32 = 14;
+    const CF_OWNERDISPLAY: u32 = 0x0080;
+    const CF_DSPBITMAP: u32 = 0x0082;
+    const CF_DSPMETAFILEPICT: u32 = 0x0083;
+    const CF_DSPENHMETAFILE: u32 = 0x008E;
+
+    !matches!(
+        format,
+        CF_BITMAP
+            | CF_METAFILEPICT
+            | CF_PALETTE
+            | CF_ENHMETAFILE
+            | CF_OWNERDISPLAY
+            | CF_DSPBITMAP
+            | CF_DSPMETAFILEPICT
+            | CF_DSPENHMETAFILE
+            | 0x0200..=0x03FF
+    )
+}
+
+/// Registered formats round-trip by name (their numeric id differs per
+/// session); predefined formats have no name and are stored as `#<id>`.
+#[cfg(target_os = "windows")]
+fn windows_format_name(format: u32) -> String {
+    use windows::Win32::System::DataExchange::GetClipboardFormatNameW;
+
+    let mut buffer = [0u16; 256];
+    let len = unsafe { GetClipboardFormatNameW(format, &mut buffer) };
+    if len > 0 {
+        String::from_utf16_lossy(&buffer[..len as usize])
+    } else {
+        format!("#{format}")
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn windows_format_id(name: &str) -> Option<u32> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::DataExchange::RegisterClipboardFormatW;
+
+    if let Some(id) = name.strip_prefix('#') {
+        return id.parse().ok();
+    }
+
+    let wide_name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    let id = unsafe { RegisterClipboardFormatW(PCWSTR(wide_name.as_ptr())) };
+    (id != 0).then_some(id)
+}
+
+/// Caller must hold the clipboard open.
+#[cfg(target_os = "windows")]
+unsafe fn windows_clipboard_bytes(format: u32) -> Option<Vec<u8>> {
+    use windows::Win32::System::DataExchange::GetClipboardData;
+
+    let handle = GetClipboardData(format).ok()?;
+    let size = GlobalSize(handle);
+    if size == 0 {
+        return None;
+    }
+
+    let data = GlobalLock(handle);
+    if data.is_null() {
+        return None;
+    }
+    let bytes = std::slice::from_raw_parts(data.cast::<u8>(), size).to_vec();
+    let _ = GlobalUnlock(handle);
+    Some(bytes)
+}
+
+/// Caller must hold the clipboard open and have emptied it. On success the
+/// system owns the allocation; on failure it is freed here.
+#[cfg(target_os = "windows")]
+unsafe fn windows_set_clipboard_bytes(format: &ClipFormat) -> Result<(), String> {
+    use windows::Win32::System::DataExchange::SetClipboardData;
+
+    const GMEM_MOVEABLE: u32 = 0x0002;
+
+    let id = windows_format_id(&format.format).ok_or("unknown clipboard format")?;
+    let handle = GlobalAlloc(GMEM_MOVEABLE, format.data.len().max(1));
+    if handle.is_invalid() {
+        return Err("GlobalAlloc failed".to_string());
+    }
+
+    let data = GlobalLock(handle);
+    if data.is_null() {
+        GlobalFree(handle);
+        return Err("GlobalLock failed".to_string());
+    }
+    std::ptr::copy_nonoverlapping(format.data.as_ptr(), data.cast::<u8>(), format.data.len());
+    let _ = GlobalUnlock(handle);
+
+    if let Err(e) = SetClipboardData(id, handle) {
+        GlobalFree(handle);
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_offset(envelope: &str, key: &str) -> usize {
+        envelope
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|value| value.parse().ok())
+            .unwrap()
+    }
+
+    #[test]
+    fn cf_html_offsets_locate_the_document_and_the_exact_fragment() {
+        let fragment = "<b>caf\u{e9}</b> & more";
+        let envelope = cf_html(fragment);
+
+        let start_fragment = header_offset(&envelope, "StartFragment");
+        let end_fragment = header_offset(&envelope, "EndFragment");
+        assert_eq!(fragment, &envelope[start_fragment..end_fragment]);
+
+        let start_html = header_offset(&envelope, "StartHTML");
+        let end_html = header_offset(&envelope, "EndHTML");
+        assert!(envelope[start_html..].starts_with("<html>"));
+        assert_eq!(envelope.len(), end_html);
+    }
+}
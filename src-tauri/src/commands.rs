@@ -2,12 +2,18 @@
 use std::sync::{mpsc, Arc, Mutex};
 use std::time::Duration;
 
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
 use tauri_plugin_notification::NotificationExt;
 
+use crate::api::{ApiHost, ApiServer};
+use crate::classify::TextKind;
+use crate::plugins::{PluginInfo, PluginSet, PLUGINS_DIR_NAME};
 use crate::settings::Settings;
-use crate::storage::{ClipStorage, ContentType, FrontendClipItem};
+use crate::shortcuts::{ActionShortcut, ShortcutAction};
+use crate::storage::{
+    ClipStatistics, ClipStorage, ContentType, FrontendClipItem, CLIP_EVENT_RETENTION_DAYS,
+};
 use crate::tray::update_tray_menu;
 use crate::{migration, safe_lock, AppState};
 
@@ -80,6 +86,120 @@ fn restart_clipboard_monitor(app: &AppHandle, state: &AppState) -> Result<(), St
     }
 }
 
+/// Sent with the ids of clips deleted outside the QuickBar (the automation
+/// API, hooks), which drops them like expired clips.
+pub const CLIPS_DELETED_EVENT: &str = "clips-deleted";
+
+/// Sent with the new preview of a clip whose content changed in place (a
+/// hook rewrote it); the clip keeps its id and position.
+pub const CLIP_UPDATED_EVENT: &str = "clip-updated";
+
+/// Tells the QuickBar and the tray about changes made through the automation
+/// API.
+struct AppApiHost {
+    app: AppHandle,
+}
+
+impl ApiHost for AppApiHost {
+    fn max_text_bytes(&self) -> usize {
+        self.app.state::<AppState>().settings.get().max_text_bytes
+    }
+
+    fn capture_text(&self, text: String) -> Option<FrontendClipItem> {
+        crate::clipboard::ClipboardMonitor::capture_text(&self.app, &text)
+    }
+
+    fn clip_changed(&self, item: &FrontendClipItem) {
+        if let Err(e) = self.app.emit("clipboard-changed", item) {
+            log::error!("Failed to emit clipboard-changed event: {}", e);
+        }
+        update_tray_menu(&self.app);
+    }
+
+    fn clip_deleted(&self, id: &str) {
+        if let Err(e) = self.app.emit(CLIPS_DELETED_EVENT, [id]) {
+            log::error!("Failed to emit {} event: {}", CLIPS_DELETED_EVENT, e);
+        }
+        update_tray_menu(&self.app);
+    }
+}
+
+/// Feeds every `clipboard-changed` event, whoever emits it, to the automation
+/// API's `/events` streams, then starts the API if it is enabled. Called once
+/// at startup.
+pub fn init_api_server(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let events = app.state::<AppState>().api_events.clone();
+    app.listen_any("clipboard-changed", move |event| {
+        events.publish(event.payload())
+    });
+    apply_api_settings(app, settings)
+}
+
+/// Starts, restarts or stops the automation API to match `settings`.
+fn apply_api_settings(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut server = safe_lock(&state.api_server);
+    // Stop the old server first: the new one may want the same port.
+    *server = None;
+    if settings.api_enabled {
+        *server = Some(ApiServer::start(
+            settings.api_port,
+            settings.api_token.clone(),
+            state.storage.clone(),
+            state.api_events.clone(),
+            Arc::new(AppApiHost { app: app.clone() }),
+        )?);
+    }
+    Ok(())
+}
+
+/// Carries D-Bus calls (Linux) over to the app.
+#[cfg(target_os = "linux")]
+struct AppDbusHost {
+    app: AppHandle,
+}
+
+#[cfg(target_os = "linux")]
+impl crate::dbus::DbusHost for AppDbusHost {
+    fn paste(&self, id: String) {
+        let app = self.app.clone();
+        tauri::async_runtime::spawn(async move {
+            match paste_into_focused_window(&app, id, false).await {
+                Ok(outcome) => log::info!("D-Bus paste: {:?}", outcome),
+                Err(e) => log::warn!("D-Bus paste failed: {}", e),
+            }
+        });
+    }
+
+    fn toggle_pause(&self) -> bool {
+        crate::pause::toggle(&self.app);
+        crate::pause::capture_is_paused(&self.app)
+    }
+
+    fn show_quickbar(&self) {
+        show_quickbar_view(
+            &self.app,
+            crate::window::QuickBarView {
+                panel: crate::window::QuickBarPanel::Recent,
+                query: None,
+                group: None,
+            },
+        );
+    }
+}
+
+/// Registers the D-Bus service on the session bus and manages it as app
+/// state, where `save_to_storage` finds it to signal new clips.
+#[cfg(target_os = "linux")]
+pub fn start_dbus_service(app: &AppHandle) -> Result<(), String> {
+    let service = crate::dbus::DbusService::start(
+        app.state::<AppState>().storage.clone(),
+        Arc::new(AppDbusHost { app: app.clone() }),
+    )?;
+    app.manage(service);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_recent_clips(
     state: State<'_, AppState>,
@@ -137,10 +257,29 @@ pub async fn get_clip(
 pub async fn search_clips(
     state: State<'_, AppState>,
     query: String,
+    kind: Option<TextKind>,
+) -> Result<Vec<FrontendClipItem>, String> {
+    with_storage(state.storage.clone(), move |storage| {
+        let items = storage
+            .search_clip_previews_of_kind(&query, kind)
+            .map_err(|e| e.to_string())?;
+        Ok(items
+            .into_iter()
+            .map(FrontendClipItem::from_preview)
+            .collect())
+    })
+    .await
+}
+
+/// Every clip in capture-rule group `group`, for the open-group shortcut.
+#[tauri::command]
+pub async fn get_group_clips(
+    state: State<'_, AppState>,
+    group: String,
 ) -> Result<Vec<FrontendClipItem>, String> {
     with_storage(state.storage.clone(), move |storage| {
         let items = storage
-            .search_clip_previews(&query)
+            .get_group_clip_previews(&group)
             .map_err(|e| e.to_string())?;
         Ok(items
             .into_iter()
@@ -150,6 +289,50 @@ pub async fn search_clips(
     .await
 }
 
+/// Image clips that look like clip `id` (within the configured perceptual
+/// hash threshold), nearest first, for grouping near-duplicate screenshots.
+#[tauri::command]
+pub async fn get_similar_images(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<FrontendClipItem>, String> {
+    let max_distance = state.settings.get().similar_image_threshold;
+    with_storage(state.storage.clone(), move |storage| {
+        let items = storage
+            .get_similar_image_previews(&id, max_distance)
+            .map_err(|e| e.to_string())?;
+        Ok(items
+            .into_iter()
+            .map(FrontendClipItem::from_preview)
+            .collect())
+    })
+    .await
+}
+
+/// Default window for `get_statistics`, in days.
+const DEFAULT_STATISTICS_DAYS: i64 = 30;
+/// How many source apps and pasted clips `get_statistics` ranks.
+const STATISTICS_TOP_LIMIT: usize = 10;
+
+/// Capture, paste and skip counts over the last `days` days (at most the
+/// event retention window), plus what the stored history takes on disk.
+#[tauri::command]
+pub async fn get_statistics(
+    state: State<'_, AppState>,
+    days: Option<i64>,
+) -> Result<ClipStatistics, String> {
+    let days = days
+        .unwrap_or(DEFAULT_STATISTICS_DAYS)
+        .clamp(1, CLIP_EVENT_RETENTION_DAYS);
+    let since = chrono::Utc::now().timestamp() - days * 24 * 60 * 60;
+    with_storage(state.storage.clone(), move |storage| {
+        storage
+            .statistics(since, STATISTICS_TOP_LIMIT)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
 #[tauri::command]
 pub async fn toggle_pin(
     app: AppHandle,
@@ -300,15 +483,17 @@ pub async fn paste_clip(
     id: String,
     mode: String,
     plain: Option<bool>,
-) -> Result<(), String> {
+) -> Result<crate::paste::PasteOutcome, String> {
     // `plain` is optional so a not-yet-upgraded frontend (no ⌥Enter) keeps
     // working: absent => rich paste, identical to previous behavior.
     crate::paste::paste_clip(app, state.inner(), id, mode, plain.unwrap_or(false)).await
 }
 
-/// Merge several clips (in `ids` order) into a single `separator`-joined text
-/// write, then paste per `mode` (task #13 multi-select). Image clips are skipped
-/// (v1). The frontend only sends `"\n"` today; the separator is honored verbatim.
+/// Merge several clips (in `ids` order) into a single write, then paste per
+/// `mode` (task #13 multi-select). Text is laid out per `format`, falling back
+/// to the `merge_format` setting; `separator` joins plain and template merges
+/// and is honored verbatim. Image clips are skipped unless `merge_images`
+/// stacks an all-image selection.
 #[tauri::command]
 pub async fn paste_clips(
     app: AppHandle,
@@ -316,8 +501,47 @@ pub async fn paste_clips(
     ids: Vec<String>,
     mode: String,
     separator: String,
-) -> Result<(), String> {
-    crate::paste::paste_clips(app, state.inner(), ids, mode, separator).await
+    format: Option<crate::merge::MergeFormat>,
+) -> Result<crate::paste::PasteOutcome, String> {
+    crate::paste::paste_clips(app, state.inner(), ids, mode, separator, format).await
+}
+
+/// Queue clips (in `ids` order) for the paste-stack shortcut. Returns how
+/// many are queued now.
+#[tauri::command]
+pub async fn push_paste_stack(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<usize, String> {
+    Ok(state.paste_stack.push(&app, ids))
+}
+
+#[tauri::command]
+pub async fn clear_paste_stack(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    state.paste_stack.clear(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_paste_stack_count(state: State<'_, AppState>) -> Result<usize, String> {
+    Ok(state.paste_stack.count())
+}
+
+/// Whether auto-paste can work in this session, and through which input path.
+#[tauri::command]
+pub async fn get_paste_support() -> Result<crate::paste::PasteSupport, String> {
+    // Probing on Wayland makes a blocking round-trip to the compositor.
+    tauri::async_runtime::spawn_blocking(crate::paste::paste_support)
+        .await
+        .map_err(|e| format!("Paste support probe failed: {e}"))
+}
+
+/// Whether this platform can capture and restore every clipboard format, so
+/// the settings page can disable `full_fidelity_capture` where it does nothing.
+#[tauri::command]
+pub async fn get_full_fidelity_support() -> Result<bool, String> {
+    Ok(crate::clipboard_formats::is_supported())
 }
 
 pub fn register_quickbar_shortcut(
@@ -374,6 +598,179 @@ where
     ))
 }
 
+/// Registers every action shortcut, or none: on a failure the ones already
+/// registered are dropped again.
+pub fn register_action_shortcuts(
+    app: &AppHandle,
+    bindings: &[ActionShortcut],
+) -> Result<(), String> {
+    for (registered, binding) in bindings.iter().enumerate() {
+        if let Err(e) = register_action_shortcut(app, binding) {
+            unregister_action_shortcuts(app, &bindings[..registered]);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+fn unregister_action_shortcuts(app: &AppHandle, bindings: &[ActionShortcut]) {
+    for binding in bindings {
+        unregister_shortcut_if_active(app, &binding.shortcut, "action");
+    }
+}
+
+/// Puts the previous action shortcuts back after a failed settings update.
+/// Only logs, like `restore_shortcut`.
+fn restore_action_shortcuts(app: &AppHandle, bindings: &[ActionShortcut]) {
+    if let Err(e) = register_action_shortcuts(app, bindings) {
+        log::warn!("Failed to restore action shortcuts: {}", e);
+    }
+}
+
+fn register_action_shortcut(app: &AppHandle, binding: &ActionShortcut) -> Result<(), String> {
+    let action = binding.action.clone();
+    let shortcut_display = binding.shortcut.clone();
+
+    app.global_shortcut()
+        .on_shortcut(binding.shortcut.as_str(), move |app, _shortcut, event| {
+            // Pastes wait for the key to come up: pressing the paste keys while
+            // this shortcut's own keys are still down would send another chord.
+            let fires = if action.pastes() {
+                matches!(event.state, ShortcutState::Released)
+            } else {
+                matches!(event.state, ShortcutState::Pressed)
+            };
+            if fires {
+                log::info!("Action shortcut triggered: {}", shortcut_display);
+                run_shortcut_action(app, &action);
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut '{}': {}", binding.shortcut, e))
+}
+
+fn run_shortcut_action(app: &AppHandle, action: &ShortcutAction) {
+    match action {
+        ShortcutAction::PasteRecent { index } => {
+            spawn_shortcut_paste(app, ShortcutPasteSource::Recent(*index), false)
+        }
+        ShortcutAction::PastePinnedSlot { slot } => {
+            spawn_shortcut_paste(app, ShortcutPasteSource::PinnedSlot(*slot), false)
+        }
+        ShortcutAction::PastePlainText => {
+            spawn_shortcut_paste(app, ShortcutPasteSource::Recent(1), true)
+        }
+        ShortcutAction::PasteStackNext => {
+            spawn_shortcut_paste(app, ShortcutPasteSource::Stack, false)
+        }
+        ShortcutAction::TogglePause => crate::pause::toggle(app),
+        ShortcutAction::OpenSearch { query } => show_quickbar_view(
+            app,
+            crate::window::QuickBarView {
+                panel: crate::window::QuickBarPanel::Recent,
+                query: Some(query.clone()).filter(|query| !query.is_empty()),
+                group: None,
+            },
+        ),
+        ShortcutAction::OpenGroup { group } => show_quickbar_view(
+            app,
+            crate::window::QuickBarView {
+                panel: crate::window::QuickBarPanel::Recent,
+                query: None,
+                group: Some(group.clone()),
+            },
+        ),
+    }
+}
+
+fn show_quickbar_view(app: &AppHandle, view: crate::window::QuickBarView) {
+    let app_for_action = app.clone();
+    let result = run_action_on_main_thread(
+        app,
+        "show_quickbar_from_action_shortcut",
+        Box::new(move || {
+            let foreground_store = app_for_action
+                .state::<AppState>()
+                .quickbar_foreground_window
+                .clone();
+            crate::window::show_quickbar_with_view(&app_for_action, &foreground_store, view)
+        }),
+    );
+    if let Err(e) = result {
+        log::error!("Failed to show QuickBar: {}", e);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ShortcutPasteSource {
+    /// The nth clip (from 1) of the recent list.
+    Recent(usize),
+    /// The clip in the nth pinned slot (from 1).
+    PinnedSlot(usize),
+    Stack,
+}
+
+fn spawn_shortcut_paste(app: &AppHandle, source: ShortcutPasteSource, plain: bool) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = paste_from_shortcut(&app, source, plain).await {
+            log::warn!("Shortcut paste from {:?} failed: {}", source, e);
+        }
+    });
+}
+
+async fn paste_from_shortcut(
+    app: &AppHandle,
+    source: ShortcutPasteSource,
+    plain: bool,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let id = match source {
+        ShortcutPasteSource::Recent(index) => {
+            with_storage(state.storage.clone(), move |storage| {
+                storage
+                    .get_recent_clip_previews(index)
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .nth(index - 1)
+                    .map(|preview| preview.id)
+                    .ok_or_else(|| format!("There is no recent clip #{}", index))
+            })
+            .await?
+        }
+        ShortcutPasteSource::PinnedSlot(slot) => {
+            // A slot emptied by deleting or unpinning its clip is not an error
+            // worth more than a log line; the shortcut just does nothing.
+            with_storage(state.storage.clone(), move |storage| {
+                storage
+                    .pinned_slot_clip_id(slot)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Pinned slot {} is empty", slot))
+            })
+            .await?
+        }
+        ShortcutPasteSource::Stack => state
+            .paste_stack
+            .pop_next(app)
+            .ok_or_else(|| "The paste stack is empty".to_string())?,
+    };
+
+    let outcome = paste_into_focused_window(app, id, plain).await?;
+    log::info!("Shortcut paste from {:?}: {:?}", source, outcome);
+    Ok(())
+}
+
+/// Paste without a QuickBar opened over the target: the paste goes to
+/// whatever has focus now.
+async fn paste_into_focused_window(
+    app: &AppHandle,
+    id: String,
+    plain: bool,
+) -> Result<crate::paste::PasteOutcome, String> {
+    let state = app.state::<AppState>();
+    crate::window::record_foreground_window(app, &state.quickbar_foreground_window)?;
+    crate::paste::paste_clip(app.clone(), state.inner(), id, "paste".to_string(), plain).await
+}
+
 #[tauri::command]
 pub async fn set_clip_label(
     app: AppHandle,
@@ -638,18 +1035,31 @@ pub async fn update_settings(
 
     let old_settings = state.settings.get();
 
-    // Two fields are owned by other subsystems, not the settings page, so a
+    // These fields are owned by other subsystems, not the settings page, so a
     // stale/reset settings object must never write over them here:
     //   * `custom_data_path` is owned exclusively by `migrate_data_location`,
     //     which relocates the database alongside changing the path. A settings
     //     save (or a reset payload sending `null`) that repointed it here would
     //     silently switch the app to a different/empty directory and strand the
     //     existing database. Keep whatever migration last set.
-    //   * `capture_paused` is owned exclusively by the tray "Pause Capture"
-    //     toggle. A stale settings window saving would otherwise clobber the
-    //     tray's current pause state.
+    //   * `capture_paused` and `paused_until` are owned exclusively by the
+    //     tray's pause actions (`pause`). A stale settings window saving would
+    //     otherwise clobber the tray's current pause state. Toggling
+    //     `persist_timed_pause` is the one settings-page input: it decides
+    //     whether the running timed pause is written out at all.
+    //   * `quickbar_last_position` is written by `window` as the QuickBar
+    //     closes; the settings window never has a fresher value.
     settings.custom_data_path = old_settings.custom_data_path.clone();
+    settings.quickbar_last_position = old_settings.quickbar_last_position;
     settings.capture_paused = old_settings.capture_paused;
+    settings.paused_until = settings
+        .persist_timed_pause
+        .then(|| state.capture_pause.deadline())
+        .flatten();
+    let plugins_changed = old_settings.plugins_enabled != settings.plugins_enabled;
+    let api_changed = old_settings.api_enabled != settings.api_enabled
+        || old_settings.api_port != settings.api_port
+        || old_settings.api_token != settings.api_token;
     let old_shortcut = old_settings.global_shortcut;
     let old_pinned_shortcut = old_settings.pinned_shortcut;
     let old_tray_text_length = old_settings.tray_text_length;
@@ -659,9 +1069,12 @@ pub async fn update_settings(
     let old_locale = old_settings.locale;
     let new_shortcut = settings.global_shortcut.clone();
     let new_pinned_shortcut = settings.pinned_shortcut.clone();
+    let old_action_shortcuts = old_settings.action_shortcuts;
+    let new_action_shortcuts = settings.action_shortcuts.clone();
 
     let shortcut_changed = old_shortcut != new_shortcut;
     let pinned_shortcut_changed = old_pinned_shortcut != new_pinned_shortcut;
+    let action_shortcuts_changed = old_action_shortcuts != new_action_shortcuts;
     let tray_text_changed = old_tray_text_length != settings.tray_text_length;
     let tray_limits_changed = old_max_pinned_in_tray != settings.max_pinned_in_tray
         || old_max_recent_in_tray != settings.max_recent_in_tray;
@@ -686,6 +1099,12 @@ pub async fn update_settings(
         );
     }
 
+    // Old action shortcuts go first, so their keys are free for a new main
+    // or pinned shortcut as well as for the new action shortcuts.
+    if action_shortcuts_changed {
+        unregister_action_shortcuts(&app, &old_action_shortcuts);
+    }
+
     if shortcut_changed || pinned_shortcut_changed {
         if let Err(e) = apply_shortcut_changes(
             &app,
@@ -695,6 +1114,9 @@ pub async fn update_settings(
             new_shortcut.as_str(),
             new_pinned_shortcut.as_deref(),
         ) {
+            if action_shortcuts_changed {
+                restore_action_shortcuts(&app, &old_action_shortcuts);
+            }
             if autostart_changed {
                 if let Err(rollback_error) = apply_autostart_setting(&app, old_autostart) {
                     log::warn!(
@@ -707,7 +1129,40 @@ pub async fn update_settings(
         }
     }
 
+    if action_shortcuts_changed {
+        if let Err(e) = register_action_shortcuts(&app, &new_action_shortcuts) {
+            if shortcut_changed || pinned_shortcut_changed {
+                if let Err(rollback_error) = apply_shortcut_changes(
+                    &app,
+                    quickbar_foreground_window.clone(),
+                    new_shortcut.as_str(),
+                    new_pinned_shortcut.as_deref(),
+                    old_shortcut.as_str(),
+                    old_pinned_shortcut.as_deref(),
+                ) {
+                    log::warn!(
+                        "Failed to roll back shortcuts after action shortcut update failed: {}",
+                        rollback_error
+                    );
+                }
+            }
+            restore_action_shortcuts(&app, &old_action_shortcuts);
+            if autostart_changed {
+                if let Err(rollback_error) = apply_autostart_setting(&app, old_autostart) {
+                    log::warn!(
+                        "Failed to roll back autostart after action shortcut update failed: {}",
+                        rollback_error
+                    );
+                }
+            }
+            return Err(e);
+        }
+    }
+
     if let Err(e) = state.settings.save_candidate(&app, &settings) {
+        if action_shortcuts_changed {
+            unregister_action_shortcuts(&app, &new_action_shortcuts);
+        }
         if shortcut_changed || pinned_shortcut_changed {
             if let Err(rollback_error) = apply_shortcut_changes(
                 &app,
@@ -723,6 +1178,9 @@ pub async fn update_settings(
                 );
             }
         }
+        if action_shortcuts_changed {
+            restore_action_shortcuts(&app, &old_action_shortcuts);
+        }
         if autostart_changed {
             if let Err(rollback_error) = apply_autostart_setting(&app, old_autostart) {
                 log::warn!(
@@ -742,21 +1200,43 @@ pub async fn update_settings(
         update_tray_menu(&app);
     }
 
+    if plugins_changed {
+        reload_plugin_set(&app)
+            .map_err(|e| format!("Settings were saved, but plugins could not load: {}", e))?;
+    }
+
+    // Last, as nothing needs rolling back for it: a port already in use is
+    // reported, and the saved settings apply from the next start.
+    if api_changed {
+        apply_api_settings(&app, &state.settings.get()).map_err(|e| {
+            format!(
+                "Settings were saved, but the automation API could not start: {}",
+                e
+            )
+        })?;
+    }
+
     Ok(())
 }
 
-#[tauri::command]
-pub async fn get_current_data_path(
-    app: AppHandle,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
+fn current_data_dir(app: &AppHandle, settings: Settings) -> Result<std::path::PathBuf, String> {
     let default_path = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?;
 
-    let settings = state.settings.get();
-    let data_dir = migration::get_data_directory(default_path, settings.custom_data_path);
+    Ok(migration::get_data_directory(
+        default_path,
+        settings.custom_data_path,
+    ))
+}
+
+#[tauri::command]
+pub async fn get_current_data_path(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let data_dir = current_data_dir(&app, state.settings.get())?;
 
     data_dir
         .to_str()
@@ -764,6 +1244,53 @@ pub async fn get_current_data_path(
         .ok_or_else(|| "Invalid data path".to_string())
 }
 
+/// Loads the plugins under `<data dir>/plugins` when `plugins_enabled`, and
+/// unloads them otherwise. The directory is created so the settings window
+/// can open it.
+pub fn reload_plugin_set(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let settings = state.settings.get();
+    let plugins = if settings.plugins_enabled {
+        let dir = current_data_dir(app, settings)?.join(PLUGINS_DIR_NAME);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create the plugins folder: {}", e))?;
+        PluginSet::load(&dir)
+    } else {
+        PluginSet::empty()
+    };
+    *safe_lock(&state.plugins) = Arc::new(plugins);
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginsOverview {
+    dir: String,
+    plugins: Vec<PluginInfo>,
+}
+
+#[tauri::command]
+pub async fn get_plugins(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PluginsOverview, String> {
+    let dir = current_data_dir(&app, state.settings.get())?.join(PLUGINS_DIR_NAME);
+    Ok(PluginsOverview {
+        dir: dir.to_string_lossy().into_owned(),
+        plugins: safe_lock(&state.plugins).infos(),
+    })
+}
+
+/// Picks up plugins added to or removed from the plugins folder.
+#[tauri::command]
+pub async fn reload_plugins(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PluginsOverview, String> {
+    reload_plugin_set(&app)?;
+    get_plugins(app, state).await
+}
+
 #[tauri::command]
 pub async fn disable_global_shortcut(
     app: AppHandle,
@@ -798,6 +1325,8 @@ pub async fn disable_global_shortcut(
         }
     }
 
+    unregister_action_shortcuts(&app, &settings.action_shortcuts);
+
     Ok(())
 }
 
@@ -833,6 +1362,9 @@ pub async fn enable_global_shortcut(
         }
     }
 
+    register_action_shortcuts(&app, &settings.action_shortcuts)
+        .map_err(|e| format!("Failed to re-enable action shortcuts: {}", e))?;
+
     Ok(())
 }
 
@@ -936,6 +1468,10 @@ pub async fn migrate_data_location(
         Ok(())
     };
     crate::tray::update_tray_menu(&app);
+    // Plugins live in the data directory and are not migrated.
+    if let Err(e) = reload_plugin_set(&app) {
+        log::warn!("Failed to reload plugins after migration: {}", e);
+    }
 
     match (migration_result, restart_result) {
         (Ok(()), Ok(())) => Ok(()),
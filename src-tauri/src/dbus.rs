@@ -0,0 +1,419 @@
+//! Session-bus D-Bus service (Linux), so launchers such as rofi and ulauncher
+//! and GNOME/KDE shell extensions can drive ClipMan.
+//!
+//! Owns `com.clipman.Manager` and serves `/com/clipman/Manager` with the
+//! `com.clipman.Manager1` interface:
+//!
+//! - `List(u limit) -> a(sssbx)`: recent unpinned clips, newest first
+//! - `Search(s query, u limit) -> a(sssbx)`
+//! - `Paste(s id)`: pastes the clip into the focused window
+//! - `TogglePause() -> b`: whether capture is paused afterwards
+//! - `ShowQuickbar()`
+//! - signal `ClipAdded(sssbx)`: a capture was saved (or refreshed an
+//!   existing clip)
+//!
+//! Clips are `(id, content_type, preview, is_pinned, timestamp)`: the preview
+//! is the start of the text or the file list, and empty for images.
+//!
+//! Try it with
+//! `gdbus call --session -d com.clipman.Manager -o /com/clipman/Manager -m com.clipman.Manager1.List 10`.
+
+use std::sync::{Arc, Mutex};
+
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+use zbus::object_server::SignalEmitter;
+
+use crate::storage::{ClipPreviewItem, ClipStorage, ContentType, FrontendClipItem};
+
+pub const BUS_NAME: &str = "com.clipman.Manager";
+pub const OBJECT_PATH: &str = "/com/clipman/Manager";
+/// Clips returned when a caller passes a limit of 0.
+const DEFAULT_LIST_LIMIT: usize = 50;
+const MAX_LIST_LIMIT: usize = 1000;
+
+/// `(id, content_type, preview, is_pinned, timestamp)`
+pub type DbusClip = (String, String, String, bool, i64);
+
+/// What the service needs from the running app beyond storage.
+pub trait DbusHost: Send + Sync + 'static {
+    /// Starts pasting an existing clip; failures are the host's to log.
+    fn paste(&self, id: String);
+    /// Returns whether capture is paused afterwards.
+    fn toggle_pause(&self) -> bool;
+    fn show_quickbar(&self);
+}
+
+/// The running service; dropping it releases the bus name.
+pub struct DbusService {
+    connection: Connection,
+}
+
+impl DbusService {
+    pub fn start(
+        storage: Arc<Mutex<ClipStorage>>,
+        host: Arc<dyn DbusHost>,
+    ) -> Result<Self, String> {
+        let builder = Builder::session()
+            .map_err(|e| format!("Failed to connect to the session bus: {}", e))?;
+        Self::serve(builder, storage, host)
+    }
+
+    fn serve(
+        builder: Builder<'_>,
+        storage: Arc<Mutex<ClipStorage>>,
+        host: Arc<dyn DbusHost>,
+    ) -> Result<Self, String> {
+        let connection = builder
+            .name(BUS_NAME)
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, ClipManInterface { storage, host }))
+            .and_then(|builder| builder.build())
+            .map_err(|e| format!("Failed to register {} on the session bus: {}", BUS_NAME, e))?;
+        Ok(Self { connection })
+    }
+
+    /// Emits `ClipAdded` for a clip `save_to_storage` just saved.
+    pub fn clip_added(&self, item: &FrontendClipItem) {
+        let result = SignalEmitter::new(self.connection.inner(), OBJECT_PATH).and_then(|emitter| {
+            zbus::block_on(ClipManInterface::clip_added(
+                &emitter,
+                dbus_clip_from_frontend(item),
+            ))
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to emit the D-Bus ClipAdded signal: {}", e);
+        }
+    }
+}
+
+struct ClipManInterface {
+    storage: Arc<Mutex<ClipStorage>>,
+    host: Arc<dyn DbusHost>,
+}
+
+#[zbus::interface(name = "com.clipman.Manager1")]
+impl ClipManInterface {
+    fn list(&self, limit: u32) -> zbus::fdo::Result<Vec<DbusClip>> {
+        let items = crate::safe_lock(&self.storage)
+            .get_recent_clip_previews(list_limit(limit))
+            .map_err(storage_error)?;
+        Ok(items.iter().map(dbus_clip).collect())
+    }
+
+    fn search(&self, query: &str, limit: u32) -> zbus::fdo::Result<Vec<DbusClip>> {
+        let items = crate::safe_lock(&self.storage)
+            .search_clip_previews(query)
+            .map_err(storage_error)?;
+        Ok(items
+            .iter()
+            .take(list_limit(limit))
+            .map(dbus_clip)
+            .collect())
+    }
+
+    fn paste(&self, id: String) -> zbus::fdo::Result<()> {
+        let exists = crate::safe_lock(&self.storage)
+            .get_preview_by_id(&id)
+            .map_err(storage_error)?
+            .is_some();
+        if !exists {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "No clip with id {}",
+                id
+            )));
+        }
+        self.host.paste(id);
+        Ok(())
+    }
+
+    fn toggle_pause(&self) -> bool {
+        self.host.toggle_pause()
+    }
+
+    fn show_quickbar(&self) {
+        self.host.show_quickbar();
+    }
+
+    #[zbus(signal)]
+    async fn clip_added(emitter: &SignalEmitter<'_>, clip: DbusClip) -> zbus::Result<()>;
+}
+
+fn list_limit(limit: u32) -> usize {
+    match limit as usize {
+        0 => DEFAULT_LIST_LIMIT,
+        limit => limit.min(MAX_LIST_LIMIT),
+    }
+}
+
+fn storage_error(error: rusqlite::Error) -> zbus::fdo::Error {
+    log::error!("D-Bus request failed: {}", error);
+    zbus::fdo::Error::Failed(error.to_string())
+}
+
+fn dbus_clip(item: &ClipPreviewItem) -> DbusClip {
+    dbus_clip_from_frontend(&FrontendClipItem::from_preview(item.clone()))
+}
+
+fn dbus_clip_from_frontend(item: &FrontendClipItem) -> DbusClip {
+    let preview = match item.content_type {
+        // Previews of text and file lists are base64 text.
+        ContentType::Text | ContentType::Files => data_encoding::BASE64
+            .decode(item.content.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default(),
+        ContentType::Image => String::new(),
+    };
+    (
+        item.id.clone(),
+        item.content_type.as_db_value().to_string(),
+        preview,
+        item.is_pinned,
+        item.timestamp,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ClipItem;
+    use std::io::{BufRead, BufReader};
+    use std::path::{Path, PathBuf};
+    use std::process::{Child, Command, Stdio};
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use uuid::Uuid;
+    use zbus::blocking::Proxy;
+
+    const INTERFACE: &str = "com.clipman.Manager1";
+
+    /// A `dbus-daemon` of our own, so tests never touch the user's session bus.
+    struct PrivateBus {
+        daemon: Child,
+        address: String,
+        config_path: PathBuf,
+    }
+
+    impl PrivateBus {
+        fn start() -> Self {
+            let config_path =
+                std::env::temp_dir().join(format!("clipman_dbus_{}.conf", Uuid::new_v4().simple()));
+            std::fs::write(
+                &config_path,
+                r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>
+  <listen>unix:tmpdir=/tmp</listen>
+  <auth>EXTERNAL</auth>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>
+"#,
+            )
+            .unwrap();
+
+            let mut daemon = Command::new("dbus-daemon")
+                .arg(format!("--config-file={}", config_path.display()))
+                .args(["--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("dbus-daemon must be installed to run the D-Bus tests");
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+            Self {
+                daemon,
+                address: address.trim().to_string(),
+                config_path,
+            }
+        }
+
+        fn connect(&self) -> Connection {
+            Builder::address(self.address.as_str())
+                .unwrap()
+                .build()
+                .unwrap()
+        }
+    }
+
+    impl Drop for PrivateBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+            let _ = std::fs::remove_file(&self.config_path);
+        }
+    }
+
+    #[derive(Default)]
+    struct TestHost {
+        pasted: Mutex<Vec<String>>,
+        paused: Mutex<bool>,
+        quickbar_shown: Mutex<usize>,
+    }
+
+    impl DbusHost for TestHost {
+        fn paste(&self, id: String) {
+            crate::safe_lock(&self.pasted).push(id);
+        }
+
+        fn toggle_pause(&self) -> bool {
+            let mut paused = crate::safe_lock(&self.paused);
+            *paused = !*paused;
+            *paused
+        }
+
+        fn show_quickbar(&self) {
+            *crate::safe_lock(&self.quickbar_shown) += 1;
+        }
+    }
+
+    struct TestService {
+        service: DbusService,
+        host: Arc<TestHost>,
+        storage: Arc<Mutex<ClipStorage>>,
+        bus: PrivateBus,
+        db_path: PathBuf,
+    }
+
+    impl Drop for TestService {
+        fn drop(&mut self) {
+            let db_path = self.db_path.clone();
+            let _ = std::fs::remove_file(&db_path);
+            let _ = std::fs::remove_file(format!("{}-shm", db_path.display()));
+            let _ = std::fs::remove_file(format!("{}-wal", db_path.display()));
+        }
+    }
+
+    impl TestService {
+        fn start() -> Self {
+            let bus = PrivateBus::start();
+            let db_path =
+                std::env::temp_dir().join(format!("clipman_dbus_{}.db", Uuid::new_v4().simple()));
+            let storage = Arc::new(Mutex::new(ClipStorage::new(Path::new(&db_path)).unwrap()));
+            let host = Arc::new(TestHost::default());
+            let builder = Builder::address(bus.address.as_str()).unwrap();
+            let service = DbusService::serve(builder, storage.clone(), host.clone()).unwrap();
+            Self {
+                service,
+                host,
+                storage,
+                bus,
+                db_path,
+            }
+        }
+
+        fn add_text(&self, text: &str, timestamp: i64) -> ClipItem {
+            let item = ClipItem {
+                id: Uuid::new_v4().to_string(),
+                content: text.as_bytes().to_vec(),
+                thumbnail: None,
+                content_type: ContentType::Text,
+                timestamp,
+                is_pinned: false,
+                pin_order: None,
+                label: None,
+                group_name: None,
+                source_app: None,
+                html: None,
+                rtf: None,
+                perceptual_hash: None,
+                text_kind: None,
+                code_language: None,
+                is_redacted: false,
+                expires_at: None,
+                capture_marker: None,
+                formats: Vec::new(),
+            };
+            crate::safe_lock(&self.storage).insert(&item, 100).unwrap();
+            item
+        }
+    }
+
+    fn proxy(connection: &Connection) -> Proxy<'_> {
+        Proxy::new(connection, BUS_NAME, OBJECT_PATH, INTERFACE).unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn clips_can_be_listed_searched_and_pasted_over_the_bus() {
+        let service = TestService::start();
+        let first = service.add_text("first clip", 1_000);
+        let second = service.add_text("second clip, from rofi", 2_000);
+        let client = service.bus.connect();
+        let proxy = proxy(&client);
+
+        let listed: Vec<DbusClip> = proxy.call("List", &(0u32,)).unwrap();
+        let ids: Vec<&str> = listed.iter().map(|clip| clip.0.as_str()).collect();
+        assert_eq!(vec![second.id.as_str(), first.id.as_str()], ids);
+        assert_eq!("text", listed[0].1);
+        assert_eq!("second clip, from rofi", listed[0].2);
+
+        let limited: Vec<DbusClip> = proxy.call("List", &(1u32,)).unwrap();
+        assert_eq!(1, limited.len());
+
+        let found: Vec<DbusClip> = proxy.call("Search", &("rofi", 10u32)).unwrap();
+        assert_eq!(
+            vec![second.id.clone()],
+            found.into_iter().map(|clip| clip.0).collect::<Vec<_>>()
+        );
+
+        let () = proxy.call("Paste", &(first.id.as_str(),)).unwrap();
+        assert_eq!(
+            vec![first.id.clone()],
+            *crate::safe_lock(&service.host.pasted)
+        );
+        let missing: zbus::Result<()> = proxy.call("Paste", &("no-such-clip",));
+        assert!(missing.is_err());
+        assert_eq!(1, crate::safe_lock(&service.host.pasted).len());
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn pause_and_quickbar_are_forwarded_to_the_app() {
+        let service = TestService::start();
+        let client = service.bus.connect();
+        let proxy = proxy(&client);
+
+        let paused: bool = proxy.call("TogglePause", &()).unwrap();
+        assert!(paused);
+        let paused: bool = proxy.call("TogglePause", &()).unwrap();
+        assert!(!paused);
+
+        let () = proxy.call("ShowQuickbar", &()).unwrap();
+        assert_eq!(1, *crate::safe_lock(&service.host.quickbar_shown));
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn saved_clips_are_signalled() {
+        let service = TestService::start();
+        let client = service.bus.connect();
+        let proxy = proxy(&client);
+        let mut signals = proxy.receive_signal("ClipAdded").unwrap();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            if let Some(message) = signals.next() {
+                let clip: DbusClip = message.body().deserialize().unwrap();
+                let _ = sender.send(clip);
+            }
+        });
+
+        let item = service.add_text("just copied", 1_000);
+        let preview = crate::safe_lock(&service.storage)
+            .get_preview_by_id(&item.id)
+            .unwrap()
+            .unwrap();
+        service
+            .service
+            .clip_added(&FrontendClipItem::from_preview(preview));
+
+        let clip = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(item.id, clip.0);
+        assert_eq!("just copied", clip.2);
+    }
+}
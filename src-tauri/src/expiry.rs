@@ -0,0 +1,111 @@
+//! Deletion of sensitive clips once their `expires_at` passes.
+//!
+//! Every sensitive capture arms a one-shot timer for its own expiry, and
+//! startup sweeps whatever expired while the app was closed and re-arms
+//! timers for the clips still pending. A sweep deletes every due clip, not
+//! just the one whose timer fired, so overlapping timers are harmless.
+//!
+//! Like a password manager, an expired clip is also cleared from the system
+//! clipboard, but only if the clipboard still holds exactly that content:
+//! the `CopyMarker` of the expired clip must match the current clipboard's,
+//! so a newer copy is never wiped.
+
+use chrono::Utc;
+use image::GenericImageView;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::storage::{ClipItem, ContentType, CopyMarker};
+use crate::AppState;
+
+/// Sweep expired clips at `expires_at` (Unix seconds), or right away if it
+/// has already passed.
+pub fn schedule(app_handle: &AppHandle, expires_at: i64) {
+    let delay = Duration::from_secs((expires_at - Utc::now().timestamp()).max(0) as u64);
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        sweep(&app_handle);
+    });
+}
+
+/// Startup pass: drop clips that expired while the app was not running and
+/// re-arm a timer for each pending expiry.
+pub fn sweep_and_rearm(app_handle: &AppHandle) {
+    sweep(app_handle);
+
+    let state = app_handle.state::<AppState>();
+    let pending = crate::safe_lock(&state.storage).pending_expiries();
+    match pending {
+        Ok(pending) => {
+            for expires_at in pending {
+                schedule(app_handle, expires_at);
+            }
+        }
+        Err(e) => log::error!("Failed to read pending clip expiries: {}", e),
+    }
+}
+
+fn sweep(app_handle: &AppHandle) {
+    use crate::tray::update_tray_menu;
+
+    let state = app_handle.state::<AppState>();
+    let expired = crate::safe_lock(&state.storage).take_expired_clips(Utc::now().timestamp());
+    let expired = match expired {
+        Ok(expired) if expired.is_empty() => return,
+        Ok(expired) => expired,
+        Err(e) => {
+            log::error!("Failed to delete expired clips: {}", e);
+            return;
+        }
+    };
+
+    log::info!("⏱️ Deleted {} expired sensitive clip(s)", expired.len());
+    clear_clipboard_if_still_holding(&expired);
+
+    let ids: Vec<String> = expired.into_iter().map(|item| item.id).collect();
+    app_handle.emit("clips-expired", &ids).ok();
+    update_tray_menu(app_handle);
+}
+
+fn clear_clipboard_if_still_holding(expired: &[ClipItem]) {
+    let Some(current) = crate::clipboard::current_clipboard_marker() else {
+        return;
+    };
+    if !expired
+        .iter()
+        .any(|item| clip_marker(item).as_ref() == Some(&current))
+    {
+        return;
+    }
+
+    match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.clear()) {
+        Ok(()) => log::info!("🧹 Cleared an expired sensitive clip from the system clipboard"),
+        Err(e) => log::warn!("Failed to clear expired clip from clipboard: {}", e),
+    }
+}
+
+/// The marker the clipboard monitor computed when `item` was captured (D5).
+/// Clips stored before capture markers were recorded fall back to a marker
+/// of their content; those images are compared by their stored pixels, so
+/// one downscaled on capture never matches and the clipboard is left alone.
+fn clip_marker(item: &ClipItem) -> Option<CopyMarker> {
+    if let Some(marker) = item.capture_copy_marker() {
+        return Some(marker);
+    }
+    match item.content_type {
+        ContentType::Text | ContentType::Files => Some(CopyMarker::from_payload(
+            item.content_type.clone(),
+            &item.content,
+        )),
+        ContentType::Image => {
+            let img = image::load_from_memory(&item.content).ok()?;
+            let (width, height) = img.dimensions();
+            Some(CopyMarker::from_normalized_image_parts(
+                width as usize,
+                height as usize,
+                &img.to_rgba8().into_raw(),
+            ))
+        }
+    }
+}
@@ -0,0 +1,447 @@
+//! User hooks: shell commands run on every new clip `save_to_storage`
+//! stores (not on a duplicate that refreshed a stored clip), e.g. to log
+//! URLs to a file or to translate text.
+//!
+//! A hook gets the clip on stdin (the text, the PNG bytes or the
+//! newline-separated file paths) and its metadata in `CLIPMAN_*` environment
+//! variables. Matching hooks run one after another in settings order; each
+//! is killed once its timeout passes, and at most `max_concurrent_hooks`
+//! hook processes run at a time across all captures. What a hook prints
+//! matters only for a `transform` hook on a Text clip: non-empty stdout
+//! replaces the text, and later hooks see the replacement. A hook exiting
+//! with [`VETO_EXIT_CODE`] deletes the clip and ends the chain. A clip that
+//! is pinned by the time the hooks finish is neither deleted nor replaced,
+//! and replacement text passes the same normalizers and secret policy as a
+//! capture. Any other failure is logged and the chain goes on.
+
+use std::io::{Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{ClipItem, ContentType};
+
+/// Exit status with which a hook asks for the clip to be deleted.
+pub const VETO_EXIT_CODE: i32 = 3;
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 10;
+/// How often a running hook is checked for exit or timeout.
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureHook {
+    /// Display name, used in log lines.
+    pub name: String,
+    pub enabled: bool,
+    /// Run with `sh -c` (`cmd /C` on Windows).
+    pub command: String,
+    /// Frontmost app at capture time, matched like `ignored_apps`
+    /// (trimmed, case-insensitive). `None` runs the hook for every app.
+    pub source_app: Option<String>,
+    /// `None` runs the hook for every content type.
+    pub content_type: Option<ContentType>,
+    /// When true, the hook's stdout replaces a Text clip's text.
+    pub transform: bool,
+    pub timeout_secs: u64,
+}
+
+impl Default for CaptureHook {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            command: String::new(),
+            source_app: None,
+            content_type: None,
+            transform: false,
+            timeout_secs: DEFAULT_HOOK_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// Checks what `normalize_capture_hooks` cannot fix.
+pub fn validate_capture_hook(hook: &CaptureHook) -> Result<(), String> {
+    if hook.command.is_empty() {
+        return Err(format!("Hook \"{}\" has no command", hook.name));
+    }
+    Ok(())
+}
+
+impl CaptureHook {
+    fn matches(&self, item: &ClipItem) -> bool {
+        self.enabled
+            && self
+                .content_type
+                .as_ref()
+                .is_none_or(|content_type| *content_type == item.content_type)
+            && self.source_app.as_deref().is_none_or(|wanted| {
+                item.source_app
+                    .as_deref()
+                    .is_some_and(|app| app.trim().eq_ignore_ascii_case(wanted.trim()))
+            })
+    }
+
+    /// The name for log lines, falling back to the command.
+    fn label(&self) -> &str {
+        if self.name.is_empty() {
+            &self.command
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// What the hooks decided about a stored clip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookVerdict {
+    Keep,
+    /// The text the transform hooks produced, different from the stored one.
+    Replace(String),
+    /// Delete the clip; carries the vetoing hook's name.
+    Veto(String),
+}
+
+enum HookExit {
+    Success(Vec<u8>),
+    Veto,
+}
+
+/// Runs hook chains and enforces the limit on concurrent hook processes.
+#[derive(Default)]
+pub struct HookRunner {
+    running: Mutex<usize>,
+    slot_freed: Condvar,
+}
+
+struct HookSlot<'a> {
+    runner: &'a HookRunner,
+}
+
+impl Drop for HookSlot<'_> {
+    fn drop(&mut self) {
+        *crate::safe_lock(&self.runner.running) -= 1;
+        self.runner.slot_freed.notify_one();
+    }
+}
+
+impl HookRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs every hook in `hooks` that matches `item`, blocking until the
+    /// chain ends. Call it off the capture thread.
+    pub fn run(
+        &self,
+        hooks: &[CaptureHook],
+        item: &ClipItem,
+        max_concurrent: usize,
+    ) -> HookVerdict {
+        let original = (item.content_type == ContentType::Text)
+            .then(|| String::from_utf8_lossy(&item.content).into_owned());
+        let mut text = original.clone();
+
+        for hook in hooks.iter().filter(|hook| hook.matches(item)) {
+            let stdin = text
+                .as_deref()
+                .map_or(item.content.as_slice(), str::as_bytes);
+            let result = {
+                let _slot = self.acquire(max_concurrent);
+                run_hook(hook, item, stdin)
+            };
+            match result {
+                Ok(HookExit::Veto) => return HookVerdict::Veto(hook.label().to_string()),
+                Ok(HookExit::Success(stdout)) => {
+                    if hook.transform && text.is_some() {
+                        if let Some(replacement) = transformed_text(stdout) {
+                            text = Some(replacement);
+                        }
+                    }
+                }
+                Err(e) => log::warn!("Hook '{}' failed: {}", hook.label(), e),
+            }
+        }
+
+        match text {
+            Some(text) if Some(&text) != original.as_ref() => HookVerdict::Replace(text),
+            _ => HookVerdict::Keep,
+        }
+    }
+
+    fn acquire(&self, max_concurrent: usize) -> HookSlot<'_> {
+        let mut running = crate::safe_lock(&self.running);
+        while *running >= max_concurrent.max(1) {
+            running = self
+                .slot_freed
+                .wait(running)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        *running += 1;
+        HookSlot { runner: self }
+    }
+}
+
+/// Empty or non-UTF-8 output leaves the text alone. One trailing newline is
+/// dropped, as nearly every command prints one.
+fn transformed_text(stdout: Vec<u8>) -> Option<String> {
+    let mut text = String::from_utf8(stdout).ok()?;
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    (!text.is_empty()).then_some(text)
+}
+
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    }
+    #[cfg(not(windows))]
+    {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+fn run_hook(hook: &CaptureHook, item: &ClipItem, stdin: &[u8]) -> Result<HookExit, String> {
+    let mut command = shell_command(&hook.command);
+    command
+        .env("CLIPMAN_CLIP_ID", &item.id)
+        .env("CLIPMAN_CONTENT_TYPE", item.content_type.as_db_value())
+        .env("CLIPMAN_TIMESTAMP", item.timestamp.to_string())
+        .env(
+            "CLIPMAN_SOURCE_APP",
+            item.source_app.as_deref().unwrap_or_default(),
+        )
+        .env(
+            "CLIPMAN_TEXT_KIND",
+            item.text_kind.map_or("", |kind| kind.as_db_value()),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("could not start '{}': {}", hook.command, e))?;
+
+    // Feed stdin and drain stdout on their own threads, so a hook that
+    // ignores one of them cannot block the other or the timeout.
+    let mut child_stdin = child.stdin.take().expect("stdin is piped");
+    let input = stdin.to_vec();
+    let writer = std::thread::spawn(move || {
+        // A hook that exits without reading its input is fine.
+        let _ = child_stdin.write_all(&input);
+    });
+    let mut child_stdout = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = child_stdout.read_to_end(&mut output);
+        output
+    });
+
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    let Some(status) = wait_with_timeout(&mut child, timeout)? else {
+        // Not joined: a process the hook started may still hold the pipes.
+        return Err(format!("timed out after {}s", hook.timeout_secs));
+    };
+    let _ = writer.join();
+    let output = reader.join().unwrap_or_default();
+
+    if status.code() == Some(VETO_EXIT_CODE) {
+        Ok(HookExit::Veto)
+    } else if status.success() {
+        Ok(HookExit::Success(output))
+    } else {
+        Err(format!("exited with {}", status))
+    }
+}
+
+/// `None` when the hook had to be killed.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Result<Option<ExitStatus>, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        std::thread::sleep(HOOK_POLL_INTERVAL);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::classify::TextKind;
+    use std::sync::Arc;
+
+    fn text_item(text: &str) -> ClipItem {
+        ClipItem {
+            id: "clip-1".to_string(),
+            content: text.as_bytes().to_vec(),
+            thumbnail: None,
+            content_type: ContentType::Text,
+            timestamp: 1_700_000_000,
+            is_pinned: false,
+            pin_order: None,
+            label: None,
+            group_name: None,
+            source_app: Some("Firefox".to_string()),
+            html: None,
+            rtf: None,
+            perceptual_hash: None,
+            text_kind: Some(TextKind::Url),
+            code_language: None,
+            is_redacted: false,
+            expires_at: None,
+            capture_marker: None,
+            formats: Vec::new(),
+        }
+    }
+
+    fn hook(command: &str) -> CaptureHook {
+        CaptureHook {
+            command: command.to_string(),
+            ..CaptureHook::default()
+        }
+    }
+
+    fn transform(command: &str) -> CaptureHook {
+        CaptureHook {
+            transform: true,
+            ..hook(command)
+        }
+    }
+
+    #[test]
+    fn hooks_match_on_content_type_and_source_app() {
+        let item = text_item("https://example.com");
+        assert!(hook("true").matches(&item));
+        assert!(CaptureHook {
+            source_app: Some(" firefox ".to_string()),
+            content_type: Some(ContentType::Text),
+            ..hook("true")
+        }
+        .matches(&item));
+        assert!(!CaptureHook {
+            content_type: Some(ContentType::Image),
+            ..hook("true")
+        }
+        .matches(&item));
+        assert!(!CaptureHook {
+            source_app: Some("Terminal".to_string()),
+            ..hook("true")
+        }
+        .matches(&item));
+        assert!(!CaptureHook {
+            enabled: false,
+            ..hook("true")
+        }
+        .matches(&item));
+    }
+
+    #[test]
+    fn transform_hooks_chain_and_see_metadata() {
+        let runner = HookRunner::new();
+        let hooks = [
+            transform("tr a-z A-Z"),
+            transform(
+                r#"printf '%s [%s %s %s]' "$(cat)" "$CLIPMAN_SOURCE_APP" "$CLIPMAN_CONTENT_TYPE" "$CLIPMAN_TEXT_KIND""#,
+            ),
+        ];
+
+        let verdict = runner.run(&hooks, &text_item("hello"), 2);
+
+        assert_eq!(
+            HookVerdict::Replace("HELLO [Firefox text url]".to_string()),
+            verdict
+        );
+    }
+
+    #[test]
+    fn output_of_plain_hooks_and_failures_leave_the_clip_alone() {
+        let runner = HookRunner::new();
+        let hooks = [hook("echo ignored"), transform("exit 1"), transform("")];
+
+        assert_eq!(
+            HookVerdict::Keep,
+            runner.run(&hooks, &text_item("hello"), 2)
+        );
+        assert_eq!(
+            HookVerdict::Keep,
+            runner.run(&[transform("cat")], &text_item("hello"), 2)
+        );
+    }
+
+    #[test]
+    fn a_veto_ends_the_chain() {
+        let runner = HookRunner::new();
+        let marker = std::env::temp_dir().join(format!("clipman_hook_{}", uuid::Uuid::new_v4()));
+        let hooks = [
+            CaptureHook {
+                name: "no urls".to_string(),
+                ..hook(&format!("exit {}", VETO_EXIT_CODE))
+            },
+            hook(&format!("touch '{}'", marker.display())),
+        ];
+
+        let verdict = runner.run(&hooks, &text_item("https://example.com"), 2);
+
+        assert_eq!(HookVerdict::Veto("no urls".to_string()), verdict);
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn slow_hooks_are_killed_after_their_timeout() {
+        let runner = HookRunner::new();
+        let hooks = [CaptureHook {
+            timeout_secs: 1,
+            ..transform("sleep 5; echo late")
+        }];
+
+        let started = Instant::now();
+        let verdict = runner.run(&hooks, &text_item("hello"), 2);
+
+        assert_eq!(HookVerdict::Keep, verdict);
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn concurrent_hooks_are_limited() {
+        let runner = Arc::new(HookRunner::new());
+        let log = std::env::temp_dir().join(format!("clipman_hooks_{}", uuid::Uuid::new_v4()));
+        // Each run appends its start and end, so overlapping runs would
+        // interleave.
+        let command = format!(
+            "echo start >> '{log}'; sleep 0.2; echo end >> '{log}'",
+            log = log.display()
+        );
+
+        let threads: Vec<_> = (0..3)
+            .map(|_| {
+                let runner = runner.clone();
+                let hooks = [hook(&command)];
+                std::thread::spawn(move || runner.run(&hooks, &text_item("hello"), 1))
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(HookVerdict::Keep, thread.join().unwrap());
+        }
+
+        let lines = std::fs::read_to_string(&log).unwrap();
+        let _ = std::fs::remove_file(&log);
+        assert_eq!("start\nend\n".repeat(3), lines);
+    }
+}
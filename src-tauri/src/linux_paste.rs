@@ -0,0 +1,236 @@
+//! Ctrl+V on Linux, for X11 and Wayland sessions alike.
+//!
+//! enigo drives XTest, which a Wayland compositor only honors for XWayland
+//! windows. Wayland sessions therefore press the shortcut through a virtual
+//! keyboard (`zwp_virtual_keyboard_v1`, offered by wlroots compositors and
+//! by KWin), or, where the compositor has no such protocol (GNOME), through
+//! a uinput device, which needs write access to `/dev/uinput`. uinput sends
+//! the physical V key, so on layouts that move it (Dvorak) the compositor
+//! sees a different shortcut; the virtual keyboard brings its own keymap.
+//!
+//! [`probe`] picks the first input path this session can use; it is cheap
+//! enough to run before every paste, so a fixed uinput permission or a
+//! switch of session takes effect without a restart.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::fd::AsFd;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+use evdev::{AttributeSet, EventType, InputEvent, Key};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry::WlRegistry, wl_seat::WlSeat};
+use wayland_client::{delegate_noop, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::{
+    zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1,
+    zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1,
+};
+
+use crate::paste::{PasteBackend, PasteUnsupportedReason};
+
+const UINPUT_PATH: &str = "/dev/uinput";
+
+/// libinput only starts listening to a new device a moment after it
+/// appears; keys pressed before that are lost.
+const UINPUT_SETTLE_DELAY: Duration = Duration::from_millis(200);
+
+/// Evdev key codes, which both the virtual-keyboard protocol and uinput
+/// take (an xkb keycode is the evdev code plus 8).
+const KEY_LEFTCTRL: u32 = 29;
+const KEY_V: u32 = 47;
+
+const KEY_RELEASED: u32 = 0;
+const KEY_PRESSED: u32 = 1;
+
+/// `wl_keyboard.keymap_format.xkb_v1`.
+const KEYMAP_FORMAT_XKB_V1: u32 = 1;
+
+/// Control's bit in the depressed-modifier mask: xkb numbers its real
+/// modifiers Shift, Lock, Control, ...
+const CONTROL_MASK: u32 = 1 << 2;
+
+/// A keymap with just the two keys the shortcut needs, so the virtual
+/// keyboard types Ctrl+V whatever the user's layout is.
+const PASTE_KEYMAP: &str = r#"xkb_keymap {
+    xkb_keycodes "clipman" {
+        minimum = 8;
+        maximum = 255;
+        <LCTL> = 37;
+        <AB04> = 55;
+    };
+    xkb_types "clipman" { include "complete" };
+    xkb_compatibility "clipman" { include "complete" };
+    xkb_symbols "clipman" {
+        key <LCTL> { [ Control_L ] };
+        key <AB04> { [ v, V ] };
+        modifier_map Control { <LCTL> };
+    };
+};
+"#;
+
+/// The uinput device, created on first use and kept: every new device makes
+/// the compositor re-scan its inputs and needs the settle delay.
+static UINPUT_DEVICE: Mutex<Option<VirtualDevice>> = Mutex::new(None);
+
+/// The input path a paste would take in this session.
+pub fn probe() -> Result<PasteBackend, PasteUnsupportedReason> {
+    if !crate::wayland::session_available() {
+        return if std::env::var_os("DISPLAY").is_some() {
+            Ok(PasteBackend::X11)
+        } else {
+            Err(PasteUnsupportedReason::NoDisplay)
+        };
+    }
+
+    if virtual_keyboard_available() {
+        Ok(PasteBackend::VirtualKeyboard)
+    } else if uinput_writable() {
+        Ok(PasteBackend::Uinput)
+    } else {
+        Err(PasteUnsupportedReason::WaylandInputUnavailable)
+    }
+}
+
+/// Press Ctrl+V through `backend`, one of the Wayland paths [`probe`]
+/// returns. A compositor may advertise the virtual keyboard and still
+/// refuse it to this client, so that path falls back to uinput.
+pub fn press_paste_shortcut(backend: PasteBackend) -> Result<(), String> {
+    match backend {
+        PasteBackend::VirtualKeyboard => press_with_virtual_keyboard().or_else(|e| {
+            if !uinput_writable() {
+                return Err(e);
+            }
+            log::warn!("{}; pasting through uinput instead", e);
+            press_with_uinput()
+        }),
+        PasteBackend::Uinput => press_with_uinput(),
+        other => Err(format!("{:?} is not a Wayland paste backend", other)),
+    }
+}
+
+fn uinput_writable() -> bool {
+    OpenOptions::new().write(true).open(UINPUT_PATH).is_ok()
+}
+
+/// State for the short-lived virtual-keyboard connection, which receives no
+/// events worth handling.
+struct KeyboardState;
+
+impl Dispatch<WlRegistry, GlobalListContents> for KeyboardState {
+    fn event(
+        _state: &mut Self,
+        _registry: &WlRegistry,
+        _event: <WlRegistry as Proxy>::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(KeyboardState: ignore WlSeat);
+delegate_noop!(KeyboardState: ZwpVirtualKeyboardManagerV1);
+delegate_noop!(KeyboardState: ZwpVirtualKeyboardV1);
+
+fn virtual_keyboard_available() -> bool {
+    let Ok(connection) = Connection::connect_to_env() else {
+        return false;
+    };
+    let Ok((globals, _queue)) = registry_queue_init::<KeyboardState>(&connection) else {
+        return false;
+    };
+    let interface = ZwpVirtualKeyboardManagerV1::interface().name;
+    globals
+        .contents()
+        .with_list(|list| list.iter().any(|global| global.interface == interface))
+}
+
+fn press_with_virtual_keyboard() -> Result<(), String> {
+    let connection = Connection::connect_to_env()
+        .map_err(|e| format!("Failed to connect to the Wayland display: {}", e))?;
+    let (globals, mut queue) = registry_queue_init::<KeyboardState>(&connection)
+        .map_err(|e| format!("Failed to list Wayland globals: {}", e))?;
+    let qh = queue.handle();
+
+    let seat: WlSeat = globals
+        .bind(&qh, 1..=1, ())
+        .map_err(|e| format!("No Wayland seat to type on: {}", e))?;
+    let manager: ZwpVirtualKeyboardManagerV1 = globals
+        .bind(&qh, 1..=1, ())
+        .map_err(|e| format!("No Wayland virtual keyboard: {}", e))?;
+    let keyboard = manager.create_virtual_keyboard(&seat, &qh, ());
+
+    let keymap = keymap_file()?;
+    keyboard.keymap(
+        KEYMAP_FORMAT_XKB_V1,
+        keymap.as_fd(),
+        PASTE_KEYMAP.len() as u32 + 1,
+    );
+    keyboard.key(0, KEY_LEFTCTRL, KEY_PRESSED);
+    keyboard.modifiers(CONTROL_MASK, 0, 0, 0);
+    keyboard.key(0, KEY_V, KEY_PRESSED);
+    keyboard.key(0, KEY_V, KEY_RELEASED);
+    keyboard.key(0, KEY_LEFTCTRL, KEY_RELEASED);
+    keyboard.modifiers(0, 0, 0, 0);
+
+    // A compositor that refuses the keyboard (KWin does for clients it does
+    // not trust) fails the connection, which the roundtrip reports.
+    queue
+        .roundtrip(&mut KeyboardState)
+        .map_err(|e| format!("The compositor rejected the virtual keyboard: {}", e))?;
+    keyboard.destroy();
+    connection
+        .flush()
+        .map_err(|e| format!("Failed to flush the Wayland connection: {}", e))
+}
+
+/// [`PASTE_KEYMAP`], NUL-terminated, in a memfd the compositor can map.
+fn keymap_file() -> Result<std::fs::File, String> {
+    let fd = rustix::fs::memfd_create("clipman-keymap", rustix::fs::MemfdFlags::CLOEXEC)
+        .map_err(|e| format!("Failed to create the keymap file: {}", e))?;
+    let mut file = std::fs::File::from(fd);
+    file.write_all(PASTE_KEYMAP.as_bytes())
+        .and_then(|()| file.write_all(b"\0"))
+        .map_err(|e| format!("Failed to write the keymap: {}", e))?;
+    Ok(file)
+}
+
+fn press_with_uinput() -> Result<(), String> {
+    let mut cached = crate::safe_lock(&UINPUT_DEVICE);
+    let mut device = match cached.take() {
+        Some(device) => device,
+        None => {
+            let device = create_uinput_device()?;
+            std::thread::sleep(UINPUT_SETTLE_DELAY);
+            device
+        }
+    };
+
+    // One report per key, so the compositor sees Control held before V. A
+    // device that fails is dropped and created afresh on the next paste.
+    for (code, value) in [
+        (KEY_LEFTCTRL, KEY_PRESSED),
+        (KEY_V, KEY_PRESSED),
+        (KEY_V, KEY_RELEASED),
+        (KEY_LEFTCTRL, KEY_RELEASED),
+    ] {
+        device
+            .emit(&[InputEvent::new(EventType::KEY, code as u16, value as i32)])
+            .map_err(|e| format!("Failed to send a key through uinput: {}", e))?;
+    }
+    *cached = Some(device);
+    Ok(())
+}
+
+fn create_uinput_device() -> Result<VirtualDevice, String> {
+    let mut keys = AttributeSet::<Key>::new();
+    keys.insert(Key::KEY_LEFTCTRL);
+    keys.insert(Key::KEY_V);
+
+    VirtualDeviceBuilder::new()
+        .and_then(|builder| builder.name("ClipMan paste").with_keys(&keys))
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Failed to create a uinput keyboard: {}", e))
+}
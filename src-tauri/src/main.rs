@@ -2,25 +2,52 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod accessibility;
+mod api;
+mod classify;
 mod clipboard;
+mod clipboard_formats;
 mod commands;
+#[cfg(target_os = "linux")]
+mod dbus;
+mod expiry;
+mod hooks;
+#[cfg(target_os = "linux")]
+mod linux_paste;
+mod merge;
 mod migration;
+mod normalize;
 mod paste;
+mod paste_keys;
+mod pause;
+mod phash;
+mod placement;
+mod plugins;
+mod rtf;
+mod rules;
 mod secrets;
 mod settings;
+mod shortcuts;
 mod storage;
 mod tray;
+mod type_paste;
+#[cfg(target_os = "linux")]
+mod wayland;
 mod window;
+#[cfg(target_os = "linux")]
+mod x11_focus;
 
 use clipboard::ClipboardMonitor;
 use commands::{
     check_accessibility_permission, check_clipboard_permission, check_for_updates,
-    clear_non_pinned_history, copy_to_system_clipboard, delete_clip, disable_global_shortcut,
-    enable_global_shortcut, get_clip, get_current_data_path, get_pinned_clips, get_recent_clips,
-    get_settings, hide_quickbar, install_update, migrate_data_location,
+    clear_non_pinned_history, clear_paste_stack, copy_to_system_clipboard, delete_clip,
+    disable_global_shortcut, enable_global_shortcut, get_clip, get_current_data_path,
+    get_full_fidelity_support, get_group_clips, get_paste_stack_count, get_paste_support,
+    get_pinned_clips, get_plugins, get_recent_clips, get_settings, get_similar_images,
+    get_statistics, hide_quickbar, init_api_server, install_update, migrate_data_location,
     open_accessibility_settings, open_folder, open_settings_window, paste_clip, paste_clips,
-    register_quickbar_shortcut, reorder_pinned, search_clips, set_clip_label, show_quickbar,
-    toggle_pin, update_settings,
+    push_paste_stack, register_action_shortcuts, register_quickbar_shortcut, reload_plugin_set,
+    reload_plugins, reorder_pinned, search_clips, set_clip_label, show_quickbar, toggle_pin,
+    update_settings,
 };
 use settings::SettingsManager;
 use storage::{ClipStorage, CopyMarker};
@@ -46,6 +73,21 @@ pub struct AppState {
     pub settings: Arc<SettingsManager>,
     pub settings_write_lock: Mutex<()>,
     pub last_copied_by_us: Arc<Mutex<Option<CopyMarker>>>,
+    /// Timed capture pause; kept here rather than in the monitor so it
+    /// survives monitor restarts.
+    pub capture_pause: pause::CapturePause,
+    /// Clipboard contents waiting to be put back after a paste.
+    pub clipboard_restore: Arc<paste::ClipboardRestore>,
+    /// Clips queued for the paste-stack shortcut.
+    pub paste_stack: paste::PasteStack,
+    /// The automation API, while `api_enabled` is on.
+    pub api_server: Mutex<Option<api::ApiServer>>,
+    /// Outlives API restarts, so it is listened to only once.
+    pub api_events: Arc<api::ApiEvents>,
+    /// Limits how many capture hooks run at once.
+    pub hook_runner: hooks::HookRunner,
+    /// Loaded from the data directory; replaced whole on reload.
+    pub plugins: Mutex<Arc<plugins::PluginSet>>,
     pub icon_cache: Arc<TrayIconCache>,
     pub quickbar_foreground_window: window::ForegroundWindowStore,
 }
@@ -327,6 +369,13 @@ fn main() {
                 settings: settings_manager.clone(),
                 settings_write_lock: Mutex::new(()),
                 last_copied_by_us: last_copied_by_us.clone(),
+                capture_pause: pause::CapturePause::new(),
+                clipboard_restore: Arc::new(paste::ClipboardRestore::new()),
+                paste_stack: paste::PasteStack::new(),
+                api_server: Mutex::new(None),
+                api_events: Arc::new(api::ApiEvents::default()),
+                hook_runner: hooks::HookRunner::new(),
+                plugins: Mutex::new(Arc::new(plugins::PluginSet::empty())),
                 icon_cache: icon_cache.clone(),
                 quickbar_foreground_window: quickbar_foreground_window.clone(),
             };
@@ -355,6 +404,11 @@ fn main() {
 
             log::info!("System tray initialized");
 
+            // Sensitive clips that expired while the app was closed go before
+            // anything can show them.
+            expiry::sweep_and_rearm(app.handle());
+            pause::restore(app.handle());
+
             // Start clipboard monitoring
             let app_handle = app.handle().clone();
             let state: tauri::State<AppState> = app_handle.state();
@@ -403,6 +457,23 @@ fn main() {
                 }
             }
 
+            if let Err(e) = register_action_shortcuts(app.handle(), &settings.action_shortcuts) {
+                log::warn!("{}", e);
+            }
+
+            if let Err(e) = reload_plugin_set(app.handle()) {
+                log::warn!("Plugins not loaded: {}", e);
+            }
+
+            if let Err(e) = init_api_server(app.handle(), &settings) {
+                log::warn!("Automation API not started: {}", e);
+            }
+
+            #[cfg(target_os = "linux")]
+            if let Err(e) = commands::start_dbus_service(app.handle()) {
+                log::warn!("D-Bus service not started: {}", e);
+            }
+
             log::info!("Global shortcuts registered: {}", current_shortcut);
 
             Ok(())
@@ -412,6 +483,8 @@ fn main() {
             get_pinned_clips,
             get_clip,
             search_clips,
+            get_similar_images,
+            get_group_clips,
             toggle_pin,
             delete_clip,
             get_settings,
@@ -423,6 +496,9 @@ fn main() {
             copy_to_system_clipboard,
             paste_clip,
             paste_clips,
+            push_paste_stack,
+            clear_paste_stack,
+            get_paste_stack_count,
             set_clip_label,
             reorder_pinned,
             open_settings_window,
@@ -434,7 +510,12 @@ fn main() {
             enable_global_shortcut,
             open_folder,
             migrate_data_location,
-            get_current_data_path
+            get_current_data_path,
+            get_paste_support,
+            get_full_fidelity_support,
+            get_statistics,
+            get_plugins,
+            reload_plugins
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
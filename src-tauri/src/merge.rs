@@ -0,0 +1,373 @@
+//! How merge-paste (task #13) combines the selected clips: the text formats
+//! a merge can produce, and stacking an all-image selection into one image.
+//!
+//! Everything here is pure so formats can be tested without a clipboard;
+//! `paste::paste_clips` does the fetching and writing.
+
+use chrono::{Local, TimeZone};
+use image::{imageops, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::ContentType;
+
+/// Largest width or height of a stacked image, matching the upper bound of
+/// `max_image_dimension`.
+const MAX_STACKED_DIMENSION: u32 = 16_384;
+
+/// One selected clip, in selection order.
+#[derive(Debug, Clone)]
+pub struct MergeItem {
+    pub content_type: ContentType,
+    /// Text, newline-joined file paths (D3), or PNG bytes for an image when
+    /// images are being stacked (empty otherwise).
+    pub content: Vec<u8>,
+    pub source_app: Option<String>,
+    /// Capture time (Unix seconds), before the merge touched it.
+    pub timestamp: i64,
+}
+
+/// Output format of a text merge.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MergeFormat {
+    /// Items joined by the caller's separator, verbatim.
+    #[default]
+    Plain,
+    /// `1. first`, `2. second`, ...
+    NumberedList,
+    /// `- first`, `- second`, ...
+    MarkdownBullets,
+    /// One CSV record with a field per item.
+    CsvRow,
+    /// One CSV record per item.
+    CsvColumn,
+    /// A JSON array of strings.
+    JsonArray,
+    /// `template` rendered per item, joined by the caller's separator.
+    /// `{index}` (from 1), `{content}`, `{source_app}` and `{timestamp}`
+    /// are replaced; any other braces are kept as written.
+    Template { template: String },
+}
+
+pub fn validate_merge_format(format: &MergeFormat) -> Result<(), String> {
+    match format {
+        MergeFormat::Template { template } if template.is_empty() => {
+            Err("Merge template cannot be empty".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Merges the text form of `items` (in order) per `format`, skipping and
+/// counting Image clips, which have no text form. Text uses its bytes;
+/// Files use their newline-joined path text (D3). `separator` is used by
+/// `Plain` and `Template` only.
+pub fn merge_texts(items: &[MergeItem], format: &MergeFormat, separator: &str) -> (String, usize) {
+    let texts: Vec<(&MergeItem, String)> = items
+        .iter()
+        .filter(|item| item.content_type != ContentType::Image)
+        .map(|item| (item, String::from_utf8_lossy(&item.content).into_owned()))
+        .collect();
+    let skipped_images = items.len() - texts.len();
+
+    let merged = match format {
+        MergeFormat::Plain => texts
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<_>>()
+            .join(separator),
+        MergeFormat::NumberedList => texts
+            .iter()
+            .enumerate()
+            .map(|(index, (_, text))| list_item(&format!("{}. ", index + 1), text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        MergeFormat::MarkdownBullets => texts
+            .iter()
+            .map(|(_, text)| list_item("- ", text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        MergeFormat::CsvRow => texts
+            .iter()
+            .map(|(_, text)| csv_field(text))
+            .collect::<Vec<_>>()
+            .join(","),
+        MergeFormat::CsvColumn => texts
+            .iter()
+            .map(|(_, text)| csv_field(text))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        MergeFormat::JsonArray => {
+            let strings: Vec<&str> = texts.iter().map(|(_, text)| text.as_str()).collect();
+            serde_json::to_string_pretty(&strings).unwrap_or_default()
+        }
+        MergeFormat::Template { template } => texts
+            .iter()
+            .enumerate()
+            .map(|(index, (item, text))| render_template(template, index + 1, text, item))
+            .collect::<Vec<_>>()
+            .join(separator),
+    };
+    (merged, skipped_images)
+}
+
+/// `marker` before the first line; later lines are indented to line up
+/// with it, so a multi-line item stays one list entry.
+fn list_item(marker: &str, text: &str) -> String {
+    let indent = " ".repeat(marker.chars().count());
+    let mut item = String::from(marker);
+    for (index, line) in text.lines().enumerate() {
+        if index > 0 {
+            item.push('\n');
+            if !line.is_empty() {
+                item.push_str(&indent);
+            }
+        }
+        item.push_str(line);
+    }
+    item
+}
+
+/// RFC 4180 quoting: only fields with a comma, quote or line break are
+/// quoted, with embedded quotes doubled.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn render_template(template: &str, index: usize, content: &str, item: &MergeItem) -> String {
+    let mut rendered = String::with_capacity(template.len() + content.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let Some(close) = rest.find('}') else {
+            break;
+        };
+        match &rest[1..close] {
+            "index" => rendered.push_str(&index.to_string()),
+            "content" => rendered.push_str(content),
+            "source_app" => rendered.push_str(item.source_app.as_deref().unwrap_or_default()),
+            "timestamp" => rendered.push_str(&format_timestamp(item.timestamp)),
+            _ => {
+                // Not a field: keep the brace and look for one after it.
+                rendered.push('{');
+                rest = &rest[1..];
+                continue;
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Stacks the PNG images top to bottom, each centered on a transparent
+/// canvas as wide as the widest.
+pub fn stack_images(pngs: &[&[u8]]) -> Result<RgbaImage, String> {
+    let images = pngs
+        .iter()
+        .enumerate()
+        .map(|(index, png)| {
+            image::load_from_memory(png)
+                .map(|image| image.to_rgba8())
+                .map_err(|e| format!("Failed to decode image {} of the merge: {}", index + 1, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let width = images.iter().map(RgbaImage::width).max().unwrap_or(0);
+    let height: u64 = images.iter().map(|image| image.height() as u64).sum();
+    if width == 0 || height == 0 {
+        return Err("No image to stack".to_string());
+    }
+    if width > MAX_STACKED_DIMENSION || height > MAX_STACKED_DIMENSION as u64 {
+        return Err(format!(
+            "Stacked image would be {}x{}, over the {} pixel limit",
+            width, height, MAX_STACKED_DIMENSION
+        ));
+    }
+
+    let mut stacked = RgbaImage::new(width, height as u32);
+    let mut top = 0i64;
+    for image in &images {
+        let left = (width - image.width()) / 2;
+        imageops::overlay(&mut stacked, image, left as i64, top);
+        top += image.height() as i64;
+    }
+    Ok(stacked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgba};
+    use std::io::Cursor;
+
+    fn clip(content_type: ContentType, content: &[u8]) -> MergeItem {
+        MergeItem {
+            content_type,
+            content: content.to_vec(),
+            source_app: None,
+            timestamp: 0,
+        }
+    }
+
+    fn texts(texts: &[&str]) -> Vec<MergeItem> {
+        texts
+            .iter()
+            .map(|text| clip(ContentType::Text, text.as_bytes()))
+            .collect()
+    }
+
+    fn png(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        RgbaImage::from_pixel(width, height, Rgba(color))
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn merge_preserves_selection_order() {
+        let clips = [
+            clip(ContentType::Text, b"first"),
+            clip(ContentType::Text, b"second"),
+            clip(ContentType::Text, b"third"),
+        ];
+        let (merged, skipped) = merge_texts(&clips, &MergeFormat::Plain, "\n");
+        assert_eq!(merged, "first\nsecond\nthird");
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn merge_skips_and_counts_image_clips() {
+        let clips = [
+            clip(ContentType::Text, b"a"),
+            clip(ContentType::Image, b"\x89PNG-bytes"),
+            clip(ContentType::Text, b"b"),
+            clip(ContentType::Image, b"more-png"),
+        ];
+        let (merged, skipped) = merge_texts(&clips, &MergeFormat::Plain, "\n");
+        // Images are dropped from the merge; only text survives, in order.
+        assert_eq!(merged, "a\nb");
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn merge_uses_the_given_separator_verbatim() {
+        let clips = [clip(ContentType::Text, b"a"), clip(ContentType::Text, b"b")];
+        assert_eq!(merge_texts(&clips, &MergeFormat::Plain, "\n").0, "a\nb");
+        assert_eq!(merge_texts(&clips, &MergeFormat::Plain, "\t").0, "a\tb");
+        assert_eq!(merge_texts(&clips, &MergeFormat::Plain, "").0, "ab");
+    }
+
+    #[test]
+    fn merge_includes_files_paths_as_text() {
+        // Files store their absolute paths newline-joined (D3); they merge as
+        // that text, indistinguishable from a plain-text clip.
+        let clips = [
+            clip(ContentType::Files, b"/a/one.txt\n/a/two.txt"),
+            clip(ContentType::Text, b"tail"),
+        ];
+        let (merged, skipped) = merge_texts(&clips, &MergeFormat::Plain, "\n");
+        assert_eq!(merged, "/a/one.txt\n/a/two.txt\ntail");
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn merge_of_only_images_yields_empty_text_and_full_skip_count() {
+        let clips = [
+            clip(ContentType::Image, b"one"),
+            clip(ContentType::Image, b"two"),
+            clip(ContentType::Image, b"three"),
+        ];
+        let (merged, skipped) = merge_texts(&clips, &MergeFormat::Plain, "\n");
+        assert_eq!(merged, "");
+        assert_eq!(skipped, 3);
+    }
+
+    #[test]
+    fn lists_keep_multi_line_items_under_their_marker() {
+        let clips = texts(&["one", "two\n\nlines", "three"]);
+
+        assert_eq!(
+            "1. one\n2. two\n\n   lines\n3. three",
+            merge_texts(&clips, &MergeFormat::NumberedList, "ignored").0
+        );
+        assert_eq!(
+            "- one\n- two\n\n  lines\n- three",
+            merge_texts(&clips, &MergeFormat::MarkdownBullets, "ignored").0
+        );
+    }
+
+    #[test]
+    fn csv_and_json_quote_their_items() {
+        let clips = texts(&["plain", "a,b", "say \"hi\"", "two\nlines"]);
+
+        assert_eq!(
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\"",
+            merge_texts(&clips, &MergeFormat::CsvRow, "ignored").0
+        );
+        assert_eq!(
+            "plain\n\"a,b\"\n\"say \"\"hi\"\"\"\n\"two\nlines\"",
+            merge_texts(&clips, &MergeFormat::CsvColumn, "ignored").0
+        );
+        let json = merge_texts(&clips, &MergeFormat::JsonArray, "ignored").0;
+        assert_eq!(
+            vec!["plain", "a,b", "say \"hi\"", "two\nlines"],
+            serde_json::from_str::<Vec<String>>(&json).unwrap()
+        );
+    }
+
+    #[test]
+    fn template_fills_fields_once_and_keeps_other_braces() {
+        let mut clips = texts(&["{index} inside", "b"]);
+        clips[0].source_app = Some("Terminal".to_string());
+        clips[1].timestamp = 1_700_000_000;
+        let format = MergeFormat::Template {
+            template: "{index}) {content} [{source_app}] {json}".to_string(),
+        };
+
+        assert_eq!(
+            "1) {index} inside [Terminal] {json}\n2) b [] {json}",
+            merge_texts(&clips, &format, "\n").0
+        );
+
+        let stamped = MergeFormat::Template {
+            template: "{timestamp}".to_string(),
+        };
+        assert_eq!(
+            format_timestamp(1_700_000_000),
+            merge_texts(&clips[1..], &stamped, "").0
+        );
+        assert!(validate_merge_format(&MergeFormat::Template {
+            template: String::new()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn images_stack_top_to_bottom_centered() {
+        let red = png(4, 2, [255, 0, 0, 255]);
+        let blue = png(2, 3, [0, 0, 255, 255]);
+
+        let stacked = stack_images(&[&red, &blue]).unwrap();
+
+        assert_eq!((4, 5), stacked.dimensions());
+        assert_eq!(&Rgba([255, 0, 0, 255]), stacked.get_pixel(0, 1));
+        assert_eq!(&Rgba([0, 0, 255, 255]), stacked.get_pixel(1, 2));
+        assert_eq!(&Rgba([0, 0, 0, 0]), stacked.get_pixel(0, 4));
+        assert!(stack_images(&[b"not a png"]).is_err());
+    }
+}
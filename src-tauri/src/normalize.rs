@@ -0,0 +1,245 @@
+//! Opt-in cleanups applied to captured text before it is stored, so dedup
+//! and search see the cleaned text: a command copied from a colored
+//! terminal, or a link with tracking parameters, collapses into the clip a
+//! clean copy would have produced.
+//!
+//! Only the plain text is normalized. The html and rtf companions keep the
+//! source app's original rich form.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+/// CSI sequences (colors, cursor moves), OSC sequences (titles, hyperlinks)
+/// terminated by BEL or ST, and the remaining two-byte escapes.
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-Z\\-_]").unwrap()
+});
+
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"https?://[^\s<>"'`]+"#).unwrap());
+
+/// Query parameters that only identify where a click came from. Any `utm_`
+/// parameter is also dropped.
+const TRACKING_PARAMS: [&str; 10] = [
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid",
+    "mc_eid",
+];
+
+/// Zero-width space, word joiner and BOM. The zero-width (non-)joiners are
+/// kept: they are part of emoji sequences and of Persian and Indic text.
+const ZERO_WIDTH_CHARS: [char; 3] = ['\u{200B}', '\u{2060}', '\u{FEFF}'];
+
+/// Which normalizers run on captured text. All are off by default.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextNormalizers {
+    /// Strip trailing whitespace from every line and blank lines around the
+    /// text. Leading indentation is kept, so code stays intact.
+    pub trim_whitespace: bool,
+    pub strip_ansi_escapes: bool,
+    /// Drop `utm_*` and click-id parameters from http(s) links.
+    pub strip_tracking_params: bool,
+    /// Turn CRLF line endings into LF.
+    pub normalize_line_endings: bool,
+    pub strip_zero_width: bool,
+}
+
+/// `text` with every enabled normalizer applied, borrowed when nothing
+/// changed. Escapes go first, so the other steps see the visible text.
+pub fn normalize_text(text: &str, normalizers: TextNormalizers) -> Cow<'_, str> {
+    let mut text = Cow::Borrowed(text);
+    if normalizers.strip_ansi_escapes {
+        text = replace_owned(text, |text| ANSI_ESCAPE.replace_all(text, ""));
+    }
+    if normalizers.strip_zero_width && text.contains(ZERO_WIDTH_CHARS) {
+        text = Cow::Owned(text.replace(ZERO_WIDTH_CHARS, ""));
+    }
+    if normalizers.normalize_line_endings && text.contains("\r\n") {
+        text = Cow::Owned(text.replace("\r\n", "\n"));
+    }
+    if normalizers.strip_tracking_params {
+        text = replace_owned(text, strip_tracking_params_from_links);
+    }
+    if normalizers.trim_whitespace {
+        text = replace_owned(text, trim_whitespace);
+    }
+    text
+}
+
+/// Applies `step` to `text`, keeping the borrow when `step` changed nothing.
+fn replace_owned<'a>(
+    text: Cow<'a, str>,
+    step: impl for<'b> FnOnce(&'b str) -> Cow<'b, str>,
+) -> Cow<'a, str> {
+    match step(&text) {
+        Cow::Borrowed(_) => text,
+        Cow::Owned(changed) => Cow::Owned(changed),
+    }
+}
+
+fn trim_whitespace(text: &str) -> Cow<'_, str> {
+    let lines: Vec<String> = text.split('\n').map(trim_line_end).collect();
+    let is_blank = |line: &String| line.trim_end_matches('\r').is_empty();
+    let first = lines.iter().position(|line| !is_blank(line));
+    let last = lines.iter().rposition(|line| !is_blank(line));
+    let trimmed = match (first, last) {
+        (Some(first), Some(last)) => lines[first..=last]
+            .join("\n")
+            .trim_end_matches('\r')
+            .to_string(),
+        _ => String::new(),
+    };
+
+    if trimmed == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(trimmed)
+    }
+}
+
+/// Drops trailing spaces and tabs from one line. A CRLF line keeps its CR:
+/// line endings are `normalize_line_endings`' business.
+fn trim_line_end(line: &str) -> String {
+    match line.strip_suffix('\r') {
+        Some(body) => format!("{}\r", body.trim_end_matches([' ', '\t'])),
+        None => line.trim_end_matches([' ', '\t']).to_string(),
+    }
+}
+
+/// `text` with tracking parameters dropped from every http(s) link.
+/// `replace_all` with a closure allocates as soon as any link matches, so
+/// the result is compared to keep the borrow when no link changed.
+fn strip_tracking_params_from_links(text: &str) -> Cow<'_, str> {
+    let stripped = URL.replace_all(text, |caps: &regex::Captures| {
+        strip_tracking_params(&caps[0])
+    });
+    if stripped == text {
+        Cow::Borrowed(text)
+    } else {
+        stripped
+    }
+}
+
+/// `url` without tracking query parameters; the `?` goes too when nothing
+/// else was in the query. The fragment is kept.
+fn strip_tracking_params(url: &str) -> String {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = rest.split_once('?') else {
+        return url.to_string();
+    };
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default();
+            !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name)
+        })
+        .collect();
+
+    let mut stripped = base.to_string();
+    if !kept.is_empty() {
+        stripped.push('?');
+        stripped.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        stripped.push('#');
+        stripped.push_str(fragment);
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(configure: impl FnOnce(&mut TextNormalizers)) -> TextNormalizers {
+        let mut normalizers = TextNormalizers::default();
+        configure(&mut normalizers);
+        normalizers
+    }
+
+    #[test]
+    fn disabled_normalizers_borrow_the_text_unchanged() {
+        let text = "  \x1b[31mred\x1b[0m \r\nhttps://a.example/?utm_source=x\u{200B}  ";
+
+        assert!(matches!(
+            normalize_text(text, TextNormalizers::default()),
+            Cow::Borrowed(borrowed) if borrowed == text
+        ));
+        assert!(matches!(
+            normalize_text("clean", only(|n| n.trim_whitespace = true)),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn trim_keeps_indentation_but_drops_trailing_space_and_blank_edges() {
+        let normalizers = only(|n| n.trim_whitespace = true);
+
+        assert_eq!(
+            "    let x = 1;\n\n    let y = 2;",
+            normalize_text(
+                "\n  \n    let x = 1;   \n\n    let y = 2;\t\n\n",
+                normalizers
+            )
+        );
+        assert_eq!("a\r\nb", normalize_text("a  \r\nb \r\n \r\n", normalizers));
+    }
+
+    #[test]
+    fn ansi_escapes_are_stripped() {
+        let normalizers = only(|n| n.strip_ansi_escapes = true);
+
+        assert_eq!(
+            "error: build failed",
+            normalize_text("\x1b[1;31merror\x1b[0m: build failed", normalizers)
+        );
+        assert_eq!(
+            "docs",
+            normalize_text(
+                "\x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x07",
+                normalizers
+            )
+        );
+    }
+
+    #[test]
+    fn tracking_params_are_removed_from_links_only() {
+        let normalizers = only(|n| n.strip_tracking_params = true);
+
+        assert_eq!(
+            "see https://example.com/a?id=7#top and https://example.com/b",
+            normalize_text(
+                "see https://example.com/a?utm_source=x&id=7&fbclid=abc#top and https://example.com/b?utm_medium=mail",
+                normalizers
+            )
+        );
+        assert!(matches!(
+            normalize_text("utm_source=x is not a link", normalizers),
+            Cow::Borrowed(_)
+        ));
+        // A link with nothing to strip must not count as a change: capture
+        // drops the raw formats of changed text.
+        assert!(matches!(
+            normalize_text("see https://example.com/a?id=1", normalizers),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn line_endings_and_zero_width_chars_are_normalized() {
+        let normalizers = only(|n| {
+            n.normalize_line_endings = true;
+            n.strip_zero_width = true;
+        });
+
+        assert_eq!(
+            "a\nb\nc\u{200D}d",
+            normalize_text("a\r\n\u{FEFF}b\u{200B}\r\nc\u{200D}d", normalizers)
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use std::{
     borrow::Cow,
-    sync::{mpsc, Arc, Mutex},
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
     thread,
     time::Duration,
 };
@@ -9,21 +13,28 @@ use arboard::{Clipboard, ImageData};
 use chrono::Utc;
 use enigo::{
     Direction::{Click, Press, Release},
-    Enigo, Key, Keyboard, Settings as EnigoSettings,
+    Enigo, Keyboard, Settings as EnigoSettings,
 };
-use image::GenericImageView;
+use image::{GenericImageView, RgbaImage};
+use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 
 use crate::{
+    clipboard::ClipboardSnapshot,
+    merge::{merge_texts, stack_images, validate_merge_format, MergeFormat, MergeItem},
+    paste_keys::{resolve_paste_keys, PasteKeys},
     safe_lock,
     storage::{
-        join_file_paths, split_file_paths, ClipItem, ContentType, CopyMarker, FrontendClipItem,
+        join_file_paths, split_file_paths, ClipFormat, ClipItem, ContentType, CopyMarker,
+        FrontendClipItem,
     },
     tray::update_tray_menu,
+    type_paste::{Keystroke, TypingOutcome, TypingSession},
     AppState,
 };
 
 const COPY_MARKER_TTL: Duration = Duration::from_secs(2);
+pub const PASTE_STACK_CHANGED_EVENT: &str = "paste-stack-changed";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum PasteMode {
@@ -31,6 +42,8 @@ enum PasteMode {
     Opposite,
     Paste,
     Copy,
+    /// Type a text clip out as keystrokes, bypassing the clipboard.
+    Type,
 }
 
 impl TryFrom<&str> for PasteMode {
@@ -42,18 +55,102 @@ impl TryFrom<&str> for PasteMode {
             "opposite" => Ok(Self::Opposite),
             "paste" => Ok(Self::Paste),
             "copy" => Ok(Self::Copy),
+            "type" => Ok(Self::Type),
             _ => Err(format!(
-                "Invalid paste mode '{mode}'. Expected 'default', 'opposite', 'paste', or 'copy'."
+                "Invalid paste mode '{mode}'. Expected 'default', 'opposite', 'paste', 'copy', or 'type'."
             )),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What a paste request did, reported to the frontend.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum PasteOutcome {
+    Pasted,
+    /// Copied without pasting, as the mode and `auto_paste` asked.
+    Copied,
+    /// Typed out as keystrokes (`PasteMode::Type`); `cancelled` when the
+    /// cancel shortcut stopped it part-way.
+    Typed {
+        cancelled: bool,
+    },
+    /// A paste was asked for but could not be simulated in this session. The
+    /// clip is on the clipboard for the user to paste by hand, unless it was
+    /// to be typed: typing never touches the clipboard.
+    PasteUnsupported {
+        reason: PasteUnsupportedReason,
+    },
+}
+
+impl PasteOutcome {
+    /// Whether the clip reached the target, or the clipboard when only a
+    /// copy was asked for; only these pastes count in the statistics.
+    fn delivered(self) -> bool {
+        matches!(
+            self,
+            PasteOutcome::Pasted | PasteOutcome::Copied | PasteOutcome::Typed { cancelled: false }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub enum PasteUnsupportedReason {
+    /// Accessibility permission is missing (macOS).
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    AccessibilityPermissionMissing,
+    /// Neither `WAYLAND_DISPLAY` nor `DISPLAY` is set.
+    NoDisplay,
+    /// The Wayland compositor offers no virtual keyboard and `/dev/uinput`
+    /// is not writable.
+    WaylandInputUnavailable,
+    /// The chosen input path failed while pressing the shortcut.
+    InputFailed,
+    /// The session can press the paste shortcut but not type arbitrary
+    /// text (Wayland).
+    TypingUnavailable,
+}
+
+/// How this session simulates the paste shortcut.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub enum PasteBackend {
+    /// enigo's platform backend (CGEvent, SendInput).
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    Native,
+    /// enigo through XTest.
+    X11,
+    /// The Wayland `zwp_virtual_keyboard_v1` protocol.
+    VirtualKeyboard,
+    /// A uinput keyboard device.
+    Uinput,
+}
+
+/// Whether auto-paste can work in this session, for the settings page.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum PasteSupport {
+    Supported { backend: PasteBackend },
+    Unsupported { reason: PasteUnsupportedReason },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(target_os = "windows", allow(dead_code))]
 enum PasteSimulation {
     Pasted,
-    CopiedOnly,
+    Unsupported(PasteUnsupportedReason),
+}
+
+impl From<PasteSimulation> for PasteOutcome {
+    fn from(simulation: PasteSimulation) -> Self {
+        match simulation {
+            PasteSimulation::Pasted => PasteOutcome::Pasted,
+            PasteSimulation::Unsupported(reason) => PasteOutcome::PasteUnsupported { reason },
+        }
+    }
 }
 
 pub async fn paste_clip(
@@ -62,29 +159,63 @@ pub async fn paste_clip(
     id: String,
     mode: String,
     plain: bool,
-) -> Result<(), String> {
+) -> Result<PasteOutcome, String> {
+    let outcome = deliver_clip(app, state, id.clone(), mode, plain).await?;
+    if outcome.delivered() {
+        record_pastes(state, &[id]);
+    }
+    Ok(outcome)
+}
+
+async fn deliver_clip(
+    app: AppHandle,
+    state: &AppState,
+    id: String,
+    mode: String,
+    plain: bool,
+) -> Result<PasteOutcome, String> {
     let mode = PasteMode::try_from(mode.as_str())?;
     let item = fetch_clip_and_touch_timestamp(&app, state, id).await?;
-    let auto_paste = state.settings.get().auto_paste;
+    let item = safe_lock(&state.plugins).clone().transform_paste(item);
+    if mode == PasteMode::Type {
+        if item.content_type != ContentType::Text {
+            return Err("Only text clips can be typed".to_string());
+        }
+        let outcome = type_out(&app, state, &String::from_utf8_lossy(&item.content)).await?;
+        log::info!("Type-out of clip {} ended: {:?}", item.id, outcome);
+        return Ok(outcome);
+    }
+    let settings = state.settings.get();
+    let simulate = should_simulate_paste(mode, settings.auto_paste);
+    let previous =
+        take_previous_clipboard(state, simulate && settings.restore_clipboard_after_paste);
 
     write_clip_to_system_clipboard(&item, state.last_copied_by_us.clone(), plain, &app)?;
     hide_quickbar(&app)?;
 
-    if should_simulate_paste(mode, auto_paste) {
-        match simulate_paste(&app, state).await? {
-            PasteSimulation::Pasted => log::info!("Pasted clip {}", item.id),
-            PasteSimulation::CopiedOnly => {
-                log::warn!(
-                    "Paste simulation unavailable; clip {} was copied only",
-                    item.id
-                );
-            }
-        }
-    } else {
+    if !simulate {
         log::info!("Copied clip {} without paste simulation", item.id);
+        return Ok(PasteOutcome::Copied);
     }
 
-    Ok(())
+    let simulation = simulate_paste(&app, state).await?;
+    match simulation {
+        PasteSimulation::Pasted => log::info!("Pasted clip {}", item.id),
+        PasteSimulation::Unsupported(reason) => {
+            log::warn!(
+                "Paste unsupported ({:?}); clip {} was copied only",
+                reason,
+                item.id
+            );
+        }
+    }
+    restore_after_paste(
+        state,
+        simulation,
+        previous,
+        settings.restore_clipboard_delay_ms,
+    );
+    Ok(simulation.into())
 }
 
 /// Merge several clips into one clipboard write, then paste per `mode` (task #13).
@@ -92,20 +223,41 @@ pub async fn paste_clip(
 /// Clips are taken in the caller's `ids` order; each is touched (timestamp +
 /// emit) through the same helper `paste_clip` uses, so the recent list stays in
 /// sync. Text/Files contribute their plain text (Files → newline-joined paths,
-/// D3); Image clips have no text form in v1 and are skipped + counted. The
-/// merged text is written through the shared self-copy marker + TTL path (D5).
+/// D3), laid out per `format` (the `merge_format` setting when `None`). Image
+/// clips have no text form and are skipped + counted, except that with
+/// `merge_images` an all-image selection is stacked into one image. The merged
+/// clip is written through the shared self-copy marker + TTL path (D5).
 pub async fn paste_clips(
     app: AppHandle,
     state: &AppState,
     ids: Vec<String>,
     mode: String,
     separator: String,
-) -> Result<(), String> {
+    format: Option<MergeFormat>,
+) -> Result<PasteOutcome, String> {
+    let outcome = deliver_merged_clips(app, state, &ids, mode, separator, format).await?;
+    if outcome.delivered() {
+        record_pastes(state, &ids);
+    }
+    Ok(outcome)
+}
+
+async fn deliver_merged_clips(
+    app: AppHandle,
+    state: &AppState,
+    ids: &[String],
+    mode: String,
+    separator: String,
+    format: Option<MergeFormat>,
+) -> Result<PasteOutcome, String> {
     let mode = PasteMode::try_from(mode.as_str())?;
     if ids.is_empty() {
         return Err("No clips selected for merge paste".to_string());
     }
-    let auto_paste = state.settings.get().auto_paste;
+    let settings = state.settings.get();
+    let format = format.unwrap_or_else(|| settings.merge_format.clone());
+    validate_merge_format(&format)?;
+    let simulate = should_simulate_paste(mode, settings.auto_paste);
 
     // Touch every selected clip's timestamp in one transaction and fetch each
     // clip's content in selection order (task #13). Unlike paste_clip's
@@ -115,63 +267,65 @@ pub async fn paste_clips(
     // emit + one tray rebuild here (#38). This runs before the merge/write (as
     // the per-item touch did), so an all-images selection still surfaces the
     // moved-up clips and refreshes the tray before the early return below.
-    let (fetched, touched_preview) = fetch_clips_and_touch_batch(state, &ids).await?;
+    let (fetched, touched_preview) =
+        fetch_clips_and_touch_batch(state, ids, settings.merge_images).await?;
     if let Err(e) = app.emit("clipboard-changed", &touched_preview) {
         log::error!("Failed to emit clipboard-changed event: {}", e);
     }
     update_tray_menu(&app);
 
-    let (merged, skipped_images) = merge_clip_texts(&fetched, &separator);
-    let merged_count = fetched.len() - skipped_images;
-
-    if skipped_images > 0 {
-        log::info!("Merge paste skipped {skipped_images} image clip(s) (unsupported in v1)");
-    }
+    let all_images = fetched
+        .iter()
+        .all(|item| item.content_type == ContentType::Image);
+    let (stacked, merged, merged_count) = if settings.merge_images && all_images {
+        let pngs: Vec<&[u8]> = fetched.iter().map(|item| item.content.as_slice()).collect();
+        (Some(stack_images(&pngs)?), String::new(), fetched.len())
+    } else {
+        let (merged, skipped_images) = merge_texts(&fetched, &format, &separator);
+        if skipped_images > 0 {
+            log::info!("Merge paste skipped {skipped_images} image clip(s) with no text form");
+        }
+        if skipped_images == fetched.len() {
+            // Every selected clip was an image and stacking is off: nothing
+            // is mergeable. Return an error so the caller surfaces a
+            // paste-failure toast instead of the panel silently staying put
+            // with no feedback (#14); the clipboard is deliberately left
+            // untouched.
+            log::warn!("Merge paste had no text/files clips to merge; skipping clipboard write");
+            return Err("Merge paste had no text or file clips to merge".to_string());
+        }
+        (None, merged, fetched.len() - skipped_images)
+    };
 
-    if merged_count == 0 {
-        // Every selected clip was an image (no text form in v1): nothing is
-        // mergeable. Return an error so the caller surfaces a paste-failure
-        // toast instead of the panel silently staying put with no feedback
-        // (#14); the clipboard is deliberately left untouched.
-        log::warn!("Merge paste had no text/files clips to merge; skipping clipboard write");
-        return Err("Merge paste had no text or file clips to merge".to_string());
+    let previous =
+        take_previous_clipboard(state, simulate && settings.restore_clipboard_after_paste);
+    match stacked {
+        Some(image) => {
+            write_merged_image_to_system_clipboard(image, state.last_copied_by_us.clone())?
+        }
+        None => write_merged_text_to_system_clipboard(&merged, state.last_copied_by_us.clone())?,
     }
-
-    write_merged_text_to_system_clipboard(&merged, state.last_copied_by_us.clone())?;
     hide_quickbar(&app)?;
 
-    if should_simulate_paste(mode, auto_paste) {
-        match simulate_paste(&app, state).await? {
-            PasteSimulation::Pasted => log::info!("Merge-pasted {merged_count} clip(s)"),
-            PasteSimulation::CopiedOnly => {
-                log::warn!(
-                    "Paste simulation unavailable; merged {merged_count} clip(s) copied only"
-                );
-            }
-        }
-    } else {
+    if !simulate {
         log::info!("Merged {merged_count} clip(s) to clipboard without paste simulation");
+        return Ok(PasteOutcome::Copied);
     }
 
-    Ok(())
-}
-
-/// Join the plain-text form of clips (in order) with `separator`, skipping and
-/// counting Image clips (v1 has no text form for them). Text uses its bytes;
-/// Files use their newline-joined path text (D3). Pure so the merge order,
-/// image-skip, and separator behaviors are unit-testable without a clipboard.
-fn merge_clip_texts(clips: &[(ContentType, Vec<u8>)], separator: &str) -> (String, usize) {
-    let mut parts: Vec<String> = Vec::new();
-    let mut skipped_images = 0usize;
-    for (content_type, content) in clips {
-        match content_type {
-            ContentType::Image => skipped_images += 1,
-            ContentType::Text | ContentType::Files => {
-                parts.push(String::from_utf8_lossy(content).into_owned());
-            }
+    let simulation = simulate_paste(&app, state).await?;
+    match simulation {
+        PasteSimulation::Pasted => log::info!("Merge-pasted {merged_count} clip(s)"),
+        PasteSimulation::Unsupported(reason) => {
+            log::warn!("Paste unsupported ({reason:?}); merged {merged_count} clip(s) copied only");
         }
     }
-    (parts.join(separator), skipped_images)
+    restore_after_paste(
+        state,
+        simulation,
+        previous,
+        settings.restore_clipboard_delay_ms,
+    );
+    Ok(simulation.into())
 }
 
 /// Write merged plain text to the clipboard using the same self-copy marker +
@@ -196,6 +350,32 @@ fn write_merged_text_to_system_clipboard(
     Ok(())
 }
 
+/// Write the stacked image of an all-image merge, with the same marker
+/// derivation as a single image clip so the monitor recognizes it (D5).
+fn write_merged_image_to_system_clipboard(
+    image: RgbaImage,
+    marker_state: Arc<Mutex<Option<CopyMarker>>>,
+) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    let (width, height) = image.dimensions();
+    let rgba_bytes = image.into_raw();
+    let marker =
+        CopyMarker::from_normalized_image_parts(width as usize, height as usize, &rgba_bytes);
+
+    write_with_marker(marker_state, marker, || {
+        clipboard
+            .set_image(ImageData {
+                width: width as usize,
+                height: height as usize,
+                bytes: Cow::Owned(rgba_bytes),
+            })
+            .map_err(|e| format!("Failed to write merged image clipboard: {e}"))
+    })?;
+
+    log::info!("Copied stacked merge image to clipboard: {width}x{height}");
+    Ok(())
+}
+
 pub async fn fetch_clip_and_touch_timestamp(
     app: &AppHandle,
     state: &AppState,
@@ -231,17 +411,28 @@ pub async fn fetch_clip_and_touch_timestamp(
     Ok(item)
 }
 
+/// Records a delivered paste of each clip for `get_statistics`. Failures are
+/// only logged: statistics must not fail a paste.
+fn record_pastes(state: &AppState, ids: &[String]) {
+    let result = safe_lock(&state.storage).record_pastes(ids, Utc::now().timestamp());
+    if let Err(e) = result {
+        log::warn!("Failed to record paste: {}", e);
+    }
+}
+
 /// Touch every clip in `ids` (same timestamp, one transaction) and return each
-/// clip's `(content_type, content)` in selection order, plus a preview of the
-/// last touched clip. Image bytes are dropped (the merge skips images, so
-/// holding every selected image only to discard it wastes memory — #44); the
-/// preview still renders images from their thumbnail. Unlike
+/// clip as a [`MergeItem`] in selection order (with its capture timestamp, for
+/// merge templates), plus a preview of the last touched clip. Image bytes are
+/// dropped unless `keep_images` (a text merge skips images, so holding every
+/// selected image only to discard it wastes memory — #44); the preview still
+/// renders images from their thumbnail. Unlike
 /// `fetch_clip_and_touch_timestamp` this emits nothing and does not rebuild the
 /// tray — the merge-paste caller does both once for the whole batch (#38).
 async fn fetch_clips_and_touch_batch(
     state: &AppState,
     ids: &[String],
-) -> Result<(Vec<(ContentType, Vec<u8>)>, FrontendClipItem), String> {
+    keep_images: bool,
+) -> Result<(Vec<MergeItem>, FrontendClipItem), String> {
     let storage = state.storage.clone();
     let ids = ids.to_vec();
     let new_timestamp = Utc::now().timestamp();
@@ -262,25 +453,29 @@ async fn fetch_clips_and_touch_batch(
             .touch_timestamps(&ids, new_timestamp)
             .map_err(|e| e.to_string())?;
 
-        // Reflect the touch in the returned copies and build the emit preview
-        // from the last touched clip (all share `new_timestamp`, so a later full
-        // reload surfaces the whole batch at the top; this live event lifts the
-        // last one).
-        for item in &mut items {
-            item.timestamp = new_timestamp;
-        }
-        let last = items.last().ok_or("No clips fetched for merge paste")?;
+        // Build the emit preview from the last clip as touched (all share
+        // `new_timestamp`, so a later full reload surfaces the whole batch at
+        // the top; this live event lifts the last one).
+        let last = items.last_mut().ok_or("No clips fetched for merge paste")?;
+        let captured_at = last.timestamp;
+        last.timestamp = new_timestamp;
         let touched_preview =
             FrontendClipItem::from_preview(crate::storage::ClipPreviewItem::from_clip_item(last));
+        last.timestamp = captured_at;
 
         let fetched = items
             .into_iter()
             .map(|item| {
                 let content = match item.content_type {
-                    ContentType::Image => Vec::new(),
-                    ContentType::Text | ContentType::Files => item.content,
+                    ContentType::Image if !keep_images => Vec::new(),
+                    _ => item.content,
                 };
-                (item.content_type, content)
+                MergeItem {
+                    content_type: item.content_type,
+                    content,
+                    source_app: item.source_app,
+                    timestamp: item.timestamp,
+                }
             })
             .collect();
 
@@ -294,7 +489,7 @@ fn should_simulate_paste(mode: PasteMode, auto_paste: bool) -> bool {
     match mode {
         PasteMode::Default | PasteMode::Paste => auto_paste,
         PasteMode::Opposite => !auto_paste,
-        PasteMode::Copy => false,
+        PasteMode::Copy | PasteMode::Type => false,
     }
 }
 
@@ -304,6 +499,16 @@ pub fn write_clip_to_system_clipboard(
     plain_text_only: bool,
     app: &AppHandle,
 ) -> Result<(), String> {
+    if !plain_text_only && should_restore_all_formats(item) {
+        match write_all_formats(item, marker_state.clone()) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::warn!(
+                "Full-fidelity restore failed for clip {} ({e}); writing representative format",
+                item.id
+            ),
+        }
+    }
+
     let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
     match &item.content_type {
         ContentType::Text => write_text(&mut clipboard, item, marker_state, plain_text_only)?,
@@ -314,6 +519,51 @@ pub fn write_clip_to_system_clipboard(
     Ok(())
 }
 
+/// Full-fidelity clips restore every stored format together, so a spreadsheet
+/// paste keeps its cell image and a rich editor keeps its own types. Files
+/// always go through the D3 path-list write; plain-text paste (⌥Enter)
+/// bypasses this entirely.
+fn should_restore_all_formats(item: &ClipItem) -> bool {
+    !item.formats.is_empty()
+        && item.content_type != ContentType::Files
+        && crate::clipboard_formats::is_supported()
+}
+
+fn write_all_formats(
+    item: &ClipItem,
+    marker_state: Arc<Mutex<Option<CopyMarker>>>,
+) -> Result<(), String> {
+    // The marker still hashes only the representative content (D5): the
+    // monitor reads back a snapshot of the restored formats and picks the
+    // same representative format out of them.
+    let marker = match item.content_type {
+        ContentType::Image => {
+            let img = image::load_from_memory(&item.content)
+                .map_err(|e| format!("Failed to decode image clip {}: {e}", item.id))?;
+            let (width, height) = img.dimensions();
+            CopyMarker::from_normalized_image_parts(
+                width as usize,
+                height as usize,
+                &img.to_rgba8().into_raw(),
+            )
+        }
+        ContentType::Text | ContentType::Files => {
+            CopyMarker::from_payload(item.content_type.clone(), &item.content)
+        }
+    };
+
+    write_with_marker(marker_state, marker, || {
+        crate::clipboard_formats::write_all_formats(&item.formats)
+    })?;
+
+    log::info!(
+        "Restored clip {} to clipboard with {} format(s)",
+        item.id,
+        item.formats.len()
+    );
+    Ok(())
+}
+
 fn write_text(
     clipboard: &mut Clipboard,
     item: &ClipItem,
@@ -326,7 +576,18 @@ fn write_text(
     let marker = CopyMarker::from_payload(ContentType::Text, text.as_bytes());
 
     let use_html = !plain_text_only && item.html.as_deref().is_some_and(|html| !html.is_empty());
+    // arboard cannot write RTF, so a clip that carries it goes through the
+    // native writer, which places rtf, html and plain text in one go.
+    let rtf = item
+        .rtf
+        .as_deref()
+        .filter(|_| !plain_text_only && crate::clipboard_formats::is_supported());
     write_with_marker(marker_state, marker, || {
+        if let Some(rtf) = rtf {
+            let html = item.html.as_deref().filter(|_| use_html);
+            return crate::clipboard_formats::write_text_formats(&text, html, rtf);
+        }
+
         if use_html {
             // Place html plus the plain-text alt; ⌥Enter (plain=true) forces text.
             let html = item.html.as_deref().unwrap_or_default();
@@ -338,10 +599,11 @@ fn write_text(
     })?;
 
     log::info!(
-        "Copied text clip {} to clipboard: {} chars (html: {})",
+        "Copied text clip {} to clipboard: {} chars (html: {}, rtf: {})",
         item.id,
         text.len(),
-        use_html
+        use_html,
+        rtf.is_some()
     );
     Ok(())
 }
@@ -584,11 +846,326 @@ fn schedule_marker_clear(marker_state: Arc<Mutex<Option<CopyMarker>>>, marker: C
     });
 }
 
+/// What the clipboard held before a paste wrote over it.
+struct PreviousClipboard {
+    /// `None` when the clipboard was empty; restoring then clears it.
+    snapshot: Option<ClipboardSnapshot>,
+    /// Every native format, where the platform can write them back together.
+    formats: Vec<ClipFormat>,
+}
+
+/// The previous clipboard of a paste whose restore delay is running. A paste
+/// made meanwhile takes it over, so back-to-back pastes still restore what
+/// the user had rather than the first pasted clip. Each change bumps a
+/// generation so a superseded restore thread exits.
+#[derive(Default)]
+pub struct ClipboardRestore {
+    pending: Mutex<Option<PreviousClipboard>>,
+    generation: AtomicU64,
+}
+
+impl ClipboardRestore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn take_pending(&self) -> Option<PreviousClipboard> {
+        let mut pending = safe_lock(&self.pending);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        pending.take()
+    }
+
+    fn arm(&self, previous: PreviousClipboard) -> u64 {
+        let mut pending = safe_lock(&self.pending);
+        *pending = Some(previous);
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Runs `restore` on the pending clipboard unless a later paste took it
+    /// over. The lock is held throughout, so a paste starting meanwhile reads
+    /// the clipboard only once it is restored.
+    fn finish(&self, generation: u64, restore: impl FnOnce(PreviousClipboard)) {
+        let mut pending = safe_lock(&self.pending);
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Some(previous) = pending.take() {
+            restore(previous);
+        }
+    }
+}
+
+/// Clips queued from the QuickBar for the paste-stack shortcut, which pastes
+/// them in the order they were added. Each change tells the QuickBar how
+/// many are left.
+#[derive(Default)]
+pub struct PasteStack {
+    ids: Mutex<VecDeque<String>>,
+}
+
+impl PasteStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, app: &AppHandle, ids: Vec<String>) -> usize {
+        let mut queued = safe_lock(&self.ids);
+        queued.extend(ids);
+        emit_paste_stack_changed(app, queued.len());
+        queued.len()
+    }
+
+    pub fn pop_next(&self, app: &AppHandle) -> Option<String> {
+        let mut queued = safe_lock(&self.ids);
+        let next = queued.pop_front()?;
+        emit_paste_stack_changed(app, queued.len());
+        Some(next)
+    }
+
+    pub fn clear(&self, app: &AppHandle) {
+        safe_lock(&self.ids).clear();
+        emit_paste_stack_changed(app, 0);
+    }
+
+    pub fn count(&self) -> usize {
+        safe_lock(&self.ids).len()
+    }
+}
+
+fn emit_paste_stack_changed(app: &AppHandle, count: usize) {
+    if let Err(e) = app.emit(PASTE_STACK_CHANGED_EVENT, count) {
+        log::warn!("Failed to emit {}: {}", PASTE_STACK_CHANGED_EVENT, e);
+    }
+}
+
+/// The clipboard to put back after this paste, when `wanted`: a restore
+/// still pending from an earlier paste, or else the clipboard as it is now.
+/// `None` when it cannot be read, so an unreadable clipboard is never
+/// "restored" by clearing it.
+fn take_previous_clipboard(state: &AppState, wanted: bool) -> Option<PreviousClipboard> {
+    let pending = state.clipboard_restore.take_pending();
+    if !wanted {
+        return None;
+    }
+    if pending.is_some() {
+        return pending;
+    }
+
+    match crate::clipboard::read_current_snapshot() {
+        Ok(snapshot) => {
+            let formats = match &snapshot {
+                Some(ClipboardSnapshot::Files(_)) | None => Vec::new(),
+                Some(_) if crate::clipboard_formats::is_supported() => {
+                    crate::clipboard_formats::read_all_formats()
+                }
+                Some(_) => Vec::new(),
+            };
+            Some(PreviousClipboard { snapshot, formats })
+        }
+        Err(e) => {
+            log::warn!("Cannot read the clipboard to restore after paste: {}", e);
+            None
+        }
+    }
+}
+
+/// After a successful simulated paste, put `previous` back once `delay_ms`
+/// has passed. The target app may read the clipboard lazily after the
+/// shortcut, hence the delay; if the clipboard no longer holds the pasted
+/// clip by then (the user copied something else), it is left alone. An
+/// unsupported paste keeps the clip on the clipboard for a manual paste.
+fn restore_after_paste(
+    state: &AppState,
+    simulation: PasteSimulation,
+    previous: Option<PreviousClipboard>,
+    delay_ms: u64,
+) {
+    let (PasteSimulation::Pasted, Some(previous)) = (simulation, previous) else {
+        return;
+    };
+
+    let pasted = safe_lock(&state.last_copied_by_us).clone();
+    let restore = state.clipboard_restore.clone();
+    let marker_state = state.last_copied_by_us.clone();
+    let generation = restore.arm(previous);
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(delay_ms));
+        restore.finish(generation, |previous| {
+            if pasted.is_none() || crate::clipboard::current_clipboard_marker() != pasted {
+                log::info!("Clipboard changed since the paste; not restoring the previous one");
+                return;
+            }
+            match write_previous_clipboard(previous, marker_state) {
+                Ok(()) => log::info!("Restored the clipboard from before the paste"),
+                Err(e) => log::warn!("Failed to restore the clipboard after paste: {}", e),
+            }
+        });
+    });
+}
+
+/// Writes `previous` back under a self-copy marker computed the way the
+/// monitor will read it, so the restored content is not captured again.
+fn write_previous_clipboard(
+    previous: PreviousClipboard,
+    marker_state: Arc<Mutex<Option<CopyMarker>>>,
+) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    let Some(snapshot) = previous.snapshot else {
+        return clipboard
+            .clear()
+            .map_err(|e| format!("Failed to clear clipboard: {e}"));
+    };
+
+    // The monitor reads a file list back the way our write resolves it.
+    let snapshot = match snapshot {
+        ClipboardSnapshot::Files(paths) => ClipboardSnapshot::Files(effective_file_paths(paths)),
+        other => other,
+    };
+    let marker = crate::clipboard::snapshot_marker(&snapshot);
+    write_with_marker(marker_state, marker, || {
+        if !previous.formats.is_empty() {
+            return crate::clipboard_formats::write_all_formats(&previous.formats);
+        }
+        match snapshot {
+            ClipboardSnapshot::Files(paths) => write_file_list(&mut clipboard, &paths),
+            ClipboardSnapshot::Text {
+                text,
+                rtf: Some(rtf),
+                html,
+            } if crate::clipboard_formats::is_supported() => {
+                crate::clipboard_formats::write_text_formats(&text, html.as_deref(), &rtf)
+            }
+            ClipboardSnapshot::Text {
+                text,
+                html: Some(html),
+                ..
+            } => clipboard
+                .set()
+                .html(html, Some(text))
+                .map_err(|e| format!("Failed to write text clipboard: {e}")),
+            ClipboardSnapshot::Text { text, .. } => clipboard
+                .set_text(text)
+                .map_err(|e| format!("Failed to write text clipboard: {e}")),
+            ClipboardSnapshot::Image(image) => clipboard
+                .set_image(image)
+                .map_err(|e| format!("Failed to write image clipboard: {e}")),
+        }
+    })
+}
+
+/// Types `text` into the app the QuickBar was opened over, with the cancel
+/// shortcut armed for the duration. The clipboard is left untouched.
+async fn type_out(app: &AppHandle, state: &AppState, text: &str) -> Result<PasteOutcome, String> {
+    let settings = state.settings.get();
+    crate::type_paste::check_length(text, settings.type_paste_max_chars)?;
+    let strokes = crate::type_paste::keystrokes(text);
+
+    // Hide first: the cancel shortcut would otherwise swallow the QuickBar's
+    // own Escape.
+    hide_quickbar(app)?;
+    let session = TypingSession::start(app, &settings.type_paste_cancel_shortcut)?;
+    let delay = Duration::from_millis(settings.type_paste_char_delay_ms);
+    let outcome = type_into_target(app, state, strokes, delay, session.cancel_flag()).await;
+    drop(session);
+    outcome
+}
+
+fn typed_outcome(outcome: TypingOutcome) -> PasteOutcome {
+    PasteOutcome::Typed {
+        cancelled: outcome == TypingOutcome::Cancelled,
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn type_into_target(
+    app: &AppHandle,
+    state: &AppState,
+    strokes: Vec<Keystroke>,
+    delay: Duration,
+    cancelled: Arc<AtomicBool>,
+) -> Result<PasteOutcome, String> {
+    let app = app.clone();
+    let foreground_store = state.quickbar_foreground_window.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(reason) = prepare_input_target(&app, &foreground_store) {
+            return Ok(PasteOutcome::PasteUnsupported { reason });
+        }
+        crate::type_paste::type_keystrokes(&strokes, delay, &cancelled).map(typed_outcome)
+    })
+    .await
+    .map_err(|e| format!("Typing task failed: {e}"))?
+}
+
+#[cfg(target_os = "windows")]
+async fn type_into_target(
+    _app: &AppHandle,
+    state: &AppState,
+    strokes: Vec<Keystroke>,
+    delay: Duration,
+    cancelled: Arc<AtomicBool>,
+) -> Result<PasteOutcome, String> {
+    crate::window::restore_recorded_foreground_window(&state.quickbar_foreground_window)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::type_paste::type_keystrokes(&strokes, delay, &cancelled).map(typed_outcome)
+    })
+    .await
+    .map_err(|e| format!("Typing task failed: {e}"))?
+}
+
+#[cfg(target_os = "linux")]
+async fn type_into_target(
+    _app: &AppHandle,
+    state: &AppState,
+    strokes: Vec<Keystroke>,
+    delay: Duration,
+    cancelled: Arc<AtomicBool>,
+) -> Result<PasteOutcome, String> {
+    let foreground_store = state.quickbar_foreground_window.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        // Only XTest can type arbitrary characters; the Wayland paths carry a
+        // fixed Ctrl+V keymap.
+        let reason = match crate::linux_paste::probe() {
+            Ok(PasteBackend::X11) => {
+                refocus_x11_target(&foreground_store);
+                return crate::type_paste::type_keystrokes(&strokes, delay, &cancelled)
+                    .map(typed_outcome);
+            }
+            Ok(_) => PasteUnsupportedReason::TypingUnavailable,
+            Err(reason) => reason,
+        };
+        log::warn!("Cannot type out the clip in this session: {:?}", reason);
+        Ok(PasteOutcome::PasteUnsupported { reason })
+    })
+    .await
+    .map_err(|e| format!("Typing task failed: {e}"))?
+}
+
+/// Gives focus back to the X11 window the QuickBar was opened over. Failing
+/// that, the keys go wherever the window manager moved focus, as before.
+#[cfg(target_os = "linux")]
+fn refocus_x11_target(foreground_store: &crate::window::ForegroundWindowStore) {
+    if let Err(e) = crate::window::restore_recorded_foreground_window(foreground_store) {
+        log::warn!("Could not reactivate the previous X11 window: {}", e);
+    }
+}
+
 fn hide_quickbar(app: &AppHandle) -> Result<(), String> {
     crate::window::hide_quickbar(app)
         .map_err(|e| format!("Failed to hide QuickBar before paste: {e}"))
 }
 
+/// The keys that paste into the app the QuickBar was opened over, per the
+/// paste key profiles.
+fn target_paste_keys(state: &AppState) -> PasteKeys {
+    let app_name = safe_lock(&state.quickbar_foreground_window)
+        .as_ref()
+        .and_then(|window| window.app_name().map(str::to_string));
+    resolve_paste_keys(
+        &state.settings.get().paste_key_profiles,
+        app_name.as_deref(),
+    )
+}
+
 #[cfg(target_os = "macos")]
 async fn simulate_paste(app: &AppHandle, state: &AppState) -> Result<PasteSimulation, String> {
     // The body blocks: it waits (up to 5s) on a main-thread round-trip to bring
@@ -598,16 +1175,36 @@ async fn simulate_paste(app: &AppHandle, state: &AppState) -> Result<PasteSimula
     // from `state`, so clone that Arc rather than borrowing `AppState`.
     let app = app.clone();
     let foreground_store = state.quickbar_foreground_window.clone();
-    tauri::async_runtime::spawn_blocking(move || simulate_paste_blocking(&app, &foreground_store))
-        .await
-        .map_err(|e| format!("Paste simulation task failed: {e}"))?
+    let keys = target_paste_keys(state);
+    tauri::async_runtime::spawn_blocking(move || {
+        simulate_paste_blocking(&app, &foreground_store, &keys)
+    })
+    .await
+    .map_err(|e| format!("Paste simulation task failed: {e}"))?
 }
 
 #[cfg(target_os = "macos")]
 fn simulate_paste_blocking(
     app: &AppHandle,
     foreground_store: &crate::window::ForegroundWindowStore,
+    keys: &PasteKeys,
 ) -> Result<PasteSimulation, String> {
+    if let Err(reason) = prepare_input_target(app, foreground_store) {
+        return Ok(PasteSimulation::Unsupported(reason));
+    }
+
+    send_paste_shortcut(keys)
+        .map(|_| PasteSimulation::Pasted)
+        .map_err(|e| format!("accessibility_permission_required_or_input_simulation_failed: {e}"))
+}
+
+/// Gets the previous app ready to receive synthesized keys: checks the
+/// permission that posting them needs, then gives that app focus back.
+#[cfg(target_os = "macos")]
+fn prepare_input_target(
+    app: &AppHandle,
+    foreground_store: &crate::window::ForegroundWindowStore,
+) -> Result<(), PasteUnsupportedReason> {
     // Without the Accessibility permission, the CGEvent post that sends Cmd+V
     // fails *silently* — enigo returns Ok but nothing is typed. So we cannot
     // rely on a paste error to detect the problem; check the permission up
@@ -620,7 +1217,7 @@ fn simulate_paste_blocking(
             log::error!("Failed to emit accessibility-permission-required event: {e}");
         }
         crate::accessibility::guide_reauthorization(app);
-        return Ok(PasteSimulation::CopiedOnly);
+        return Err(PasteUnsupportedReason::AccessibilityPermissionMissing);
     }
 
     // The QuickBar stole keyboard focus while it was open. It is now hidden, so
@@ -631,10 +1228,7 @@ fn simulate_paste_blocking(
     }
     // Give the reactivated app a brief moment to become key and accept input.
     thread::sleep(Duration::from_millis(60));
-
-    send_paste_shortcut(Key::Meta)
-        .map(|_| PasteSimulation::Pasted)
-        .map_err(|e| format!("accessibility_permission_required_or_input_simulation_failed: {e}"))
+    Ok(())
 }
 
 #[cfg(target_os = "macos")]
@@ -660,62 +1254,98 @@ fn restore_recorded_foreground_window_on_main_thread(
 #[cfg(target_os = "windows")]
 async fn simulate_paste(_app: &AppHandle, state: &AppState) -> Result<PasteSimulation, String> {
     crate::window::restore_recorded_foreground_window(&state.quickbar_foreground_window)?;
-    send_paste_shortcut(Key::Control).map(|_| PasteSimulation::Pasted)
+    send_paste_shortcut(&target_paste_keys(state)).map(|_| PasteSimulation::Pasted)
 }
 
 #[cfg(target_os = "linux")]
-async fn simulate_paste(_app: &AppHandle, _state: &AppState) -> Result<PasteSimulation, String> {
-    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
-        log::warn!("Wayland detected; degrading paste request to copy-only");
-        return Ok(PasteSimulation::CopiedOnly);
-    }
+async fn simulate_paste(_app: &AppHandle, state: &AppState) -> Result<PasteSimulation, String> {
+    // The probe and the Wayland paths make blocking round-trips to the
+    // compositor, and a new uinput device waits for libinput to pick it up.
+    let keys = target_paste_keys(state);
+    let foreground_store = state.quickbar_foreground_window.clone();
+    tauri::async_runtime::spawn_blocking(move || simulate_paste_blocking(&foreground_store, &keys))
+        .await
+        .map_err(|e| format!("Paste simulation task failed: {e}"))
+}
+
+/// Wayland sessions always press Ctrl+V: neither path can tell which app has
+/// focus, and the virtual keyboard's keymap carries only those two keys.
+#[cfg(target_os = "linux")]
+fn simulate_paste_blocking(
+    foreground_store: &crate::window::ForegroundWindowStore,
+    keys: &PasteKeys,
+) -> PasteSimulation {
+    let backend = match crate::linux_paste::probe() {
+        Ok(backend) => backend,
+        Err(reason) => {
+            log::warn!("No way to simulate paste in this session: {:?}", reason);
+            return PasteSimulation::Unsupported(reason);
+        }
+    };
 
-    match send_paste_shortcut(Key::Control) {
-        Ok(()) => Ok(PasteSimulation::Pasted),
+    let result = match backend {
+        PasteBackend::X11 => {
+            refocus_x11_target(foreground_store);
+            send_paste_shortcut(keys)
+        }
+        backend => crate::linux_paste::press_paste_shortcut(backend),
+    };
+    match result {
+        Ok(()) => PasteSimulation::Pasted,
         Err(e) => {
-            log::warn!(
-                "Linux paste simulation failed; degrading to copy-only: {}",
-                e
-            );
-            Ok(PasteSimulation::CopiedOnly)
+            log::warn!("Paste simulation through {:?} failed: {}", backend, e);
+            PasteSimulation::Unsupported(PasteUnsupportedReason::InputFailed)
         }
     }
 }
 
-fn send_paste_shortcut(modifier: Key) -> Result<(), String> {
-    let mut enigo = Enigo::new(&EnigoSettings::default())
-        .map_err(|e| format!("Failed to initialize input simulation: {e}"))?;
-
-    enigo
-        .key(modifier, Press)
-        .map_err(|e| format!("Failed to press paste modifier: {e}"))?;
-    let click_result = enigo
-        .key(paste_key(), Click)
-        .map_err(|e| format!("Failed to click paste key: {e}"));
-    let release_result = enigo
-        .key(modifier, Release)
-        .map_err(|e| format!("Failed to release paste modifier: {e}"));
+/// Which input path a paste would take right now, without pressing anything.
+pub fn paste_support() -> PasteSupport {
+    #[cfg(target_os = "linux")]
+    let probed = crate::linux_paste::probe();
+    #[cfg(target_os = "macos")]
+    let probed = if crate::accessibility::is_trusted() {
+        Ok(PasteBackend::Native)
+    } else {
+        Err(PasteUnsupportedReason::AccessibilityPermissionMissing)
+    };
+    #[cfg(target_os = "windows")]
+    let probed: Result<PasteBackend, PasteUnsupportedReason> = Ok(PasteBackend::Native);
 
-    click_result?;
-    release_result
+    match probed {
+        Ok(backend) => PasteSupport::Supported { backend },
+        Err(reason) => PasteSupport::Unsupported { reason },
+    }
 }
 
-/// The "V" key pressed together with the platform modifier to trigger a paste.
-///
-/// On macOS we must NOT use `Key::Unicode('v')`: enigo resolves that character
-/// to a virtual key code through `TSMGetInputSourceProperty` (Text Input Source
-/// Manager). That API asserts it is running on the main dispatch queue and
-/// aborts the whole process (EXC_BREAKPOINT) when called from the Tokio worker
-/// thread handling the paste command. The raw key code for the physical "V"
-/// key (kVK_ANSI_V = 9) bypasses that lookup entirely.
-#[cfg(target_os = "macos")]
-fn paste_key() -> Key {
-    Key::Other(9)
-}
+fn send_paste_shortcut(keys: &PasteKeys) -> Result<(), String> {
+    let mut enigo = Enigo::new(&EnigoSettings::default())
+        .map_err(|e| format!("Failed to initialize input simulation: {e}"))?;
 
-#[cfg(not(target_os = "macos"))]
-fn paste_key() -> Key {
-    Key::Unicode('v')
+    // Modifiers go down in order and come up in reverse; whatever was pressed
+    // is released even when a later step fails, so no key is left held.
+    let modifiers = keys.enigo_modifiers();
+    let mut pressed = 0;
+    let mut result = Ok(());
+    for modifier in &modifiers {
+        if let Err(e) = enigo.key(*modifier, Press) {
+            result = Err(format!("Failed to press paste modifier: {e}"));
+            break;
+        }
+        pressed += 1;
+    }
+    if result.is_ok() {
+        result = enigo
+            .key(keys.enigo_key(), Click)
+            .map_err(|e| format!("Failed to click paste key: {e}"));
+    }
+    for modifier in modifiers[..pressed].iter().rev() {
+        let released = enigo
+            .key(*modifier, Release)
+            .map_err(|e| format!("Failed to release paste modifier: {e}"));
+        result = result.and(released);
+    }
+    result
 }
 
 #[cfg(test)]
@@ -732,68 +1362,60 @@ mod tests {
         assert!(!should_simulate_paste(PasteMode::Copy, false));
         assert!(!should_simulate_paste(PasteMode::Opposite, true));
         assert!(should_simulate_paste(PasteMode::Opposite, false));
-    }
-
-    fn clip(content_type: ContentType, content: &[u8]) -> (ContentType, Vec<u8>) {
-        (content_type, content.to_vec())
+        assert!(!should_simulate_paste(PasteMode::Type, true));
     }
 
     #[test]
-    fn merge_preserves_selection_order() {
-        let clips = [
-            clip(ContentType::Text, b"first"),
-            clip(ContentType::Text, b"second"),
-            clip(ContentType::Text, b"third"),
-        ];
-        let (merged, skipped) = merge_clip_texts(&clips, "\n");
-        assert_eq!(merged, "first\nsecond\nthird");
-        assert_eq!(skipped, 0);
-    }
-
-    #[test]
-    fn merge_skips_and_counts_image_clips() {
-        let clips = [
-            clip(ContentType::Text, b"a"),
-            clip(ContentType::Image, b"\x89PNG-bytes"),
-            clip(ContentType::Text, b"b"),
-            clip(ContentType::Image, b"more-png"),
-        ];
-        let (merged, skipped) = merge_clip_texts(&clips, "\n");
-        // Images are dropped from the merge; only text survives, in order.
-        assert_eq!(merged, "a\nb");
-        assert_eq!(skipped, 2);
+    fn only_delivered_pastes_count_in_statistics() {
+        assert!(PasteOutcome::Pasted.delivered());
+        assert!(PasteOutcome::Copied.delivered());
+        assert!(PasteOutcome::Typed { cancelled: false }.delivered());
+        assert!(!PasteOutcome::Typed { cancelled: true }.delivered());
+        assert!(!PasteOutcome::PasteUnsupported {
+            reason: PasteUnsupportedReason::InputFailed
+        }
+        .delivered());
     }
 
     #[test]
-    fn merge_uses_the_given_separator_verbatim() {
-        let clips = [clip(ContentType::Text, b"a"), clip(ContentType::Text, b"b")];
-        assert_eq!(merge_clip_texts(&clips, "\n").0, "a\nb");
-        assert_eq!(merge_clip_texts(&clips, "\t").0, "a\tb");
-        assert_eq!(merge_clip_texts(&clips, "").0, "ab");
-    }
+    fn unsupported_paste_reaches_the_frontend_with_its_reason() {
+        let outcome: PasteOutcome =
+            PasteSimulation::Unsupported(PasteUnsupportedReason::WaylandInputUnavailable).into();
 
-    #[test]
-    fn merge_includes_files_paths_as_text() {
-        // Files store their absolute paths newline-joined (D3); they merge as
-        // that text, indistinguishable from a plain-text clip.
-        let clips = [
-            clip(ContentType::Files, b"/a/one.txt\n/a/two.txt"),
-            clip(ContentType::Text, b"tail"),
-        ];
-        let (merged, skipped) = merge_clip_texts(&clips, "\n");
-        assert_eq!(merged, "/a/one.txt\n/a/two.txt\ntail");
-        assert_eq!(skipped, 0);
+        assert_eq!(
+            serde_json::json!({"status": "pasteUnsupported", "reason": "waylandInputUnavailable"}),
+            serde_json::to_value(outcome).unwrap()
+        );
+        assert_eq!(
+            serde_json::json!({"status": "pasted"}),
+            serde_json::to_value(PasteOutcome::from(PasteSimulation::Pasted)).unwrap()
+        );
+        assert_eq!(
+            serde_json::json!({"status": "supported", "backend": "virtualKeyboard"}),
+            serde_json::to_value(PasteSupport::Supported {
+                backend: PasteBackend::VirtualKeyboard
+            })
+            .unwrap()
+        );
     }
 
     #[test]
-    fn merge_of_only_images_yields_empty_text_and_full_skip_count() {
-        let clips = [
-            clip(ContentType::Image, b"one"),
-            clip(ContentType::Image, b"two"),
-            clip(ContentType::Image, b"three"),
-        ];
-        let (merged, skipped) = merge_clip_texts(&clips, "\n");
-        assert_eq!(merged, "");
-        assert_eq!(skipped, 3);
+    fn clipboard_restore_is_taken_over_by_a_later_paste() {
+        let empty = || PreviousClipboard {
+            snapshot: None,
+            formats: Vec::new(),
+        };
+        let restore = ClipboardRestore::new();
+
+        let first = restore.arm(empty());
+        assert!(restore.take_pending().is_some());
+        let mut restored = false;
+        restore.finish(first, |_| restored = true);
+        assert!(!restored, "a superseded restore must not run");
+
+        let second = restore.arm(empty());
+        restore.finish(second, |_| restored = true);
+        assert!(restored);
+        assert!(restore.take_pending().is_none());
     }
 }
@@ -0,0 +1,311 @@
+//! Per-app paste keystrokes. Most apps paste on Ctrl+V (Cmd+V on macOS),
+//! but terminals bind that to something else and paste on Ctrl+Shift+V or
+//! Shift+Insert. A profile maps the app the QuickBar was opened over (as
+//! recorded in the `ForegroundWindowStore`) to the keys that paste there.
+//!
+//! App names are compared case-insensitively against what the platform
+//! reports for the foreground window: the executable name without extension
+//! on Windows, the localized app name on macOS (the same name clips record as
+//! `source_app`), and the class half of `WM_CLASS` on X11. Wayland records no
+//! foreground app, so it always pastes with the default keys.
+
+use enigo::Key;
+use serde::{Deserialize, Serialize};
+
+/// Terminals that do not paste on Ctrl+V, with the keys they do paste on.
+const DEFAULT_PROFILES: [(&str, &str); 13] = [
+    ("gnome-terminal", "Ctrl+Shift+V"),
+    ("gnome-terminal-server", "Ctrl+Shift+V"),
+    ("ptyxis", "Ctrl+Shift+V"),
+    ("konsole", "Ctrl+Shift+V"),
+    ("xfce4-terminal", "Ctrl+Shift+V"),
+    ("mate-terminal", "Ctrl+Shift+V"),
+    ("lxterminal", "Ctrl+Shift+V"),
+    ("tilix", "Ctrl+Shift+V"),
+    ("terminator", "Ctrl+Shift+V"),
+    ("alacritty", "Ctrl+Shift+V"),
+    ("kitty", "Ctrl+Shift+V"),
+    ("org.wezfurlong.wezterm", "Ctrl+Shift+V"),
+    ("mintty", "Shift+Insert"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteKeyProfile {
+    pub app: String,
+    /// Modifiers and one key joined by `+`, e.g. `Ctrl+Shift+V`.
+    pub keys: String,
+}
+
+pub fn default_paste_key_profiles() -> Vec<PasteKeyProfile> {
+    DEFAULT_PROFILES
+        .iter()
+        .map(|(app, keys)| PasteKeyProfile {
+            app: app.to_string(),
+            keys: keys.to_string(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Control,
+    Shift,
+    Alt,
+    Meta,
+    /// Cmd on macOS, Ctrl elsewhere.
+    CommandOrControl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteKey {
+    /// A letter or digit, lowercase.
+    Char(char),
+    Insert,
+}
+
+/// A parsed key combination: modifiers held, in order, around one key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasteKeys {
+    pub modifiers: Vec<Modifier>,
+    pub key: PasteKey,
+}
+
+impl PasteKeys {
+    pub fn parse(keys: &str) -> Result<Self, String> {
+        let invalid = |why: &str| format!("Invalid paste keys '{}': {}", keys, why);
+
+        let mut parts: Vec<&str> = keys.split('+').map(str::trim).collect();
+        let key = parts.pop().filter(|key| !key.is_empty());
+        let key = match key {
+            Some(key) if key.eq_ignore_ascii_case("insert") => PasteKey::Insert,
+            Some(key) => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) if ch.is_ascii_alphanumeric() => {
+                        PasteKey::Char(ch.to_ascii_lowercase())
+                    }
+                    _ => return Err(invalid("the key must be a letter, a digit or Insert")),
+                }
+            }
+            None => return Err(invalid("no key")),
+        };
+
+        let mut modifiers = Vec::with_capacity(parts.len());
+        for part in parts {
+            let modifier = match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Modifier::Control,
+                "shift" => Modifier::Shift,
+                "alt" | "option" => Modifier::Alt,
+                "cmd" | "command" | "meta" | "super" => Modifier::Meta,
+                "commandorcontrol" | "cmdorctrl" => Modifier::CommandOrControl,
+                _ => return Err(invalid(&format!("unknown modifier '{}'", part))),
+            };
+            if modifiers.contains(&modifier) {
+                return Err(invalid(&format!("'{}' is repeated", part)));
+            }
+            modifiers.push(modifier);
+        }
+        Ok(Self { modifiers, key })
+    }
+
+    pub fn platform_default() -> Self {
+        Self {
+            modifiers: vec![Modifier::CommandOrControl],
+            key: PasteKey::Char('v'),
+        }
+    }
+
+    pub fn enigo_modifiers(&self) -> Vec<Key> {
+        self.modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                Modifier::Control => Key::Control,
+                Modifier::Shift => Key::Shift,
+                Modifier::Alt => Key::Alt,
+                Modifier::Meta => Key::Meta,
+                #[cfg(target_os = "macos")]
+                Modifier::CommandOrControl => Key::Meta,
+                #[cfg(not(target_os = "macos"))]
+                Modifier::CommandOrControl => Key::Control,
+            })
+            .collect()
+    }
+
+    /// On macOS we must NOT use `Key::Unicode`: enigo resolves that character
+    /// to a virtual key code through `TSMGetInputSourceProperty` (Text Input
+    /// Source Manager). That API asserts it is running on the main dispatch
+    /// queue and aborts the whole process (EXC_BREAKPOINT) when called from the
+    /// Tokio worker thread handling the paste command. The raw key code of the
+    /// physical key (kVK_ANSI_V = 9 for "V") bypasses that lookup entirely.
+    #[cfg(target_os = "macos")]
+    pub fn enigo_key(&self) -> Key {
+        match self.key {
+            PasteKey::Char(ch) => Key::Other(mac_ansi_key_code(ch)),
+            // kVK_Help, which Apple keyboards put where Insert is.
+            PasteKey::Insert => Key::Other(0x72),
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn enigo_key(&self) -> Key {
+        match self.key {
+            PasteKey::Char(ch) => Key::Unicode(ch),
+            PasteKey::Insert => Key::Insert,
+        }
+    }
+}
+
+/// `kVK_ANSI_*` from HIToolbox's Events.h.
+#[cfg(target_os = "macos")]
+fn mac_ansi_key_code(ch: char) -> u32 {
+    match ch {
+        'a' => 0x00,
+        's' => 0x01,
+        'd' => 0x02,
+        'f' => 0x03,
+        'h' => 0x04,
+        'g' => 0x05,
+        'z' => 0x06,
+        'x' => 0x07,
+        'c' => 0x08,
+        'v' => 0x09,
+        'b' => 0x0B,
+        'q' => 0x0C,
+        'w' => 0x0D,
+        'e' => 0x0E,
+        'r' => 0x0F,
+        'y' => 0x10,
+        't' => 0x11,
+        '1' => 0x12,
+        '2' => 0x13,
+        '3' => 0x14,
+        '4' => 0x15,
+        '6' => 0x16,
+        '5' => 0x17,
+        '9' => 0x19,
+        '7' => 0x1A,
+        '8' => 0x1C,
+        '0' => 0x1D,
+        'o' => 0x1F,
+        'u' => 0x20,
+        'i' => 0x22,
+        'p' => 0x23,
+        'l' => 0x25,
+        'j' => 0x26,
+        'k' => 0x28,
+        'n' => 0x2D,
+        'm' => 0x2E,
+        _ => 0x09,
+    }
+}
+
+/// The keys that paste into `app_name`: the first matching profile's, or
+/// the platform default when no profile matches, the app is unknown, or the
+/// matching profile no longer parses.
+pub fn resolve_paste_keys(profiles: &[PasteKeyProfile], app_name: Option<&str>) -> PasteKeys {
+    let Some(app_name) = app_name.map(|name| name.trim().to_lowercase()) else {
+        return PasteKeys::platform_default();
+    };
+    let Some(profile) = profiles
+        .iter()
+        .find(|profile| profile.app.trim().to_lowercase() == app_name)
+    else {
+        return PasteKeys::platform_default();
+    };
+
+    match PasteKeys::parse(&profile.keys) {
+        Ok(keys) => {
+            log::debug!("Pasting into '{}' with {}", profile.app, profile.keys);
+            keys
+        }
+        Err(e) => {
+            log::warn!("{}; pasting with the default keys", e);
+            PasteKeys::platform_default()
+        }
+    }
+}
+
+pub fn validate_paste_key_profile(profile: &PasteKeyProfile) -> Result<(), String> {
+    if profile.app.trim().is_empty() {
+        return Err("Paste key profile needs an app name".to_string());
+    }
+    PasteKeys::parse(&profile.keys).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_parse_modifiers_in_order_and_case_insensitively() {
+        assert_eq!(
+            PasteKeys {
+                modifiers: vec![Modifier::Control, Modifier::Shift],
+                key: PasteKey::Char('v'),
+            },
+            PasteKeys::parse("ctrl + SHIFT + V").unwrap()
+        );
+        assert_eq!(
+            PasteKeys {
+                modifiers: vec![Modifier::Shift],
+                key: PasteKey::Insert,
+            },
+            PasteKeys::parse("Shift+Insert").unwrap()
+        );
+        assert_eq!(
+            PasteKeys::platform_default(),
+            PasteKeys::parse("CommandOrControl+V").unwrap()
+        );
+
+        for invalid in [
+            "",
+            "Ctrl+",
+            "Ctrl+Shift",
+            "Hyper+V",
+            "Ctrl+Ctrl+V",
+            "Ctrl+F5",
+        ] {
+            assert!(PasteKeys::parse(invalid).is_err(), "{invalid:?} parsed");
+        }
+    }
+
+    #[test]
+    fn profiles_match_the_app_case_insensitively_and_fall_back_to_the_default() {
+        let profiles = vec![
+            PasteKeyProfile {
+                app: "Konsole".to_string(),
+                keys: "Ctrl+Shift+V".to_string(),
+            },
+            PasteKeyProfile {
+                app: "broken".to_string(),
+                keys: "Ctrl+".to_string(),
+            },
+        ];
+        let ctrl_shift_v = PasteKeys::parse("Ctrl+Shift+V").unwrap();
+
+        assert_eq!(
+            ctrl_shift_v,
+            resolve_paste_keys(&profiles, Some(" konsole "))
+        );
+        assert_eq!(
+            PasteKeys::platform_default(),
+            resolve_paste_keys(&profiles, Some("Firefox"))
+        );
+        assert_eq!(
+            PasteKeys::platform_default(),
+            resolve_paste_keys(&profiles, None)
+        );
+        assert_eq!(
+            PasteKeys::platform_default(),
+            resolve_paste_keys(&profiles, Some("broken"))
+        );
+    }
+
+    #[test]
+    fn default_profiles_are_valid() {
+        for profile in default_paste_key_profiles() {
+            validate_paste_key_profile(&profile).unwrap();
+        }
+    }
+}
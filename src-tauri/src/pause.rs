@@ -0,0 +1,255 @@
+//! Timed capture pauses ("pause for 5 minutes", "until restart").
+//!
+//! The manual tray toggle (`Settings::capture_paused`) pauses until the user
+//! turns it off and is always persisted. A timed pause instead lives in
+//! `AppState`, outside the clipboard monitor, so it survives
+//! `restart_clipboard_monitor`; it resumes on its own, and is written to
+//! `Settings::paused_until` only when `persist_timed_pause` asks for it to
+//! outlive the app. "Until restart" is never persisted, by definition.
+
+use chrono::Utc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+/// How often the tray's "N min left" label is refreshed while a timed pause
+/// runs.
+const TRAY_REFRESH_INTERVAL_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedPause {
+    /// Paused until this Unix time (seconds).
+    Until(i64),
+    /// Paused until the app exits.
+    UntilRestart,
+}
+
+impl TimedPause {
+    /// Whether capture is still paused at `now`.
+    pub fn is_active(self, now: i64) -> bool {
+        match self {
+            TimedPause::Until(until) => now < until,
+            TimedPause::UntilRestart => true,
+        }
+    }
+
+    /// Seconds left at `now`, or `None` for a pause without a deadline.
+    pub fn remaining_secs(self, now: i64) -> Option<i64> {
+        match self {
+            TimedPause::Until(until) => Some((until - now).max(0)),
+            TimedPause::UntilRestart => None,
+        }
+    }
+
+    fn deadline(self) -> Option<i64> {
+        match self {
+            TimedPause::Until(until) => Some(until),
+            TimedPause::UntilRestart => None,
+        }
+    }
+}
+
+/// The current timed pause. Each change bumps a generation so a ticker
+/// armed for an earlier pause notices it was superseded and exits.
+#[derive(Default)]
+pub struct CapturePause {
+    current: Mutex<Option<TimedPause>>,
+    generation: AtomicU64,
+}
+
+impl CapturePause {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The pause in effect at `now`; an elapsed deadline reads as `None`
+    /// even before its ticker has cleared it.
+    pub fn current(&self, now: i64) -> Option<TimedPause> {
+        (*crate::safe_lock(&self.current)).filter(|pause| pause.is_active(now))
+    }
+
+    /// Deadline of the pause in effect, for persisting.
+    pub fn deadline(&self) -> Option<i64> {
+        self.current(Utc::now().timestamp())
+            .and_then(TimedPause::deadline)
+    }
+
+    fn replace(&self, pause: Option<TimedPause>) -> u64 {
+        *crate::safe_lock(&self.current) = pause;
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn is_generation(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+}
+
+/// Whether capture is paused right now, by the manual toggle or a timed
+/// pause.
+pub fn capture_is_paused(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    state.settings.get().capture_paused
+        || state
+            .capture_pause
+            .current(Utc::now().timestamp())
+            .is_some()
+}
+
+/// Pause capture for `duration`, replacing any manual or timed pause.
+pub fn pause_for(app: &AppHandle, duration: Duration) {
+    let until = Utc::now().timestamp() + duration.as_secs() as i64;
+    start(app, TimedPause::Until(until));
+}
+
+/// Pause capture until the app exits, replacing any manual or timed pause.
+pub fn pause_until_restart(app: &AppHandle) {
+    start(app, TimedPause::UntilRestart);
+}
+
+/// The tray's "Until Resumed" checkbox: flips the persisted manual pause.
+/// Either way any timed pause ends, so the checkbox is the whole state.
+pub fn toggle_manual(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    state.capture_pause.replace(None);
+    let now_paused = !state.settings.get().capture_paused;
+    save_pause_settings(app, now_paused, None);
+    log::info!(
+        "Clipboard capture {} via tray menu",
+        if now_paused { "paused" } else { "resumed" }
+    );
+    crate::tray::update_tray_menu(app);
+}
+
+/// The toggle-pause shortcut: resume when paused in any way, otherwise
+/// pause until resumed.
+pub fn toggle(app: &AppHandle) {
+    if capture_is_paused(app) {
+        resume(app);
+    } else {
+        toggle_manual(app);
+    }
+}
+
+/// End any pause, manual or timed.
+pub fn resume(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    state.capture_pause.replace(None);
+    save_pause_settings(app, false, None);
+    log::info!("Clipboard capture resumed");
+    crate::tray::update_tray_menu(app);
+}
+
+/// Startup: re-apply a timed pause persisted by the previous run if it has
+/// not elapsed yet, and drop a stale one.
+pub fn restore(app: &AppHandle) {
+    let settings = app.state::<AppState>().settings.get();
+    let Some(until) = settings.paused_until else {
+        return;
+    };
+
+    if settings.persist_timed_pause && TimedPause::Until(until).is_active(Utc::now().timestamp()) {
+        log::info!("Restoring timed capture pause until {}", until);
+        start(app, TimedPause::Until(until));
+    } else {
+        save_pause_settings(app, settings.capture_paused, None);
+    }
+}
+
+fn start(app: &AppHandle, pause: TimedPause) {
+    let state = app.state::<AppState>();
+    let generation = state.capture_pause.replace(Some(pause));
+    let persisted = state
+        .settings
+        .get()
+        .persist_timed_pause
+        .then(|| pause.deadline())
+        .flatten();
+    save_pause_settings(app, false, persisted);
+    log::info!("Clipboard capture paused ({:?})", pause);
+    crate::tray::update_tray_menu(app);
+
+    if let TimedPause::Until(until) = pause {
+        spawn_ticker(app.clone(), generation, until);
+    }
+}
+
+/// Keeps the tray's remaining time current and resumes capture once
+/// `until` passes, unless the pause was replaced in the meantime.
+fn spawn_ticker(app: AppHandle, generation: u64, until: i64) {
+    std::thread::spawn(move || loop {
+        let remaining = until - Utc::now().timestamp();
+        if remaining > 0 {
+            std::thread::sleep(Duration::from_secs(
+                remaining.min(TRAY_REFRESH_INTERVAL_SECS) as u64,
+            ));
+        }
+
+        let state = app.state::<AppState>();
+        if !state.capture_pause.is_generation(generation) {
+            return;
+        }
+        if Utc::now().timestamp() >= until {
+            state.capture_pause.replace(None);
+            save_pause_settings(&app, state.settings.get().capture_paused, None);
+            log::info!("Timed capture pause ended; capture resumed");
+            crate::tray::update_tray_menu(&app);
+            return;
+        }
+        crate::tray::update_tray_menu(&app);
+    });
+}
+
+/// Writes the two pause fields the settings page never owns, under the
+/// settings write lock so a concurrent `update_settings` can't clobber them.
+fn save_pause_settings(app: &AppHandle, capture_paused: bool, paused_until: Option<i64>) {
+    let state = app.state::<AppState>();
+    let _settings_write_guard = crate::safe_lock(&state.settings_write_lock);
+
+    let mut settings = state.settings.get();
+    if settings.capture_paused == capture_paused && settings.paused_until == paused_until {
+        return;
+    }
+    settings.capture_paused = capture_paused;
+    settings.paused_until = paused_until;
+    state.settings.set(settings);
+
+    if let Err(e) = state.settings.save(app) {
+        log::error!("Failed to persist capture pause: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timed_pause_is_active_until_its_deadline() {
+        let pause = TimedPause::Until(1_000);
+        assert!(pause.is_active(999));
+        assert!(!pause.is_active(1_000));
+        assert_eq!(Some(300), pause.remaining_secs(700));
+        assert_eq!(Some(0), pause.remaining_secs(1_200));
+
+        assert!(TimedPause::UntilRestart.is_active(i64::MAX));
+        assert_eq!(None, TimedPause::UntilRestart.remaining_secs(0));
+    }
+
+    #[test]
+    fn capture_pause_hides_elapsed_pauses_and_tracks_generations() {
+        let pause = CapturePause::new();
+        assert_eq!(None, pause.current(0));
+
+        let first = pause.replace(Some(TimedPause::Until(100)));
+        assert_eq!(Some(TimedPause::Until(100)), pause.current(50));
+        assert_eq!(None, pause.current(100));
+        assert!(pause.is_generation(first));
+
+        let second = pause.replace(Some(TimedPause::UntilRestart));
+        assert!(!pause.is_generation(first));
+        assert!(pause.is_generation(second));
+        assert_eq!(Some(TimedPause::UntilRestart), pause.current(1_000));
+    }
+}
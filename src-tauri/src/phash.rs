@@ -0,0 +1,115 @@
+//! Perceptual image hashing for near-duplicate detection.
+//!
+//! Exact image dedup hashes normalized RGBA (D5), so a screenshot retaken a
+//! pixel off or an image re-encoded by another app is a different clip. A
+//! difference hash (dHash) instead summarizes the image's coarse brightness
+//! gradients: visually identical images land within a few bits of each other
+//! regardless of size, compression noise or small shifts.
+
+use image::imageops::FilterType;
+use image::DynamicImage;
+
+/// dHash grid: 9 columns give 8 horizontal comparisons per row, 8 rows give
+/// 64 bits.
+const HASH_WIDTH: u32 = 9;
+const HASH_HEIGHT: u32 = 8;
+
+/// 64-bit difference hash of `image`. Bit `row * 8 + col` is set when that
+/// cell of the 9x8 grayscale thumbnail is brighter than its right neighbour.
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image
+        .resize_exact(HASH_WIDTH, HASH_HEIGHT, FilterType::Triangle)
+        .to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..HASH_HEIGHT {
+        for x in 0..HASH_WIDTH - 1 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    hash
+}
+
+/// Number of differing bits between two hashes; `0` means perceptually
+/// identical, and unrelated images sit around 32.
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    /// A synthetic "screenshot": a light background with a dark window and a
+    /// mid-grey title bar, offset by `(dx, dy)` pixels.
+    fn screenshot(width: u32, height: u32, dx: u32, dy: u32) -> DynamicImage {
+        let image = RgbaImage::from_fn(width, height, |x, y| {
+            let in_window = (width / 4 + dx..width * 3 / 4 + dx).contains(&x)
+                && (height / 4 + dy..height * 3 / 4 + dy).contains(&y);
+            let in_title = in_window && y < height / 4 + dy + height / 10;
+            match (in_window, in_title) {
+                (_, true) => Rgba([128, 128, 128, 255]),
+                (true, false) => Rgba([20, 30, 40, 255]),
+                _ => Rgba([240, 240, 235, 255]),
+            }
+        });
+        DynamicImage::ImageRgba8(image)
+    }
+
+    fn gradient(width: u32, height: u32, rising: bool) -> DynamicImage {
+        let image = RgbaImage::from_fn(width, height, |x, y| {
+            let value = ((x + y) * 255 / (width + height)) as u8;
+            let value = if rising { value } else { 255 - value };
+            Rgba([value, value.wrapping_mul(3), 255 - value, 255])
+        });
+        DynamicImage::ImageRgba8(image)
+    }
+
+    #[test]
+    fn identical_images_hash_identically() {
+        let image = screenshot(320, 200, 0, 0);
+        assert_eq!(dhash(&image), dhash(&image.clone()));
+    }
+
+    #[test]
+    fn pixel_shift_rescale_and_reencode_stay_within_a_few_bits() {
+        let original = dhash(&screenshot(320, 200, 0, 0));
+
+        let shifted = dhash(&screenshot(320, 200, 1, 1));
+        assert!(hamming_distance(original, shifted) <= 2);
+
+        let rescaled = dhash(&screenshot(640, 400, 0, 0));
+        assert!(hamming_distance(original, rescaled) <= 2);
+
+        let mut jpeg = Vec::new();
+        screenshot(320, 200, 0, 0)
+            .to_rgb8()
+            .write_to(
+                &mut std::io::Cursor::new(&mut jpeg),
+                image::ImageFormat::Jpeg,
+            )
+            .unwrap();
+        let reencoded = dhash(&image::load_from_memory(&jpeg).unwrap());
+        assert!(hamming_distance(original, reencoded) <= 2);
+    }
+
+    #[test]
+    fn different_images_are_far_apart() {
+        let window = dhash(&screenshot(320, 200, 0, 0));
+        let rising = dhash(&gradient(320, 200, true));
+        let falling = dhash(&gradient(320, 200, false));
+
+        assert!(hamming_distance(window, rising) > 10);
+        assert!(hamming_distance(rising, falling) > 10);
+    }
+
+    #[test]
+    fn hamming_distance_counts_differing_bits() {
+        assert_eq!(0, hamming_distance(0xDEAD_BEEF, 0xDEAD_BEEF));
+        assert_eq!(1, hamming_distance(0b1000, 0b0000));
+        assert_eq!(64, hamming_distance(0, u64::MAX));
+    }
+}
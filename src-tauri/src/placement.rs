@@ -0,0 +1,253 @@
+//! Where the QuickBar opens on its monitor, and how large it is.
+//!
+//! `window::position_quickbar` picks the monitor and resolves the anchor
+//! point (cursor or caret); the geometry here is pure so each placement mode
+//! can be tested without a display. Positions and sizes are physical pixels
+//! unless named logical.
+
+use serde::{Deserialize, Serialize};
+
+/// Comfortable maximum QuickBar size on large screens, in logical px. The
+/// configured size is capped here, and scaled down proportionally on smaller
+/// screens. The initial window size lives in `tauri.conf.json` (820×600).
+pub const QUICKBAR_MAX_WIDTH: f64 = 820.0;
+pub const QUICKBAR_MAX_HEIGHT: f64 = 600.0;
+/// Smallest configurable size that still fits the search row, a few results
+/// and the footer.
+pub const QUICKBAR_MIN_WIDTH: f64 = 420.0;
+pub const QUICKBAR_MIN_HEIGHT: f64 = 280.0;
+
+/// Space kept between the QuickBar and a screen corner or its anchor point.
+const MARGIN: f64 = 12.0;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum QuickBarPlacement {
+    /// Centered horizontally, a third of the way down.
+    #[default]
+    Center,
+    /// Just below the mouse cursor.
+    Cursor,
+    /// Just below the text caret of the focused app, where the platform
+    /// exposes it (Windows); elsewhere, or without a caret, at the cursor.
+    Caret,
+    /// Where the QuickBar was when it last closed, on whichever monitor it
+    /// opens on now.
+    LastPosition,
+    Corner {
+        corner: ScreenCorner,
+    },
+}
+
+impl QuickBarPlacement {
+    /// Whether this placement opens next to a point rather than at a fixed
+    /// spot on the monitor.
+    pub fn is_anchored(self) -> bool {
+        matches!(self, Self::Cursor | Self::Caret)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScreenCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// The QuickBar's top-left corner relative to its monitor's work area, in
+/// logical px, so it carries over to a monitor of another size or scale.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct QuickBarOffset {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A monitor's work area (the screen minus taskbars and docks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Compute the QuickBar logical size from a (logical) work-area: the
+/// configured size, shrunk to a fraction of smaller screens. The fraction
+/// guarantees the result never exceeds the work area, so no floor is needed.
+pub fn quickbar_logical_size(
+    work_width: f64,
+    work_height: f64,
+    configured_width: f64,
+    configured_height: f64,
+) -> (f64, f64) {
+    let width = (work_width * 0.92).min(configured_width);
+    let height = (work_height * 0.7).min(configured_height);
+    (width, height)
+}
+
+/// Top-left position of a QuickBar of `size` on `area`. `anchor` is the
+/// cursor or caret point for anchored placements (without one they fall back
+/// to the center); `last` is the remembered offset for `LastPosition`. The
+/// result always lies inside the work area.
+pub fn quickbar_position(
+    placement: QuickBarPlacement,
+    area: WorkArea,
+    size: (i32, i32),
+    scale: f64,
+    anchor: Option<(i32, i32)>,
+    last: Option<QuickBarOffset>,
+) -> (i32, i32) {
+    let (width, height) = size;
+    let margin = (MARGIN * scale).round() as i32;
+    let center = (
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 3,
+    );
+
+    let position = match placement {
+        QuickBarPlacement::Center => center,
+        QuickBarPlacement::Cursor | QuickBarPlacement::Caret => match anchor {
+            // Below the anchor, or above it when there is no room below.
+            Some((x, y)) if y + margin + height <= area.y + area.height => (x, y + margin),
+            Some((x, y)) => (x, y - margin - height),
+            None => center,
+        },
+        QuickBarPlacement::LastPosition => match last {
+            Some(offset) => (
+                area.x + (offset.x * scale).round() as i32,
+                area.y + (offset.y * scale).round() as i32,
+            ),
+            None => center,
+        },
+        QuickBarPlacement::Corner { corner } => {
+            let left = area.x + margin;
+            let right = area.x + area.width - width - margin;
+            let top = area.y + margin;
+            let bottom = area.y + area.height - height - margin;
+            match corner {
+                ScreenCorner::TopLeft => (left, top),
+                ScreenCorner::TopRight => (right, top),
+                ScreenCorner::BottomLeft => (left, bottom),
+                ScreenCorner::BottomRight => (right, bottom),
+            }
+        }
+    };
+    clamp_into(area, size, position)
+}
+
+/// The offset `LastPosition` remembers for a QuickBar at `position`.
+pub fn quickbar_offset(area: WorkArea, position: (i32, i32), scale: f64) -> QuickBarOffset {
+    QuickBarOffset {
+        x: (position.0 - area.x) as f64 / scale,
+        y: (position.1 - area.y) as f64 / scale,
+    }
+}
+
+fn clamp_into(area: WorkArea, (width, height): (i32, i32), (x, y): (i32, i32)) -> (i32, i32) {
+    let max_x = (area.x + area.width - width).max(area.x);
+    let max_y = (area.y + area.height - height).max(area.y);
+    (x.clamp(area.x, max_x), y.clamp(area.y, max_y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: WorkArea = WorkArea {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1400,
+    };
+    const SIZE: (i32, i32) = (800, 600);
+
+    fn place(placement: QuickBarPlacement, anchor: Option<(i32, i32)>) -> (i32, i32) {
+        quickbar_position(placement, AREA, SIZE, 1.0, anchor, None)
+    }
+
+    #[test]
+    fn center_is_a_third_of_the_way_down() {
+        assert_eq!((2800, 266), place(QuickBarPlacement::Center, None));
+    }
+
+    #[test]
+    fn anchored_placements_open_below_the_anchor_or_above_near_the_bottom() {
+        assert_eq!(
+            (2000, 112),
+            place(QuickBarPlacement::Cursor, Some((2000, 100)))
+        );
+        assert_eq!(
+            (2000, 688),
+            place(QuickBarPlacement::Caret, Some((2000, 1300)))
+        );
+        // Kept on screen at the right edge, centered without an anchor.
+        assert_eq!(
+            (3680, 112),
+            place(QuickBarPlacement::Cursor, Some((4400, 100)))
+        );
+        assert_eq!(
+            place(QuickBarPlacement::Center, None),
+            place(QuickBarPlacement::Caret, None)
+        );
+    }
+
+    #[test]
+    fn corners_keep_a_margin() {
+        let corner = |corner| QuickBarPlacement::Corner { corner };
+        assert_eq!((1932, 12), place(corner(ScreenCorner::TopLeft), None));
+        assert_eq!((3668, 788), place(corner(ScreenCorner::BottomRight), None));
+    }
+
+    #[test]
+    fn last_position_is_relative_to_the_work_area_and_scale() {
+        let offset = quickbar_offset(AREA, (2120, 300), 2.0);
+        assert_eq!(QuickBarOffset { x: 100.0, y: 150.0 }, offset);
+
+        let other = WorkArea {
+            x: 0,
+            y: 40,
+            width: 1280,
+            height: 760,
+        };
+        assert_eq!(
+            (100, 190),
+            quickbar_position(
+                QuickBarPlacement::LastPosition,
+                other,
+                (400, 300),
+                1.0,
+                None,
+                Some(offset)
+            )
+        );
+        // An offset past the edge of a smaller monitor is pulled back on.
+        assert_eq!(
+            (880, 500),
+            quickbar_position(
+                QuickBarPlacement::LastPosition,
+                other,
+                (400, 300),
+                1.0,
+                None,
+                Some(QuickBarOffset {
+                    x: 5000.0,
+                    y: 5000.0
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn size_is_the_configured_size_shrunk_to_small_screens() {
+        assert_eq!(
+            (600.0, 400.0),
+            quickbar_logical_size(2560.0, 1400.0, 600.0, 400.0)
+        );
+        assert_eq!(
+            (500.0 * 0.92, 500.0 * 0.7),
+            quickbar_logical_size(500.0, 500.0, 820.0, 600.0)
+        );
+    }
+}
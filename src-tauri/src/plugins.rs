@@ -0,0 +1,765 @@
+//! Sandboxed content-processor plugins: WebAssembly modules loaded from
+//! `<data dir>/plugins/<plugin>/`, run at capture and at paste.
+//!
+//! Each plugin directory holds a `plugin.json` manifest and the module it
+//! names. The manifest lists the plugin's capabilities, and a result the
+//! plugin has no capability for is ignored:
+//!
+//! - `veto`: drop a capture
+//! - `transform`: replace a captured text
+//! - `classify`: set a captured text's kind
+//! - `annotate`: label or group a capture the capture rules left unlabelled
+//!   or ungrouped
+//! - `pasteTransform`: replace the text of a clip being pasted
+//!
+//! Plugins only ever see Text clips. A module gets nothing from the host but
+//! [`HOST_MODULE`]`.log(ptr, len)`; it must export `memory` and
+//! `alloc(len) -> ptr`, plus `on_capture` and/or `on_paste`, both
+//! `(ptr, len) -> i64`. The host writes a JSON request into memory from
+//! `alloc`, and the plugin returns `ptr << 32 | len` of a JSON reply, or 0
+//! to leave the clip alone. Every call runs in a fresh instance with a fuel
+//! budget and a memory cap, so a plugin keeps no state between clips and
+//! cannot loop forever or grow without bound. A plugin that fails is logged
+//! and skipped.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use wasmi::{Caller, Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::classify::TextKind;
+use crate::storage::{ClipItem, ContentType};
+
+pub const PLUGINS_DIR_NAME: &str = "plugins";
+pub const MANIFEST_FILE_NAME: &str = "plugin.json";
+/// The only import namespace a plugin may use.
+pub const HOST_MODULE: &str = "clipman";
+const MAX_MODULE_BYTES: u64 = 4 * 1024 * 1024;
+const DEFAULT_FUEL: u64 = 5_000_000;
+const MAX_FUEL: u64 = 50_000_000;
+const WASM_PAGE_BYTES: usize = 64 * 1024;
+const DEFAULT_MEMORY_PAGES: u32 = 32;
+const MAX_MEMORY_PAGES: u32 = 256;
+const MAX_REPLY_BYTES: usize = 1024 * 1024;
+/// Per call, so a chatty plugin cannot flood the log.
+const MAX_LOG_LINES: usize = 20;
+const MAX_LOG_LINE_BYTES: usize = 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginCapability {
+    Veto,
+    Transform,
+    Classify,
+    Annotate,
+    PasteTransform,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginLimits {
+    /// Fuel per call, roughly one unit per executed instruction.
+    pub fuel: u64,
+    /// Largest linear memory, in 64 KiB pages.
+    pub memory_pages: u32,
+}
+
+impl Default for PluginLimits {
+    fn default() -> Self {
+        Self {
+            fuel: DEFAULT_FUEL,
+            memory_pages: DEFAULT_MEMORY_PAGES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Module file, relative to the plugin directory.
+    #[serde(default = "default_module_file")]
+    pub module: String,
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+    /// Asked-for limits, lowered to the host's hard caps.
+    #[serde(default)]
+    pub limits: PluginLimits,
+}
+
+fn default_module_file() -> String {
+    "plugin.wasm".to_string()
+}
+
+/// A plugin directory as the settings window lists it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    /// Directory name under `plugins/`.
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub capabilities: Vec<PluginCapability>,
+    /// Why the plugin did not load; `None` for a loaded plugin.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureRequest<'a> {
+    text: &'a str,
+    source_app: Option<&'a str>,
+    text_kind: Option<TextKind>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CaptureReply {
+    veto: bool,
+    text: Option<String>,
+    kind: Option<TextKind>,
+    label: Option<String>,
+    group: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PasteRequest<'a> {
+    text: &'a str,
+    source_app: Option<&'a str>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PasteReply {
+    text: Option<String>,
+}
+
+struct Plugin {
+    id: String,
+    manifest: PluginManifest,
+    module: Module,
+}
+
+impl Plugin {
+    fn has(&self, capability: PluginCapability) -> bool {
+        self.manifest.capabilities.contains(&capability)
+    }
+
+    fn exports(&self, name: &str) -> bool {
+        self.module.exports().any(|export| export.name() == name)
+    }
+
+    /// Ignores, with a warning, a reply field the manifest does not allow.
+    fn allowed(&self, capability: PluginCapability) -> bool {
+        let allowed = self.has(capability);
+        if !allowed {
+            log::warn!(
+                "Plugin '{}' lacks the {:?} capability; ignoring that part of its reply",
+                self.id,
+                capability
+            );
+        }
+        allowed
+    }
+}
+
+struct HostState {
+    limits: StoreLimits,
+    plugin_id: String,
+    log_lines: usize,
+}
+
+/// The loaded plugins, in directory-name order.
+pub struct PluginSet {
+    engine: Engine,
+    plugins: Vec<Plugin>,
+    infos: Vec<PluginInfo>,
+}
+
+impl Default for PluginSet {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl PluginSet {
+    pub fn empty() -> Self {
+        Self {
+            engine: plugin_engine(),
+            plugins: Vec::new(),
+            infos: Vec::new(),
+        }
+    }
+
+    /// Loads every plugin directory under `dir`. A plugin that fails to load
+    /// is listed with its error and otherwise left out; a missing `dir`
+    /// means no plugins.
+    pub fn load(dir: &Path) -> Self {
+        let mut set = Self::empty();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return set;
+        };
+        let mut plugin_dirs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+        plugin_dirs.sort();
+
+        for plugin_dir in plugin_dirs {
+            let id = plugin_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let manifest = match read_manifest(&plugin_dir) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    log::warn!("Plugin '{}' not loaded: {}", id, e);
+                    set.infos.push(plugin_info(&id, None, Some(e)));
+                    continue;
+                }
+            };
+            let info = plugin_info(&id, Some(&manifest), None);
+            match load_module(&set.engine, &plugin_dir, &manifest) {
+                Ok(module) => {
+                    log::info!("Loaded plugin '{}' ({})", manifest.name, id);
+                    set.infos.push(info);
+                    set.plugins.push(Plugin {
+                        id,
+                        manifest,
+                        module,
+                    });
+                }
+                Err(e) => {
+                    log::warn!("Plugin '{}' not loaded: {}", id, e);
+                    set.infos.push(PluginInfo {
+                        error: Some(e),
+                        ..info
+                    });
+                }
+            }
+        }
+        set
+    }
+
+    pub fn infos(&self) -> Vec<PluginInfo> {
+        self.infos.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Runs every plugin's `on_capture` on a Text capture, in order. `None`
+    /// when a plugin vetoed it.
+    pub fn process_capture(&self, mut item: ClipItem) -> Option<ClipItem> {
+        if item.content_type != ContentType::Text {
+            return Some(item);
+        }
+
+        for plugin in self
+            .plugins
+            .iter()
+            .filter(|plugin| plugin.exports("on_capture"))
+        {
+            let text = String::from_utf8_lossy(&item.content).into_owned();
+            let request = CaptureRequest {
+                text: &text,
+                source_app: item.source_app.as_deref(),
+                text_kind: item.text_kind,
+            };
+            let reply: CaptureReply = match self.call(plugin, "on_capture", &request) {
+                Ok(Some(reply)) => reply,
+                Ok(None) => continue,
+                Err(e) => {
+                    log::warn!("Plugin '{}' failed at capture: {}", plugin.id, e);
+                    continue;
+                }
+            };
+
+            if reply.veto && plugin.allowed(PluginCapability::Veto) {
+                log::info!("Plugin '{}' vetoed a capture", plugin.id);
+                return None;
+            }
+            if let Some(text) = reply.text {
+                if plugin.allowed(PluginCapability::Transform) && !text.is_empty() {
+                    replace_text(&mut item, text);
+                }
+            }
+            if let Some(kind) = reply.kind {
+                if plugin.allowed(PluginCapability::Classify) {
+                    item.text_kind = Some(kind);
+                }
+            }
+            if (reply.label.is_some() || reply.group.is_some())
+                && plugin.allowed(PluginCapability::Annotate)
+            {
+                item.label = item.label.take().or(reply.label);
+                item.group_name = item.group_name.take().or(reply.group);
+            }
+        }
+        Some(item)
+    }
+
+    /// Runs every `pasteTransform` plugin's `on_paste` on a Text clip about
+    /// to be pasted, in order.
+    pub fn transform_paste(&self, mut item: ClipItem) -> ClipItem {
+        if item.content_type != ContentType::Text {
+            return item;
+        }
+
+        for plugin in self.plugins.iter().filter(|plugin| {
+            plugin.has(PluginCapability::PasteTransform) && plugin.exports("on_paste")
+        }) {
+            let text = String::from_utf8_lossy(&item.content).into_owned();
+            let request = PasteRequest {
+                text: &text,
+                source_app: item.source_app.as_deref(),
+            };
+            match self.call::<_, PasteReply>(plugin, "on_paste", &request) {
+                Ok(Some(PasteReply { text: Some(text) })) if !text.is_empty() => {
+                    replace_text(&mut item, text);
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Plugin '{}' failed at paste: {}", plugin.id, e),
+            }
+        }
+        item
+    }
+
+    /// One call into a fresh instance of `plugin`. `Ok(None)` when the
+    /// plugin returned 0.
+    fn call<Req: Serialize, Reply: for<'de> Deserialize<'de>>(
+        &self,
+        plugin: &Plugin,
+        function: &str,
+        request: &Req,
+    ) -> Result<Option<Reply>, String> {
+        let limits = &plugin.manifest.limits;
+        let memory_pages = limits.memory_pages.min(MAX_MEMORY_PAGES) as usize;
+        let state = HostState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(memory_pages * WASM_PAGE_BYTES)
+                .instances(1)
+                .memories(1)
+                .tables(1)
+                .build(),
+            plugin_id: plugin.id.clone(),
+            log_lines: 0,
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(limits.fuel.min(MAX_FUEL))
+            .map_err(|e| e.to_string())?;
+
+        let instance = host_linker(&self.engine)?
+            .instantiate(&mut store, &plugin.module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|e| format!("could not instantiate: {}", e))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or("the module exports no memory")?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| format!("alloc: {}", e))?;
+        let entry = instance
+            .get_typed_func::<(i32, i32), i64>(&store, function)
+            .map_err(|e| format!("{}: {}", function, e))?;
+
+        let input = serde_json::to_vec(request).map_err(|e| e.to_string())?;
+        let input_len = i32::try_from(input.len()).map_err(|_| "the clip is too large")?;
+        let input_ptr = alloc
+            .call(&mut store, input_len)
+            .map_err(|e| format!("alloc: {}", e))?;
+        memory
+            .write(&mut store, input_ptr as u32 as usize, &input)
+            .map_err(|e| format!("alloc returned an invalid pointer: {}", e))?;
+
+        let packed = entry
+            .call(&mut store, (input_ptr, input_len))
+            .map_err(|e| format!("{}: {}", function, e))? as u64;
+        if packed == 0 {
+            return Ok(None);
+        }
+        let (reply_ptr, reply_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if reply_len > MAX_REPLY_BYTES {
+            return Err(format!("the reply is over {} bytes", MAX_REPLY_BYTES));
+        }
+        let mut reply = vec![0; reply_len];
+        memory
+            .read(&store, reply_ptr, &mut reply)
+            .map_err(|e| format!("the reply is out of bounds: {}", e))?;
+        serde_json::from_slice(&reply)
+            .map(Some)
+            .map_err(|e| format!("invalid reply: {}", e))
+    }
+}
+
+/// The text changed, so its rich companions no longer match it and its kind
+/// is worked out again (a later `classify` plugin may still override it).
+fn replace_text(item: &mut ClipItem, text: String) {
+    let classification = crate::classify::classify_text(&text);
+    item.content = text.into_bytes();
+    item.html = None;
+    item.rtf = None;
+    item.formats.clear();
+    item.text_kind = Some(classification.kind);
+    item.code_language = classification.code_language.map(str::to_string);
+}
+
+fn plugin_engine() -> Engine {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+fn host_linker(engine: &Engine) -> Result<Linker<HostState>, String> {
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "log",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                if caller.data().log_lines >= MAX_LOG_LINES {
+                    return;
+                }
+                let Some(memory) = caller
+                    .get_export("memory")
+                    .and_then(|export| export.into_memory())
+                else {
+                    return;
+                };
+                let mut line = vec![0; (len as u32 as usize).min(MAX_LOG_LINE_BYTES)];
+                if memory.read(&caller, ptr as u32 as usize, &mut line).is_ok() {
+                    log::info!(
+                        "Plugin '{}': {}",
+                        caller.data().plugin_id,
+                        String::from_utf8_lossy(&line)
+                    );
+                }
+                let mut caller = caller;
+                caller.data_mut().log_lines += 1;
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(linker)
+}
+
+fn read_manifest(dir: &Path) -> Result<PluginManifest, String> {
+    let manifest_text = std::fs::read_to_string(dir.join(MANIFEST_FILE_NAME))
+        .map_err(|e| format!("cannot read {}: {}", MANIFEST_FILE_NAME, e))?;
+    serde_json::from_str(&manifest_text)
+        .map_err(|e| format!("invalid {}: {}", MANIFEST_FILE_NAME, e))
+}
+
+fn load_module(engine: &Engine, dir: &Path, manifest: &PluginManifest) -> Result<Module, String> {
+    // A bare file name keeps the module inside the plugin directory.
+    let module_name = Path::new(&manifest.module);
+    if module_name.components().count() != 1 || module_name.file_name().is_none() {
+        return Err(format!(
+            "module \"{}\" must be a file in the plugin directory",
+            manifest.module
+        ));
+    }
+    let module_path = dir.join(module_name);
+    let size = std::fs::metadata(&module_path)
+        .map_err(|e| format!("cannot read {}: {}", manifest.module, e))?
+        .len();
+    if size > MAX_MODULE_BYTES {
+        return Err(format!(
+            "{} is over {} bytes",
+            manifest.module, MAX_MODULE_BYTES
+        ));
+    }
+    let bytes = std::fs::read(&module_path)
+        .map_err(|e| format!("cannot read {}: {}", manifest.module, e))?;
+    let module = Module::new(engine, &bytes[..]).map_err(|e| format!("invalid module: {}", e))?;
+
+    if let Some(import) = module
+        .imports()
+        .find(|import| import.module() != HOST_MODULE || import.name() != "log")
+    {
+        return Err(format!(
+            "imports {}.{}, but the host only provides {}.log",
+            import.module(),
+            import.name(),
+            HOST_MODULE
+        ));
+    }
+
+    Ok(module)
+}
+
+fn plugin_info(id: &str, manifest: Option<&PluginManifest>, error: Option<String>) -> PluginInfo {
+    PluginInfo {
+        id: id.to_string(),
+        name: manifest.map_or_else(|| id.to_string(), |manifest| manifest.name.clone()),
+        version: manifest
+            .map(|manifest| manifest.version.clone())
+            .unwrap_or_default(),
+        description: manifest
+            .map(|manifest| manifest.description.clone())
+            .unwrap_or_default(),
+        capabilities: manifest
+            .map(|manifest| manifest.capabilities.clone())
+            .unwrap_or_default(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// A module whose `on_capture` and `on_paste` both answer `reply`.
+    fn replying(reply: &str) -> String {
+        format!(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (data (i32.const 0) "{data}")
+                 (func (export "alloc") (param i32) (result i32) (i32.const 4096))
+                 (func (export "on_capture") (param i32 i32) (result i64) (i64.const {len}))
+                 (func (export "on_paste") (param i32 i32) (result i64) (i64.const {len})))"#,
+            data = reply.replace('\\', "\\\\").replace('"', "\\\""),
+            len = reply.len()
+        )
+    }
+
+    struct PluginDir {
+        path: PathBuf,
+    }
+
+    impl PluginDir {
+        fn new() -> Self {
+            let path =
+                std::env::temp_dir().join(format!("clipman_plugins_{}", Uuid::new_v4().simple()));
+            std::fs::create_dir_all(&path).unwrap();
+            Self { path }
+        }
+
+        fn add(&self, id: &str, manifest: serde_json::Value, wat: &str) -> &Self {
+            let dir = self.path.join(id);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(MANIFEST_FILE_NAME), manifest.to_string()).unwrap();
+            std::fs::write(dir.join("plugin.wasm"), wat::parse_str(wat).unwrap()).unwrap();
+            self
+        }
+
+        fn load(&self) -> PluginSet {
+            PluginSet::load(&self.path)
+        }
+    }
+
+    impl Drop for PluginDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn manifest(capabilities: &[&str]) -> serde_json::Value {
+        serde_json::json!({ "name": "test", "capabilities": capabilities })
+    }
+
+    fn text_item(text: &str) -> ClipItem {
+        ClipItem {
+            id: "clip-1".to_string(),
+            content: text.as_bytes().to_vec(),
+            thumbnail: None,
+            content_type: ContentType::Text,
+            timestamp: 1_700_000_000,
+            is_pinned: false,
+            pin_order: None,
+            label: None,
+            group_name: None,
+            source_app: None,
+            html: Some("<b>rich</b>".to_string()),
+            rtf: None,
+            perceptual_hash: None,
+            text_kind: Some(TextKind::Plain),
+            code_language: None,
+            is_redacted: false,
+            expires_at: None,
+            capture_marker: None,
+            formats: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn a_veto_needs_the_veto_capability() {
+        let dir = PluginDir::new();
+        dir.add("a", manifest(&[]), &replying(r#"{"veto":true}"#));
+        assert!(dir.load().process_capture(text_item("hello")).is_some());
+
+        dir.add("b", manifest(&["veto"]), &replying(r#"{"veto":true}"#));
+        assert!(dir.load().process_capture(text_item("hello")).is_none());
+    }
+
+    #[test]
+    fn capture_replies_transform_classify_and_annotate() {
+        let dir = PluginDir::new();
+        let reply = r#"{"text":"fn main() {}","kind":"url","label":"plugin","group":"work"}"#;
+        dir.add(
+            "a",
+            manifest(&["transform", "classify", "annotate"]),
+            &replying(reply),
+        );
+        let mut item = text_item("hello");
+        item.label = Some("from a rule".to_string());
+
+        let item = dir.load().process_capture(item).unwrap();
+
+        assert_eq!(b"fn main() {}".to_vec(), item.content);
+        assert_eq!(None, item.html);
+        assert_eq!(Some(TextKind::Url), item.text_kind);
+        assert_eq!(Some("from a rule".to_string()), item.label);
+        assert_eq!(Some("work".to_string()), item.group_name);
+    }
+
+    #[test]
+    fn reply_fields_without_a_capability_are_ignored() {
+        let dir = PluginDir::new();
+        dir.add(
+            "a",
+            manifest(&["classify"]),
+            &replying(r#"{"text":"changed","kind":"code","group":"g"}"#),
+        );
+
+        let item = dir.load().process_capture(text_item("hello")).unwrap();
+
+        assert_eq!(b"hello".to_vec(), item.content);
+        assert_eq!(Some(TextKind::Code), item.text_kind);
+        assert_eq!(None, item.group_name);
+    }
+
+    #[test]
+    fn paste_transforms_need_their_capability_and_skip_images() {
+        let dir = PluginDir::new();
+        dir.add(
+            "a",
+            manifest(&["transform"]),
+            &replying(r#"{"text":"nope"}"#),
+        );
+        assert_eq!(
+            b"hello".to_vec(),
+            dir.load().transform_paste(text_item("hello")).content
+        );
+
+        dir.add(
+            "b",
+            manifest(&["pasteTransform"]),
+            &replying(r#"{"text":"HELLO"}"#),
+        );
+        let plugins = dir.load();
+        assert_eq!(
+            b"HELLO".to_vec(),
+            plugins.transform_paste(text_item("hello")).content
+        );
+
+        let mut image = text_item("");
+        image.content_type = ContentType::Image;
+        image.content = vec![0x89, b'P', b'N', b'G'];
+        assert_eq!(
+            image.content.clone(),
+            plugins.transform_paste(image).content
+        );
+    }
+
+    #[test]
+    fn runaway_plugins_run_out_of_fuel() {
+        let dir = PluginDir::new();
+        dir.add(
+            "a",
+            manifest(&["veto"]),
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "on_capture") (param i32 i32) (result i64)
+                   (loop $forever (br $forever))
+                   (i64.const 0)))"#,
+        );
+
+        assert!(dir.load().process_capture(text_item("hello")).is_some());
+    }
+
+    #[test]
+    fn memory_is_capped_by_the_manifest_and_the_host() {
+        // Vetoes only when it managed to grow its memory by 64 pages.
+        let greedy = r#"(module
+             (memory (export "memory") 1)
+             (data (i32.const 0) "{\"veto\":true}")
+             (func (export "alloc") (param i32) (result i32) (i32.const 4096))
+             (func (export "on_capture") (param i32 i32) (result i64)
+               (if (result i64) (i32.eq (memory.grow (i32.const 64)) (i32.const -1))
+                 (then (i64.const 0))
+                 (else (i64.const 13)))))"#;
+        let with_pages = |pages: u32| {
+            serde_json::json!({
+                "name": "greedy",
+                "capabilities": ["veto"],
+                "limits": { "memoryPages": pages },
+            })
+        };
+
+        let dir = PluginDir::new();
+        dir.add("a", with_pages(8), greedy);
+        assert!(dir.load().process_capture(text_item("hello")).is_some());
+
+        let dir = PluginDir::new();
+        dir.add("a", with_pages(128), greedy);
+        assert!(dir.load().process_capture(text_item("hello")).is_none());
+    }
+
+    #[test]
+    fn broken_plugins_are_listed_with_their_error() {
+        let dir = PluginDir::new();
+        dir.add("fine", manifest(&["veto"]), &replying("{}"));
+        dir.add(
+            "wasi",
+            manifest(&[]),
+            r#"(module (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))))"#,
+        );
+        dir.add(
+            "escape",
+            serde_json::json!({ "name": "escape", "module": "../plugin.wasm" }),
+            "(module)",
+        );
+        std::fs::create_dir_all(dir.path.join("no-manifest")).unwrap();
+
+        let plugins = dir.load();
+        let infos = plugins.infos();
+        let errors: Vec<(&str, bool)> = infos
+            .iter()
+            .map(|info| (info.id.as_str(), info.error.is_some()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("escape", true),
+                ("fine", false),
+                ("no-manifest", true),
+                ("wasi", true),
+            ],
+            errors
+        );
+        assert_eq!(1, plugins.plugins.len());
+        assert!(infos[3]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("wasi_snapshot_preview1.fd_write"));
+    }
+
+    #[test]
+    fn a_missing_plugins_directory_means_no_plugins() {
+        let plugins = PluginSet::load(Path::new("/nonexistent/clipman/plugins"));
+        assert!(plugins.is_empty());
+        assert!(plugins.infos().is_empty());
+    }
+}
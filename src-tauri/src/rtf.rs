@@ -0,0 +1,408 @@
+//! Minimal RTF reader for clips whose source offered RTF (the D2 follow-up).
+//!
+//! It covers what search and an html fallback need: text runs, paragraph and
+//! line breaks, bold/italic/underline, escaped and Unicode characters. Fonts,
+//! colours, pictures, field instructions and every other destination are
+//! dropped. Kept pure so conversions are unit-testable without a clipboard.
+
+/// Control words that open a destination whose content is never text.
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "author",
+    "colortbl",
+    "datastore",
+    "doccomm",
+    "fldinst",
+    "fonttbl",
+    "footer",
+    "footerf",
+    "footerl",
+    "footerr",
+    "generator",
+    "header",
+    "headerf",
+    "headerl",
+    "headerr",
+    "info",
+    "keywords",
+    "latentstyles",
+    "listoverridetable",
+    "listtable",
+    "object",
+    "operator",
+    "pict",
+    "rsidtbl",
+    "stylesheet",
+    "subject",
+    "themedata",
+    "title",
+    "xmlnstbl",
+];
+
+/// The code page `\'hh` escapes are decoded with when the document does not
+/// declare one. Documents in other code pages (e.g. GBK) carry the real text
+/// in `\uN` escapes, so their `\'hh` fallback bytes are dropped instead.
+const DEFAULT_CODE_PAGE: u32 = 1252;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GroupState {
+    style: Style,
+    skip: bool,
+    /// `\ucN`: how many fallback characters follow each `\uN` escape.
+    unicode_skip: usize,
+}
+
+impl Default for GroupState {
+    fn default() -> Self {
+        Self {
+            style: Style::default(),
+            skip: false,
+            unicode_skip: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Run {
+    style: Style,
+    text: String,
+}
+
+/// The document as paragraphs of styled runs; a line break inside a
+/// paragraph is kept as `'\n'` in the run text.
+#[derive(Debug)]
+struct Document {
+    paragraphs: Vec<Vec<Run>>,
+}
+
+impl Document {
+    fn push_char(&mut self, ch: char, style: Style) {
+        let paragraph = self
+            .paragraphs
+            .last_mut()
+            .expect("document always has a current paragraph");
+        match paragraph.last_mut() {
+            Some(run) if run.style == style => run.text.push(ch),
+            _ => paragraph.push(Run {
+                style,
+                text: ch.to_string(),
+            }),
+        }
+    }
+
+    fn break_paragraph(&mut self) {
+        self.paragraphs.push(Vec::new());
+    }
+
+    /// Paragraphs up to the last one holding text: RTF writers terminate the
+    /// final paragraph with `\par`, which must not become a trailing blank.
+    fn content_paragraphs(&self) -> &[Vec<Run>] {
+        let end = self
+            .paragraphs
+            .iter()
+            .rposition(|paragraph| paragraph.iter().any(|run| !run.text.is_empty()))
+            .map_or(0, |index| index + 1);
+        &self.paragraphs[..end]
+    }
+}
+
+/// Plain text of an RTF document: paragraphs joined by newlines.
+pub fn rtf_to_plain_text(rtf: &str) -> String {
+    parse(rtf)
+        .content_paragraphs()
+        .iter()
+        .map(|paragraph| paragraph.iter().map(|run| run.text.as_str()).collect())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// A simple html rendering of an RTF document: one `<p>` per paragraph,
+/// `<br>` for line breaks and `<b>`/`<i>`/`<u>` for character styles.
+pub fn rtf_to_html(rtf: &str) -> String {
+    let mut html = String::new();
+    for paragraph in parse(rtf).content_paragraphs() {
+        html.push_str("<p>");
+        for run in paragraph {
+            push_run_html(&mut html, run);
+        }
+        html.push_str("</p>");
+    }
+    html
+}
+
+/// Whether `bytes` look like an RTF document, returned as text. Clipboard
+/// RTF is 7-bit ASCII by spec (everything else is escaped), but platforms
+/// may NUL-terminate it.
+pub fn rtf_from_bytes(bytes: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_end_matches('\0');
+    text.trim_start()
+        .starts_with("{\\rtf")
+        .then(|| text.to_string())
+}
+
+fn push_run_html(html: &mut String, run: &Run) {
+    let tags: Vec<&str> = [
+        (run.style.bold, "b"),
+        (run.style.italic, "i"),
+        (run.style.underline, "u"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, tag)| enabled.then_some(tag))
+    .collect();
+
+    for tag in &tags {
+        html.push_str(&format!("<{tag}>"));
+    }
+    for ch in run.text.chars() {
+        match ch {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\n' => html.push_str("<br>"),
+            _ => html.push(ch),
+        }
+    }
+    for tag in tags.iter().rev() {
+        html.push_str(&format!("</{tag}>"));
+    }
+}
+
+fn parse(rtf: &str) -> Document {
+    let mut document = Document {
+        paragraphs: vec![Vec::new()],
+    };
+    let mut stack: Vec<GroupState> = Vec::new();
+    let mut state = GroupState::default();
+    let mut code_page = DEFAULT_CODE_PAGE;
+    // Fallback characters still to be swallowed after a `\uN` escape.
+    let mut pending_fallback = 0usize;
+    let mut chars = rtf.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => stack.push(state),
+            '}' => {
+                state = stack.pop().unwrap_or_default();
+                pending_fallback = 0;
+            }
+            // Raw line breaks are source formatting, not content.
+            '\r' | '\n' => {}
+            '\\' => match chars.next() {
+                None => break,
+                Some(symbol @ ('\\' | '{' | '}')) => {
+                    emit(&mut document, &state, &mut pending_fallback, symbol)
+                }
+                Some('\'') => {
+                    let hex: String = (0..2).filter_map(|_| chars.next()).collect();
+                    let Ok(byte) = u8::from_str_radix(&hex, 16) else {
+                        continue;
+                    };
+                    if pending_fallback > 0 {
+                        pending_fallback -= 1;
+                    } else if code_page == DEFAULT_CODE_PAGE {
+                        emit(&mut document, &state, &mut pending_fallback, cp1252(byte));
+                    }
+                }
+                Some('*') => state.skip = true,
+                Some('~') => emit(&mut document, &state, &mut pending_fallback, '\u{a0}'),
+                Some('_') => emit(&mut document, &state, &mut pending_fallback, '\u{2011}'),
+                Some('\r' | '\n') if !state.skip => document.break_paragraph(),
+                Some(first) if first.is_ascii_alphabetic() => {
+                    let mut word = first.to_string();
+                    while let Some(&next) = chars.peek() {
+                        if !next.is_ascii_alphabetic() {
+                            break;
+                        }
+                        word.push(next);
+                        chars.next();
+                    }
+
+                    let mut digits = String::new();
+                    if chars.peek() == Some(&'-') {
+                        digits.push('-');
+                        chars.next();
+                    }
+                    while let Some(&next) = chars.peek() {
+                        if !next.is_ascii_digit() {
+                            break;
+                        }
+                        digits.push(next);
+                        chars.next();
+                    }
+                    let param: Option<i32> = digits.parse().ok();
+
+                    // A single space delimits the control word and is not text.
+                    if chars.peek() == Some(&' ') {
+                        chars.next();
+                    }
+
+                    match word.as_str() {
+                        "par" | "row" if !state.skip => document.break_paragraph(),
+                        "line" => emit(&mut document, &state, &mut pending_fallback, '\n'),
+                        "tab" | "cell" => emit(&mut document, &state, &mut pending_fallback, '\t'),
+                        "emdash" => emit(&mut document, &state, &mut pending_fallback, '—'),
+                        "endash" => emit(&mut document, &state, &mut pending_fallback, '–'),
+                        "bullet" => emit(&mut document, &state, &mut pending_fallback, '•'),
+                        "lquote" => emit(&mut document, &state, &mut pending_fallback, '‘'),
+                        "rquote" => emit(&mut document, &state, &mut pending_fallback, '’'),
+                        "ldblquote" => emit(&mut document, &state, &mut pending_fallback, '“'),
+                        "rdblquote" => emit(&mut document, &state, &mut pending_fallback, '”'),
+                        "b" => state.style.bold = param != Some(0),
+                        "i" => state.style.italic = param != Some(0),
+                        "ul" => state.style.underline = param != Some(0),
+                        "ulnone" => state.style.underline = false,
+                        "plain" => state.style = Style::default(),
+                        "uc" => state.unicode_skip = param.unwrap_or(1).max(0) as usize,
+                        "ansicpg" => code_page = param.unwrap_or(0).max(0) as u32,
+                        "u" => {
+                            if let Some(code) = param {
+                                // RTF stores code units as signed 16-bit values.
+                                let code = if code < 0 { code + 65_536 } else { code };
+                                if let Some(ch) = char::from_u32(code as u32) {
+                                    pending_fallback = 0;
+                                    emit(&mut document, &state, &mut pending_fallback, ch);
+                                }
+                                pending_fallback = state.unicode_skip;
+                            }
+                        }
+                        word if SKIPPED_DESTINATIONS.contains(&word) => state.skip = true,
+                        _ => {}
+                    }
+                }
+                // `\-` (optional hyphen), `\|`, `\:` and other symbols carry no text.
+                Some(_) => {}
+            },
+            _ => emit(&mut document, &state, &mut pending_fallback, ch),
+        }
+    }
+
+    document
+}
+
+/// Emit one text character, unless it is a `\uN` fallback still owed or the
+/// current group is a skipped destination.
+fn emit(document: &mut Document, state: &GroupState, pending_fallback: &mut usize, ch: char) {
+    if *pending_fallback > 0 {
+        *pending_fallback -= 1;
+    } else if !state.skip {
+        document.push_char(ch, state.style);
+    }
+}
+
+/// Windows-1252 byte to char: Latin-1 except for the 0x80..=0x9F block.
+fn cp1252(byte: u8) -> char {
+    const HIGH_BLOCK: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+        '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}',
+        'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH_BLOCK[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Shaped like TextEdit's output: header tables first, body after.
+    const TEXTEDIT_SAMPLE: &str = "{\\rtf1\\ansi\\ansicpg1252\\cocoartf2761\n\
+        \\cocoatextscaling0\\cocoaplatform0{\\fonttbl\\f0\\fswiss\\fcharset0 Helvetica;}\n\
+        {\\colortbl;\\red255\\green255\\blue255;}\n\
+        {\\*\\expandedcolortbl;;}\n\
+        \\paperw11900\\paperh16840\\margl1440\\margr1440\\vieww11520\\viewh8400\\viewkind0\n\
+        \\pard\\tx566\\tx1133\\pardirnatural\\partightenfactor0\n\
+        \n\
+        \\f0\\fs24 \\cf0 Hello {\\b bold} and \\i italic\\i0  text\\\n\
+        second line\\par\n\
+        Next paragraph}";
+
+    #[test]
+    fn plain_text_keeps_body_and_drops_header_tables() {
+        assert_eq!(
+            "Hello bold and italic text\nsecond line\nNext paragraph",
+            rtf_to_plain_text(TEXTEDIT_SAMPLE)
+        );
+    }
+
+    #[test]
+    fn html_renders_paragraphs_and_character_styles() {
+        assert_eq!(
+            "<p>Hello <b>bold</b> and <i>italic</i> text</p>\
+             <p>second line</p><p>Next paragraph</p>",
+            rtf_to_html(TEXTEDIT_SAMPLE)
+        );
+    }
+
+    #[test]
+    fn group_scope_restores_style_and_line_breaks_stay_inside_a_paragraph() {
+        let rtf = "{\\rtf1 {\\b\\ul both}\\ul0  plain\\line next\\par}";
+
+        assert_eq!("both plain\nnext", rtf_to_plain_text(rtf));
+        assert_eq!("<p><b><u>both</u></b> plain<br>next</p>", rtf_to_html(rtf));
+    }
+
+    #[test]
+    fn unicode_escapes_swallow_their_fallback_characters() {
+        // \u233 is é with one '?' fallback; \uc0 means no fallback follows.
+        let rtf = "{\\rtf1 caf\\u233?\\par {\\uc0\\u20320\\u22909}\\u-3913?}";
+
+        assert_eq!("café\n你好\u{f0b7}", rtf_to_plain_text(rtf));
+    }
+
+    #[test]
+    fn hex_escapes_decode_as_cp1252_but_are_dropped_in_other_code_pages() {
+        assert_eq!(
+            "é “q” €",
+            rtf_to_plain_text("{\\rtf1\\ansi \\'e9 \\'93q\\'94 \\'80}")
+        );
+        // GBK bytes would decode to Latin-1 mojibake; the \u escapes carry
+        // the real text and their \'hh fallbacks are swallowed.
+        assert_eq!(
+            "中文",
+            rtf_to_plain_text("{\\rtf1\\ansi\\ansicpg936 \\u20013\\'d6\\'d0\\u25991\\'ce\\'c4}")
+        );
+    }
+
+    #[test]
+    fn escaped_braces_and_backslashes_are_text_but_destinations_are_not() {
+        let rtf = "{\\rtf1 a \\{b\\} c\\\\d{\\*\\generator Riched20;}\
+                   {\\pict\\pngblip 89504e47}{\\field{\\*\\fldinst HYPERLINK x}{\\fldrslt link}}}";
+
+        assert_eq!("a {b} c\\dlink", rtf_to_plain_text(rtf));
+    }
+
+    #[test]
+    fn html_escapes_markup_characters_in_text() {
+        assert_eq!(
+            "<p>&lt;script&gt; &amp; &quot;x&quot;</p>",
+            rtf_to_html("{\\rtf1 <script> & \"x\"}")
+        );
+    }
+
+    #[test]
+    fn table_cells_and_rows_become_tabs_and_lines() {
+        let rtf = "{\\rtf1 \\trowd a\\cell b\\cell\\row c\\cell d\\cell\\row}";
+
+        assert_eq!("a\tb\t\nc\td\t", rtf_to_plain_text(rtf));
+    }
+
+    #[test]
+    fn rtf_from_bytes_accepts_nul_terminated_rtf_only() {
+        assert_eq!(
+            Some("{\\rtf1 x}".to_string()),
+            rtf_from_bytes(b"{\\rtf1 x}\0")
+        );
+        assert_eq!(None, rtf_from_bytes(b"<p>not rtf</p>"));
+        assert_eq!(None, rtf_from_bytes(b""));
+    }
+}
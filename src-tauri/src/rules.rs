@@ -0,0 +1,494 @@
+//! User capture rules: ordered "when ... then ..." entries evaluated once per
+//! captured clip, after the built-in checks (pause, concealed marker, size,
+//! secrets, ignored apps) and before the clip is stored.
+//!
+//! A rule matches when every condition it sets holds; a rule with no
+//! conditions matches everything. All matching rules apply in order, except
+//! that a matching `Skip` ends evaluation and the first matching rule wins
+//! for the single-valued label and group. Evaluation is a pure function of
+//! the compiled rules and a [`CaptureContext`], so it never touches the
+//! clipboard, settings or storage.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{ClipItem, ContentType};
+
+/// Minutes in a day; time windows are expressed in minutes after local
+/// midnight.
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Compiled-size cap for rule patterns, same reasoning as for custom secret
+/// patterns.
+const RULE_PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureRule {
+    /// Display name, reported in the log when the rule skips a clip.
+    pub name: String,
+    pub enabled: bool,
+    /// Frontmost app at capture time, matched like `ignored_apps`
+    /// (trimmed, case-insensitive).
+    pub source_app: Option<String>,
+    /// Regex over the clip text (the path list for Files). Never matches an
+    /// image.
+    pub content_pattern: Option<String>,
+    pub content_type: Option<ContentType>,
+    /// Inclusive bounds on the stored content size in bytes (encoded PNG
+    /// for images).
+    pub min_bytes: Option<usize>,
+    pub max_bytes: Option<usize>,
+    pub time_window: Option<TimeWindow>,
+    pub action: RuleAction,
+}
+
+impl Default for CaptureRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            source_app: None,
+            content_pattern: None,
+            content_type: None,
+            min_bytes: None,
+            max_bytes: None,
+            time_window: None,
+            action: RuleAction::Skip,
+        }
+    }
+}
+
+/// Local time of day, in minutes after midnight, from `start` (inclusive)
+/// to `end` (exclusive). A window whose end is before its start runs past
+/// midnight, e.g. 22:00–06:00; equal bounds cover the whole day.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeWindow {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl TimeWindow {
+    fn contains(self, minute_of_day: u32) -> bool {
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => (self.start..self.end).contains(&minute_of_day),
+            std::cmp::Ordering::Greater => minute_of_day >= self.start || minute_of_day < self.end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleAction {
+    /// Don't capture the clip.
+    #[default]
+    Skip,
+    /// Capture it pinned.
+    Pin,
+    Label {
+        label: String,
+    },
+    Group {
+        group: String,
+    },
+    /// Capture it as a sensitive clip that expires after
+    /// `sensitive_clip_ttl_secs`.
+    Sensitive,
+}
+
+/// What the rules decided for one clip.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleOutcome {
+    /// Name of the rule that skipped the clip; the other fields are then
+    /// meaningless.
+    pub skipped_by: Option<String>,
+    pub pin: bool,
+    pub label: Option<String>,
+    pub group: Option<String>,
+    pub sensitive: bool,
+}
+
+/// The facts about a captured clip that rules can test.
+#[derive(Debug, Clone, Copy)]
+pub struct CaptureContext<'a> {
+    pub source_app: Option<&'a str>,
+    pub content_type: &'a ContentType,
+    /// Clip text, or the newline-joined path list for Files; `None` for
+    /// images.
+    pub text: Option<&'a str>,
+    pub size_bytes: usize,
+    pub minute_of_day: u32,
+}
+
+impl<'a> CaptureContext<'a> {
+    pub fn for_clip(item: &'a ClipItem, minute_of_day: u32) -> Self {
+        let text = match item.content_type {
+            ContentType::Text | ContentType::Files => std::str::from_utf8(&item.content).ok(),
+            ContentType::Image => None,
+        };
+        Self {
+            source_app: item.source_app.as_deref(),
+            content_type: &item.content_type,
+            text,
+            size_bytes: item.content.len(),
+            minute_of_day,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    name: String,
+    source_app: Option<String>,
+    content_pattern: Option<Regex>,
+    content_type: Option<ContentType>,
+    min_bytes: Option<usize>,
+    max_bytes: Option<usize>,
+    time_window: Option<TimeWindow>,
+    action: RuleAction,
+}
+
+impl CompiledRule {
+    fn matches(&self, context: &CaptureContext) -> bool {
+        if let Some(source_app) = &self.source_app {
+            let matches_app = context
+                .source_app
+                .is_some_and(|app| app.trim().to_lowercase() == *source_app);
+            if !matches_app {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.content_pattern {
+            if !context.text.is_some_and(|text| pattern.is_match(text)) {
+                return false;
+            }
+        }
+        if self
+            .content_type
+            .as_ref()
+            .is_some_and(|content_type| content_type != context.content_type)
+        {
+            return false;
+        }
+        if self.min_bytes.is_some_and(|min| context.size_bytes < min)
+            || self.max_bytes.is_some_and(|max| context.size_bytes > max)
+        {
+            return false;
+        }
+        self.time_window
+            .is_none_or(|window| window.contains(context.minute_of_day))
+    }
+}
+
+/// Check `rule` the way [`CaptureRules::new`] will compile it, so a bad rule
+/// is rejected when settings are saved rather than at capture time.
+pub fn validate_capture_rule(rule: &CaptureRule) -> Result<(), String> {
+    compile_capture_rule(rule).map(|_| ())
+}
+
+fn compile_capture_rule(rule: &CaptureRule) -> Result<CompiledRule, String> {
+    let invalid = |reason: String| format!("Invalid capture rule \"{}\": {}", rule.name, reason);
+
+    let content_pattern = rule
+        .content_pattern
+        .as_deref()
+        .map(|pattern| {
+            RegexBuilder::new(pattern)
+                .size_limit(RULE_PATTERN_SIZE_LIMIT)
+                .build()
+                .map_err(|e| invalid(e.to_string()))
+        })
+        .transpose()?;
+
+    if let (Some(min), Some(max)) = (rule.min_bytes, rule.max_bytes) {
+        if min > max {
+            return Err(invalid(format!(
+                "minimum size {} exceeds maximum size {}",
+                min, max
+            )));
+        }
+    }
+    if let Some(window) = rule.time_window {
+        if window.start >= MINUTES_PER_DAY || window.end >= MINUTES_PER_DAY {
+            return Err(invalid("time window must be within one day".to_string()));
+        }
+    }
+    match &rule.action {
+        RuleAction::Label { label } if label.is_empty() => {
+            return Err(invalid("label cannot be empty".to_string()));
+        }
+        RuleAction::Group { group } if group.is_empty() => {
+            return Err(invalid("group cannot be empty".to_string()));
+        }
+        _ => {}
+    }
+
+    Ok(CompiledRule {
+        name: rule.name.clone(),
+        source_app: rule
+            .source_app
+            .as_deref()
+            .map(|app| app.trim().to_lowercase()),
+        content_pattern,
+        content_type: rule.content_type.clone(),
+        min_bytes: rule.min_bytes,
+        max_bytes: rule.max_bytes,
+        time_window: rule.time_window,
+        action: rule.action.clone(),
+    })
+}
+
+/// The enabled rules, compiled once per settings change.
+#[derive(Debug, Default)]
+pub struct CaptureRules {
+    rules: Vec<CompiledRule>,
+}
+
+impl CaptureRules {
+    pub fn new(rules: &[CaptureRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(compile_capture_rule)
+            .collect::<Result<_, String>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn evaluate(&self, context: &CaptureContext) -> RuleOutcome {
+        let mut outcome = RuleOutcome::default();
+        for rule in self.rules.iter().filter(|rule| rule.matches(context)) {
+            match &rule.action {
+                RuleAction::Skip => {
+                    return RuleOutcome {
+                        skipped_by: Some(rule.name.clone()),
+                        ..RuleOutcome::default()
+                    };
+                }
+                RuleAction::Pin => outcome.pin = true,
+                RuleAction::Label { label } => {
+                    outcome.label.get_or_insert_with(|| label.clone());
+                }
+                RuleAction::Group { group } => {
+                    outcome.group.get_or_insert_with(|| group.clone());
+                }
+                RuleAction::Sensitive => outcome.sensitive = true,
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, action: RuleAction) -> CaptureRule {
+        CaptureRule {
+            name: name.to_string(),
+            action,
+            ..CaptureRule::default()
+        }
+    }
+
+    fn text_context<'a>(text: &'a str, source_app: Option<&'a str>) -> CaptureContext<'a> {
+        CaptureContext {
+            source_app,
+            content_type: &ContentType::Text,
+            text: Some(text),
+            size_bytes: text.len(),
+            minute_of_day: 12 * 60,
+        }
+    }
+
+    #[test]
+    fn rule_without_conditions_matches_every_clip() {
+        let rules = CaptureRules::new(&[rule("everything", RuleAction::Pin)]).unwrap();
+
+        assert!(rules.evaluate(&text_context("hello", None)).pin);
+    }
+
+    #[test]
+    fn every_condition_must_hold() {
+        let rules = CaptureRules::new(&[CaptureRule {
+            source_app: Some("Terminal".to_string()),
+            content_pattern: Some(r"^git ".to_string()),
+            content_type: Some(ContentType::Text),
+            max_bytes: Some(100),
+            ..rule(
+                "git commands",
+                RuleAction::Label {
+                    label: "git".to_string(),
+                },
+            )
+        }])
+        .unwrap();
+
+        let labeled = |context: CaptureContext| rules.evaluate(&context).label;
+        assert_eq!(
+            Some("git".to_string()),
+            labeled(text_context("git status", Some(" terminal ")))
+        );
+        assert_eq!(None, labeled(text_context("git status", Some("Notes"))));
+        assert_eq!(None, labeled(text_context("git status", None)));
+        assert_eq!(None, labeled(text_context("ls -la", Some("Terminal"))));
+        assert_eq!(
+            None,
+            labeled(text_context(
+                &format!("git {}", "x".repeat(100)),
+                Some("Terminal")
+            ))
+        );
+    }
+
+    #[test]
+    fn content_pattern_never_matches_images() {
+        let rules = CaptureRules::new(&[CaptureRule {
+            content_pattern: Some(".*".to_string()),
+            ..rule("any text", RuleAction::Skip)
+        }])
+        .unwrap();
+        let image = CaptureContext {
+            source_app: None,
+            content_type: &ContentType::Image,
+            text: None,
+            size_bytes: 10,
+            minute_of_day: 0,
+        };
+
+        assert_eq!(None, rules.evaluate(&image).skipped_by);
+    }
+
+    #[test]
+    fn time_window_may_wrap_past_midnight() {
+        let night = TimeWindow {
+            start: 22 * 60,
+            end: 6 * 60,
+        };
+        assert!(night.contains(23 * 60));
+        assert!(night.contains(0));
+        assert!(!night.contains(6 * 60));
+        assert!(!night.contains(12 * 60));
+
+        let morning = TimeWindow {
+            start: 9 * 60,
+            end: 12 * 60,
+        };
+        assert!(morning.contains(9 * 60));
+        assert!(!morning.contains(12 * 60));
+
+        assert!(TimeWindow { start: 0, end: 0 }.contains(17 * 60));
+    }
+
+    #[test]
+    fn skip_ends_evaluation_and_first_label_wins() {
+        let rules = CaptureRules::new(&[
+            rule("pin", RuleAction::Pin),
+            rule(
+                "work",
+                RuleAction::Label {
+                    label: "work".to_string(),
+                },
+            ),
+            rule(
+                "other",
+                RuleAction::Label {
+                    label: "other".to_string(),
+                },
+            ),
+            rule("sensitive", RuleAction::Sensitive),
+        ])
+        .unwrap();
+        assert_eq!(
+            RuleOutcome {
+                skipped_by: None,
+                pin: true,
+                label: Some("work".to_string()),
+                group: None,
+                sensitive: true,
+            },
+            rules.evaluate(&text_context("notes", None))
+        );
+
+        let rules = CaptureRules::new(&[
+            rule("pin", RuleAction::Pin),
+            rule("drop", RuleAction::Skip),
+            rule("sensitive", RuleAction::Sensitive),
+        ])
+        .unwrap();
+        assert_eq!(
+            RuleOutcome {
+                skipped_by: Some("drop".to_string()),
+                ..RuleOutcome::default()
+            },
+            rules.evaluate(&text_context("notes", None))
+        );
+    }
+
+    #[test]
+    fn disabled_rules_are_ignored_and_invalid_rules_rejected() {
+        let disabled = CaptureRule {
+            enabled: false,
+            content_pattern: Some("(".to_string()),
+            ..rule("off", RuleAction::Skip)
+        };
+        assert!(CaptureRules::new(&[disabled]).unwrap().is_empty());
+
+        let bad_pattern = CaptureRule {
+            content_pattern: Some("(".to_string()),
+            ..rule("bad", RuleAction::Skip)
+        };
+        assert!(validate_capture_rule(&bad_pattern)
+            .unwrap_err()
+            .contains("\"bad\""));
+
+        let bad_window = CaptureRule {
+            time_window: Some(TimeWindow {
+                start: 0,
+                end: MINUTES_PER_DAY,
+            }),
+            ..rule("late", RuleAction::Skip)
+        };
+        assert!(validate_capture_rule(&bad_window).is_err());
+
+        let bad_sizes = CaptureRule {
+            min_bytes: Some(10),
+            max_bytes: Some(5),
+            ..rule("sizes", RuleAction::Skip)
+        };
+        assert!(validate_capture_rule(&bad_sizes).is_err());
+
+        let empty_label = rule(
+            "label",
+            RuleAction::Label {
+                label: String::new(),
+            },
+        );
+        assert!(validate_capture_rule(&empty_label).is_err());
+    }
+
+    #[test]
+    fn rules_deserialize_with_defaults_and_tagged_actions() {
+        let rule: CaptureRule = serde_json::from_value(serde_json::json!({
+            "name": "Slack to work",
+            "sourceApp": "Slack",
+            "timeWindow": { "start": 540, "end": 1080 },
+            "action": { "type": "group", "group": "work" }
+        }))
+        .unwrap();
+
+        assert!(rule.enabled);
+        assert_eq!(Some("Slack".to_string()), rule.source_app);
+        assert_eq!(
+            RuleAction::Group {
+                group: "work".to_string()
+            },
+            rule.action
+        );
+    }
+}
@@ -1,8 +1,23 @@
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
+use crate::api::{generate_api_token, DEFAULT_API_PORT, MIN_API_TOKEN_LEN};
+use crate::hooks::{validate_capture_hook, CaptureHook};
+use crate::merge::{validate_merge_format, MergeFormat};
+use crate::normalize::TextNormalizers;
+use crate::paste_keys::{default_paste_key_profiles, validate_paste_key_profile, PasteKeyProfile};
+use crate::placement::{
+    QuickBarOffset, QuickBarPlacement, QUICKBAR_MAX_HEIGHT, QUICKBAR_MAX_WIDTH,
+    QUICKBAR_MIN_HEIGHT, QUICKBAR_MIN_WIDTH,
+};
+use crate::rules::{validate_capture_rule, CaptureRule, CaptureRules, RuleAction};
+use crate::secrets::{compile_secret_pattern, SecretAction, SecretDetector, SecretPattern};
+use crate::shortcuts::{
+    find_shortcut_conflict, shortcut_keys, validate_action_shortcut, ActionShortcut, ShortcutAction,
+};
+
 const DEFAULT_LOCALE: &str = "zh-CN";
 const SETTINGS_KEY: &str = "settings";
 const LEGACY_SETTINGS_KEYS: [&str; 16] = [
@@ -29,8 +44,44 @@ const LEGACY_SETTINGS_KEYS: [&str; 16] = [
 pub struct Settings {
     pub global_shortcut: String,
     pub auto_paste: bool,
+    /// When true, a simulated paste puts back whatever the clipboard held
+    /// before, `restore_clipboard_delay_ms` after the paste shortcut, so
+    /// pasting from history does not replace the user's clipboard.
+    pub restore_clipboard_after_paste: bool,
+    /// How long the pasted clip stays on the clipboard before the previous
+    /// contents return. Apps that read the clipboard lazily need a moment
+    /// after the shortcut to fetch it.
+    pub restore_clipboard_delay_ms: u64,
+    /// Pause between keystrokes of a type-out paste. Remote consoles drop
+    /// characters that arrive faster than they poll the keyboard.
+    pub type_paste_char_delay_ms: u64,
+    /// Longest clip, in characters, a type-out paste accepts.
+    pub type_paste_max_chars: usize,
+    /// Global shortcut that stops a type-out paste. Registered only while
+    /// typing, so it does not shadow the key the rest of the time.
+    pub type_paste_cancel_shortcut: String,
+    /// How merge-paste lays out the selected clips' text, unless the paste
+    /// request names a format itself.
+    pub merge_format: MergeFormat,
+    /// When true, merge-pasting only images stacks them top to bottom into
+    /// one image instead of refusing the merge.
+    pub merge_images: bool,
+    /// Keys pressed to paste into specific apps, first match wins; others get
+    /// Ctrl+V (Cmd+V on macOS).
+    pub paste_key_profiles: Vec<PasteKeyProfile>,
+    /// Where the QuickBar opens on the monitor under the cursor.
+    pub quickbar_placement: QuickBarPlacement,
+    /// QuickBar size in logical px, within the `placement` caps. Smaller
+    /// screens shrink it further.
+    pub quickbar_width: u32,
+    pub quickbar_height: u32,
+    /// Where the QuickBar last closed, for `QuickBarPlacement::LastPosition`.
+    /// Owned by `window`, like `paused_until` is by `pause`.
+    pub quickbar_last_position: Option<QuickBarOffset>,
     pub ignore_concealed: bool,
     pub pinned_shortcut: Option<String>,
+    /// Global shortcuts bound to actions other than opening the QuickBar.
+    pub action_shortcuts: Vec<ActionShortcut>,
     pub max_history_items: usize,
     pub tray_text_length: usize,
     pub max_pinned_in_tray: usize,
@@ -44,19 +95,85 @@ pub struct Settings {
     /// Images whose longest side exceeds this many pixels are downsampled
     /// before being stored (§5). `0` disables downscaling.
     pub max_image_dimension: u32,
+    /// Opt-in cleanups (trim, ANSI escapes, tracking parameters, CRLF,
+    /// zero-width characters) applied to captured text before it is
+    /// deduplicated and stored.
+    pub text_normalizers: TextNormalizers,
     /// When true, Text clips matching a high-confidence secret pattern
     /// (PEM private key, cloud/API token, JWT, ...) are skipped at capture
     /// time instead of being recorded (SPEC-4 §2).
     pub skip_secrets: bool,
+    /// Whether a Text clip matching a secret rule is dropped or stored with
+    /// the matched spans redacted. Only consulted when `skip_secrets` is on.
+    pub secret_action: SecretAction,
+    /// Extra named regexes checked alongside the built-in secret patterns
+    /// (internal token formats, credentialed URLs). Validated on save;
+    /// invalid entries stored by an older build are dropped on load.
+    pub custom_secret_patterns: Vec<SecretPattern>,
+    /// When true, long random-looking tokens also count as secrets.
+    pub entropy_detection: bool,
+    /// Shannon entropy, in bits per character, at or above which a token is
+    /// treated as a secret by `entropy_detection`.
+    pub entropy_threshold: f64,
     /// App names whose copies are never captured, matched case-insensitively
     /// against the frontmost app at capture time (SPEC-4 §3). Normalized on
     /// every load/save: trimmed, emptied entries dropped, deduplicated, and
     /// capped at 100 entries.
     pub ignored_apps: Vec<String>,
+    /// When true (and `ignore_concealed` is on), content the source app
+    /// marked concealed or transient is kept as a sensitive clip that
+    /// expires after `sensitive_clip_ttl_secs`, instead of being skipped.
+    pub concealed_as_sensitive: bool,
+    /// When true, copies from `ignored_apps` are kept as sensitive clips
+    /// instead of being skipped.
+    pub ignored_apps_as_sensitive: bool,
+    /// How long a sensitive clip (including secrets under
+    /// `SecretAction::Expire`) is kept before it is deleted and, if still
+    /// on the system clipboard, cleared from it.
+    pub sensitive_clip_ttl_secs: u64,
+    /// Ordered user rules applied to each capture that passed the built-in
+    /// checks (see `rules`). Validated on save; invalid entries stored by an
+    /// older build are dropped on load.
+    pub capture_rules: Vec<CaptureRule>,
     /// When true, the clipboard monitor observes clipboard changes but
     /// captures nothing at all, regardless of source app or content
     /// (SPEC-4 §3). Toggled from the tray's "Pause Capture" menu item.
     pub capture_paused: bool,
+    /// When true, a timed pause ("pause for 1 hour") is written to
+    /// `paused_until` and outlives an app restart. Off by default: a timed
+    /// pause ends with the app.
+    pub persist_timed_pause: bool,
+    /// Deadline (Unix seconds) of a persisted timed pause. Owned by
+    /// `pause`, like `capture_paused` is by the tray toggle.
+    pub paused_until: Option<i64>,
+    /// When true, every format the source app offered (text, html, rtf,
+    /// images, custom types) is stored with the clip and restored together
+    /// on paste, instead of only the single representative format (D1).
+    /// Off by default: it multiplies storage per clip.
+    pub full_fidelity_capture: bool,
+    /// When true, a captured image whose perceptual hash is within
+    /// `similar_image_threshold` bits of a stored image refreshes that clip
+    /// instead of adding a new one. Off by default: exact dedup only.
+    pub collapse_similar_images: bool,
+    /// Largest perceptual-hash distance (in bits, out of 64) at which two
+    /// images count as visually identical, for both collapsing on capture
+    /// and grouping similar images.
+    pub similar_image_threshold: u32,
+    /// When true, the loopback HTTP API (see `api`) listens on `api_port`.
+    /// Off by default.
+    pub api_enabled: bool,
+    pub api_port: u16,
+    /// Bearer token every API request must carry. Generated when the API is
+    /// turned on without one.
+    pub api_token: String,
+    /// Commands run on every stored capture (see `hooks`), in order.
+    /// Validated on save; entries without a command are dropped on load.
+    pub capture_hooks: Vec<CaptureHook>,
+    /// Hook processes allowed to run at once, across all captures.
+    pub max_concurrent_hooks: usize,
+    /// When true, WebAssembly plugins (see `plugins`) are loaded from the
+    /// data directory and run at capture and paste. Off by default.
+    pub plugins_enabled: bool,
 }
 
 impl Default for Settings {
@@ -64,8 +181,21 @@ impl Default for Settings {
         Self {
             global_shortcut: "CommandOrControl+Shift+V".to_string(),
             auto_paste: true,
+            restore_clipboard_after_paste: false,
+            restore_clipboard_delay_ms: DEFAULT_RESTORE_CLIPBOARD_DELAY_MS,
+            type_paste_char_delay_ms: 10,
+            type_paste_max_chars: 5_000,
+            type_paste_cancel_shortcut: DEFAULT_TYPE_PASTE_CANCEL_SHORTCUT.to_string(),
+            merge_format: MergeFormat::default(),
+            merge_images: false,
+            paste_key_profiles: default_paste_key_profiles(),
+            quickbar_placement: QuickBarPlacement::default(),
+            quickbar_width: QUICKBAR_MAX_WIDTH as u32,
+            quickbar_height: QUICKBAR_MAX_HEIGHT as u32,
+            quickbar_last_position: None,
             ignore_concealed: true,
             pinned_shortcut: None,
+            action_shortcuts: Vec::new(),
             max_history_items: 100,
             tray_text_length: 70,
             max_pinned_in_tray: 5,
@@ -75,9 +205,29 @@ impl Default for Settings {
             locale: DEFAULT_LOCALE.to_string(),
             max_text_bytes: 2_000_000,
             max_image_dimension: 4096,
+            text_normalizers: TextNormalizers::default(),
             skip_secrets: true,
+            secret_action: SecretAction::Skip,
+            custom_secret_patterns: Vec::new(),
+            entropy_detection: false,
+            entropy_threshold: DEFAULT_ENTROPY_THRESHOLD,
             ignored_apps: Vec::new(),
+            concealed_as_sensitive: false,
+            ignored_apps_as_sensitive: false,
+            sensitive_clip_ttl_secs: DEFAULT_SENSITIVE_CLIP_TTL_SECS,
+            capture_rules: Vec::new(),
             capture_paused: false,
+            persist_timed_pause: false,
+            paused_until: None,
+            full_fidelity_capture: false,
+            collapse_similar_images: false,
+            similar_image_threshold: 5,
+            api_enabled: false,
+            api_port: DEFAULT_API_PORT,
+            api_token: String::new(),
+            capture_hooks: Vec::new(),
+            max_concurrent_hooks: 2,
+            plugins_enabled: false,
         }
     }
 }
@@ -91,8 +241,36 @@ impl Settings {
 
         self.normalize_common();
 
-        if self.pinned_shortcut.as_deref() == Some(self.global_shortcut.as_str()) {
-            return Err("Pinned shortcut cannot match the main global shortcut".to_string());
+        for binding in &self.action_shortcuts {
+            validate_action_shortcut(binding)?;
+        }
+        let bindings = self.shortcut_bindings();
+        let shortcuts: Vec<&str> = bindings.iter().map(|(_, shortcut)| *shortcut).collect();
+        if let Some((earlier, later)) = find_shortcut_conflict(&shortcuts) {
+            return Err(format!(
+                "The {} cannot match the {}",
+                bindings[later].0, bindings[earlier].0
+            ));
+        }
+
+        validate_merge_format(&self.merge_format)?;
+        for profile in &self.paste_key_profiles {
+            validate_paste_key_profile(profile)?;
+        }
+        for pattern in &self.custom_secret_patterns {
+            compile_secret_pattern(pattern)?;
+        }
+        for rule in &self.capture_rules {
+            validate_capture_rule(rule)?;
+        }
+        for hook in &self.capture_hooks {
+            validate_capture_hook(hook)?;
+        }
+        if self.api_token_too_short() {
+            return Err(format!(
+                "API token must be at least {} characters",
+                MIN_API_TOKEN_LEN
+            ));
         }
 
         Ok(self)
@@ -106,10 +284,84 @@ impl Settings {
 
         self.normalize_common();
 
-        if self.pinned_shortcut.as_deref() == Some(self.global_shortcut.as_str()) {
+        if self
+            .pinned_shortcut
+            .as_deref()
+            .is_some_and(|pinned| shortcut_keys(pinned) == shortcut_keys(&self.global_shortcut))
+        {
             log::warn!("Pinned shortcut matches main shortcut on load; clearing pinned shortcut");
             self.pinned_shortcut = None;
         }
+        if self.type_paste_cancel_shortcut_conflicts() {
+            log::warn!("Typing cancel shortcut matches another shortcut on load; resetting it");
+            self.type_paste_cancel_shortcut = DEFAULT_TYPE_PASTE_CANCEL_SHORTCUT.to_string();
+        }
+        let mut taken: Vec<String> = [
+            Some(self.global_shortcut.as_str()),
+            self.pinned_shortcut.as_deref(),
+            Some(self.type_paste_cancel_shortcut.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .map(shortcut_keys)
+        .collect();
+        self.action_shortcuts.retain(|binding| {
+            if let Err(e) = validate_action_shortcut(binding) {
+                log::warn!("{}; dropping it", e);
+                return false;
+            }
+            let keys = shortcut_keys(&binding.shortcut);
+            if taken.contains(&keys) {
+                log::warn!(
+                    "Action shortcut '{}' matches another shortcut on load; dropping it",
+                    binding.shortcut
+                );
+                return false;
+            }
+            taken.push(keys);
+            true
+        });
+        if let Err(e) = validate_merge_format(&self.merge_format) {
+            log::warn!("{} on load; merging as plain text", e);
+            self.merge_format = MergeFormat::default();
+        }
+        self.paste_key_profiles.retain(|profile| {
+            let result = validate_paste_key_profile(profile);
+            if let Err(e) = &result {
+                log::warn!(
+                    "{}; dropping the paste key profile for '{}'",
+                    e,
+                    profile.app
+                );
+            }
+            result.is_ok()
+        });
+
+        self.custom_secret_patterns.retain(|pattern| {
+            let result = compile_secret_pattern(pattern);
+            if let Err(e) = &result {
+                log::warn!("{}; dropping it", e);
+            }
+            result.is_ok()
+        });
+        self.capture_rules.retain(|rule| {
+            let result = validate_capture_rule(rule);
+            if let Err(e) = &result {
+                log::warn!("{}; dropping it", e);
+            }
+            result.is_ok()
+        });
+        self.capture_hooks.retain(|hook| {
+            let result = validate_capture_hook(hook);
+            if let Err(e) = &result {
+                log::warn!("{}; dropping it", e);
+            }
+            result.is_ok()
+        });
+        if self.api_token_too_short() {
+            log::warn!("API token is too short on load; turning the API off");
+            self.api_enabled = false;
+        }
 
         self
     }
@@ -127,9 +379,86 @@ impl Settings {
         self.max_recent_in_tray = self.max_recent_in_tray.clamp(0, 100);
         self.max_text_bytes = self.max_text_bytes.clamp(4096, 50_000_000);
         self.max_image_dimension = clamp_max_image_dimension(self.max_image_dimension);
+        self.similar_image_threshold = self.similar_image_threshold.min(16);
+        self.sensitive_clip_ttl_secs = self.sensitive_clip_ttl_secs.clamp(5, 86_400);
+        self.restore_clipboard_delay_ms = self.restore_clipboard_delay_ms.clamp(100, 10_000);
+        self.type_paste_char_delay_ms = self.type_paste_char_delay_ms.min(1_000);
+        self.type_paste_max_chars = self.type_paste_max_chars.clamp(1, 100_000);
+        self.quickbar_width = self
+            .quickbar_width
+            .clamp(QUICKBAR_MIN_WIDTH as u32, QUICKBAR_MAX_WIDTH as u32);
+        self.quickbar_height = self
+            .quickbar_height
+            .clamp(QUICKBAR_MIN_HEIGHT as u32, QUICKBAR_MAX_HEIGHT as u32);
+        self.type_paste_cancel_shortcut = self.type_paste_cancel_shortcut.trim().to_string();
+        if self.type_paste_cancel_shortcut.is_empty() {
+            self.type_paste_cancel_shortcut = DEFAULT_TYPE_PASTE_CANCEL_SHORTCUT.to_string();
+        }
         self.ignored_apps = normalize_ignored_apps(std::mem::take(&mut self.ignored_apps));
+        self.custom_secret_patterns =
+            normalize_secret_patterns(std::mem::take(&mut self.custom_secret_patterns));
+        self.capture_rules = normalize_capture_rules(std::mem::take(&mut self.capture_rules));
+        self.capture_hooks = normalize_capture_hooks(std::mem::take(&mut self.capture_hooks));
+        self.max_concurrent_hooks = self.max_concurrent_hooks.clamp(1, 16);
+        for binding in &mut self.action_shortcuts {
+            binding.shortcut = binding.shortcut.trim().to_string();
+            if let ShortcutAction::OpenGroup { group } = &mut binding.action {
+                *group = group.trim().to_string();
+            }
+        }
+        for profile in &mut self.paste_key_profiles {
+            profile.app = profile.app.trim().to_string();
+            profile.keys = profile.keys.trim().to_string();
+        }
+        self.entropy_threshold = if self.entropy_threshold.is_finite() {
+            self.entropy_threshold.clamp(3.0, 6.0)
+        } else {
+            DEFAULT_ENTROPY_THRESHOLD
+        };
 
         self.locale = normalize_locale(&self.locale);
+
+        self.api_port = self.api_port.max(1024);
+        self.api_token = self.api_token.trim().to_string();
+        if self.api_enabled && self.api_token.is_empty() {
+            self.api_token = generate_api_token();
+        }
+    }
+
+    fn api_token_too_short(&self) -> bool {
+        self.api_enabled && self.api_token.len() < MIN_API_TOKEN_LEN
+    }
+
+    fn type_paste_cancel_shortcut_conflicts(&self) -> bool {
+        let cancel = shortcut_keys(&self.type_paste_cancel_shortcut);
+        cancel == shortcut_keys(&self.global_shortcut)
+            || self
+                .pinned_shortcut
+                .as_deref()
+                .is_some_and(|pinned| shortcut_keys(pinned) == cancel)
+    }
+
+    /// Every global shortcut with a name for conflict errors, in the order
+    /// they take precedence in.
+    fn shortcut_bindings(&self) -> Vec<(String, &str)> {
+        let mut bindings = vec![(
+            "main global shortcut".to_string(),
+            self.global_shortcut.as_str(),
+        )];
+        if let Some(pinned) = &self.pinned_shortcut {
+            bindings.push(("pinned shortcut".to_string(), pinned.as_str()));
+        }
+        bindings.push((
+            "typing cancel shortcut".to_string(),
+            self.type_paste_cancel_shortcut.as_str(),
+        ));
+        for binding in &self.action_shortcuts {
+            bindings.push((
+                format!("action shortcut '{}'", binding.shortcut),
+                binding.shortcut.as_str(),
+            ));
+        }
+        bindings
     }
 }
 
@@ -169,6 +498,117 @@ fn normalize_ignored_apps(apps: Vec<String>) -> Vec<String> {
         .collect()
 }
 
+const DEFAULT_ENTROPY_THRESHOLD: f64 = 4.5;
+
+/// Long enough to paste a password once or twice, like a password manager's
+/// clipboard timeout.
+const DEFAULT_SENSITIVE_CLIP_TTL_SECS: u64 = 30;
+
+/// Enough for editors that fetch the clipboard on the next event-loop turn
+/// after Ctrl+V, short enough that a quick copy afterwards is unaffected.
+const DEFAULT_RESTORE_CLIPBOARD_DELAY_MS: u64 = 500;
+
+const DEFAULT_TYPE_PASTE_CANCEL_SHORTCUT: &str = "Escape";
+
+/// Cap on the number of custom secret patterns checked per captured clip.
+const MAX_CUSTOM_SECRET_PATTERNS: usize = 50;
+
+/// Trims names and patterns, drops entries missing either, keeps the first
+/// of any duplicate name (names are what the skip log reports), and caps
+/// the list at `MAX_CUSTOM_SECRET_PATTERNS`.
+fn normalize_secret_patterns(patterns: Vec<SecretPattern>) -> Vec<SecretPattern> {
+    let mut seen = std::collections::HashSet::new();
+    patterns
+        .into_iter()
+        .map(|pattern| SecretPattern {
+            name: pattern.name.trim().to_string(),
+            pattern: pattern.pattern.trim().to_string(),
+        })
+        .filter(|pattern| !pattern.name.is_empty() && !pattern.pattern.is_empty())
+        .filter(|pattern| seen.insert(pattern.name.clone()))
+        .take(MAX_CUSTOM_SECRET_PATTERNS)
+        .collect()
+}
+
+/// Cap on the number of capture rules evaluated per captured clip.
+const MAX_CAPTURE_RULES: usize = 100;
+
+/// Trims every free-text field, turns blank optional conditions into "no
+/// condition", and caps the list at `MAX_CAPTURE_RULES`. Order is kept: it
+/// is the evaluation order.
+fn normalize_capture_rules(rules: Vec<CaptureRule>) -> Vec<CaptureRule> {
+    fn trimmed(value: Option<String>) -> Option<String> {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    rules
+        .into_iter()
+        .map(|rule| CaptureRule {
+            name: rule.name.trim().to_string(),
+            source_app: trimmed(rule.source_app),
+            content_pattern: trimmed(rule.content_pattern),
+            action: match rule.action {
+                RuleAction::Label { label } => RuleAction::Label {
+                    label: label.trim().to_string(),
+                },
+                RuleAction::Group { group } => RuleAction::Group {
+                    group: group.trim().to_string(),
+                },
+                action => action,
+            },
+            ..rule
+        })
+        .take(MAX_CAPTURE_RULES)
+        .collect()
+}
+
+/// Cap on the number of hooks run per new clip.
+const MAX_CAPTURE_HOOKS: usize = 20;
+
+/// Trims every free-text field, turns a blank app filter into "any app",
+/// keeps timeouts between 1 s and 5 min, and caps the list at
+/// `MAX_CAPTURE_HOOKS`. Order is kept: it is the run order.
+fn normalize_capture_hooks(hooks: Vec<CaptureHook>) -> Vec<CaptureHook> {
+    hooks
+        .into_iter()
+        .map(|hook| CaptureHook {
+            name: hook.name.trim().to_string(),
+            command: hook.command.trim().to_string(),
+            source_app: hook
+                .source_app
+                .map(|app| app.trim().to_string())
+                .filter(|app| !app.is_empty()),
+            timeout_secs: hook.timeout_secs.clamp(1, 300),
+            ..hook
+        })
+        .take(MAX_CAPTURE_HOOKS)
+        .collect()
+}
+
+/// Like `build_secret_detector`: a failure here is unexpected, and capture
+/// falls back to no rules.
+fn build_capture_rules(settings: &Settings) -> CaptureRules {
+    CaptureRules::new(&settings.capture_rules).unwrap_or_else(|e| {
+        log::error!("{}; capture rules disabled", e);
+        CaptureRules::default()
+    })
+}
+
+/// Compile the secret rules for `settings`. Settings reaching here have been
+/// validated or normalized, so a compile failure is unexpected; fall back
+/// to the built-in patterns rather than disabling detection.
+fn build_secret_detector(settings: &Settings) -> SecretDetector {
+    let entropy_threshold = settings
+        .entropy_detection
+        .then_some(settings.entropy_threshold);
+    SecretDetector::new(&settings.custom_secret_patterns, entropy_threshold).unwrap_or_else(|e| {
+        log::error!("{}; using built-in secret patterns only", e);
+        SecretDetector::default()
+    })
+}
+
 fn settings_from_legacy_store(mut get: impl FnMut(&str) -> Option<serde_json::Value>) -> Settings {
     let mut candidate = Settings::default();
 
@@ -247,6 +687,10 @@ fn settings_from_legacy_store(mut get: impl FnMut(&str) -> Option<serde_json::Va
 
 pub struct SettingsManager {
     settings: Mutex<Settings>,
+    /// Compiled from `settings` whenever they change, so capture never
+    /// compiles a user pattern.
+    secret_detector: Mutex<Arc<SecretDetector>>,
+    capture_rules: Mutex<Arc<CaptureRules>>,
 }
 
 impl Default for SettingsManager {
@@ -259,6 +703,8 @@ impl SettingsManager {
     pub fn new() -> Self {
         Self {
             settings: Mutex::new(Settings::default()),
+            secret_detector: Mutex::new(Arc::new(SecretDetector::default())),
+            capture_rules: Mutex::new(Arc::new(CaptureRules::default())),
         }
     }
 
@@ -273,7 +719,7 @@ impl SettingsManager {
         };
 
         let normalized = candidate.normalize_for_load();
-        *crate::safe_lock(&self.settings) = normalized;
+        self.set(normalized);
 
         log::info!("Settings loaded: {:?}", self.get());
         Ok(())
@@ -317,8 +763,18 @@ impl SettingsManager {
     }
 
     pub fn set(&self, settings: Settings) {
+        *crate::safe_lock(&self.secret_detector) = Arc::new(build_secret_detector(&settings));
+        *crate::safe_lock(&self.capture_rules) = Arc::new(build_capture_rules(&settings));
         *crate::safe_lock(&self.settings) = settings;
     }
+
+    pub fn secret_detector(&self) -> Arc<SecretDetector> {
+        crate::safe_lock(&self.secret_detector).clone()
+    }
+
+    pub fn capture_rules(&self) -> Arc<CaptureRules> {
+        crate::safe_lock(&self.capture_rules).clone()
+    }
 }
 
 #[cfg(test)]
@@ -367,6 +823,71 @@ mod tests {
         assert!(result.unwrap_err().contains("cannot match"));
     }
 
+    #[test]
+    fn enabling_the_api_generates_a_token_and_rejects_short_ones() {
+        let enabled = Settings {
+            api_enabled: true,
+            api_port: 80,
+            ..Settings::default()
+        }
+        .validate_and_normalize()
+        .unwrap();
+        assert_eq!(64, enabled.api_token.len());
+        assert_eq!(1024, enabled.api_port);
+        assert!(Settings::default()
+            .validate_and_normalize()
+            .unwrap()
+            .api_token
+            .is_empty());
+
+        let short = Settings {
+            api_enabled: true,
+            api_token: "letmein".to_string(),
+            ..Settings::default()
+        };
+        assert!(short.clone().validate_and_normalize().is_err());
+        assert!(!short.normalize_for_load().api_enabled);
+    }
+
+    #[test]
+    fn action_shortcuts_cannot_reuse_any_other_shortcut() {
+        let action = |shortcut: &str| ActionShortcut {
+            shortcut: shortcut.to_string(),
+            action: ShortcutAction::TogglePause,
+        };
+        let with_actions = |shortcuts: &[&str]| Settings {
+            pinned_shortcut: Some("Alt+P".to_string()),
+            action_shortcuts: shortcuts.iter().map(|shortcut| action(shortcut)).collect(),
+            ..Settings::default()
+        };
+
+        with_actions(&["Alt+1", "Alt+2"])
+            .validate_and_normalize()
+            .unwrap();
+        let error = with_actions(&["Alt+1", "shift+commandorcontrol+v"])
+            .validate_and_normalize()
+            .unwrap_err();
+        assert_eq!(
+            "The action shortcut 'shift+commandorcontrol+v' cannot match the main global shortcut",
+            error
+        );
+        assert!(with_actions(&["Alt+1", " alt+p "])
+            .validate_and_normalize()
+            .is_err());
+        assert!(with_actions(&["Alt+1", "Alt+1"])
+            .validate_and_normalize()
+            .is_err());
+
+        let loaded =
+            with_actions(&["Alt+1", "alt+p", "Alt+1", "Escape", "Alt+2"]).normalize_for_load();
+        let kept: Vec<&str> = loaded
+            .action_shortcuts
+            .iter()
+            .map(|binding| binding.shortcut.as_str())
+            .collect();
+        assert_eq!(vec!["Alt+1", "Alt+2"], kept);
+    }
+
     #[test]
     fn settings_load_normalization_clears_conflicting_pinned_shortcut() {
         let default_settings = Settings::default();
@@ -417,11 +938,161 @@ mod tests {
         assert_eq!(4096, settings.max_image_dimension);
     }
 
+    #[test]
+    fn sensitive_clip_settings_default_off_and_clamp_ttl() {
+        let defaults = Settings::default();
+        assert!(!defaults.concealed_as_sensitive);
+        assert!(!defaults.ignored_apps_as_sensitive);
+        assert_eq!(30, defaults.sensitive_clip_ttl_secs);
+
+        for (stored, expected) in [(0, 5), (45, 45), (u64::MAX, 86_400)] {
+            let settings = Settings {
+                sensitive_clip_ttl_secs: stored,
+                ..Settings::default()
+            }
+            .validate_and_normalize()
+            .unwrap();
+            assert_eq!(expected, settings.sensitive_clip_ttl_secs);
+        }
+    }
+
+    #[test]
+    fn clipboard_restore_defaults_off_and_clamps_delay() {
+        let defaults = Settings::default();
+        assert!(!defaults.restore_clipboard_after_paste);
+        assert_eq!(500, defaults.restore_clipboard_delay_ms);
+
+        for (stored, expected) in [(0, 100), (750, 750), (u64::MAX, 10_000)] {
+            let settings = Settings {
+                restore_clipboard_delay_ms: stored,
+                ..Settings::default()
+            }
+            .validate_and_normalize()
+            .unwrap();
+            assert_eq!(expected, settings.restore_clipboard_delay_ms);
+        }
+    }
+
+    #[test]
+    fn type_paste_cancel_shortcut_must_differ_from_the_other_shortcuts() {
+        let conflicting = Settings {
+            type_paste_cancel_shortcut: " CommandOrControl+Shift+V ".to_string(),
+            ..Settings::default()
+        };
+        assert!(conflicting.clone().validate_and_normalize().is_err());
+        assert_eq!(
+            "Escape",
+            conflicting.normalize_for_load().type_paste_cancel_shortcut
+        );
+
+        let blank = Settings {
+            type_paste_cancel_shortcut: "  ".to_string(),
+            type_paste_max_chars: 0,
+            ..Settings::default()
+        }
+        .validate_and_normalize()
+        .unwrap();
+        assert_eq!("Escape", blank.type_paste_cancel_shortcut);
+        assert_eq!(1, blank.type_paste_max_chars);
+    }
+
+    #[test]
+    fn empty_merge_template_is_rejected_on_save_and_reset_on_load() {
+        let empty = Settings {
+            merge_format: MergeFormat::Template {
+                template: String::new(),
+            },
+            ..Settings::default()
+        };
+        assert!(empty.clone().validate_and_normalize().is_err());
+        assert_eq!(MergeFormat::Plain, empty.normalize_for_load().merge_format);
+
+        let stored: Settings = serde_json::from_value(serde_json::json!({
+            "mergeFormat": { "type": "template", "template": "{index}: {content}" }
+        }))
+        .unwrap();
+        assert_eq!(
+            MergeFormat::Template {
+                template: "{index}: {content}".to_string()
+            },
+            stored.merge_format
+        );
+    }
+
+    #[test]
+    fn invalid_paste_key_profiles_are_rejected_on_save_and_dropped_on_load() {
+        let profiles = vec![
+            PasteKeyProfile {
+                app: " kitty ".to_string(),
+                keys: " Ctrl+Shift+V ".to_string(),
+            },
+            PasteKeyProfile {
+                app: "vim".to_string(),
+                keys: "Ctrl+Shift".to_string(),
+            },
+        ];
+        let settings = Settings {
+            paste_key_profiles: profiles,
+            ..Settings::default()
+        };
+
+        assert!(settings.clone().validate_and_normalize().is_err());
+        assert_eq!(
+            vec![PasteKeyProfile {
+                app: "kitty".to_string(),
+                keys: "Ctrl+Shift+V".to_string(),
+            }],
+            settings.normalize_for_load().paste_key_profiles
+        );
+    }
+
+    #[test]
+    fn quickbar_size_is_clamped_to_the_placement_caps() {
+        let defaults = Settings::default();
+        assert_eq!(QuickBarPlacement::Center, defaults.quickbar_placement);
+        assert_eq!(
+            (820, 600),
+            (defaults.quickbar_width, defaults.quickbar_height)
+        );
+
+        let settings = Settings {
+            quickbar_width: 10_000,
+            quickbar_height: 0,
+            ..Settings::default()
+        }
+        .validate_and_normalize()
+        .unwrap();
+        assert_eq!(
+            (820, 280),
+            (settings.quickbar_width, settings.quickbar_height)
+        );
+    }
+
     #[test]
     fn default_settings_skip_secrets_is_enabled() {
         assert!(Settings::default().skip_secrets);
     }
 
+    #[test]
+    fn secret_action_round_trips_and_defaults_to_skip_when_missing() {
+        let loaded =
+            serde_json::from_value::<Settings>(serde_json::json!({ "skipSecrets": true })).unwrap();
+        assert_eq!(SecretAction::Skip, loaded.secret_action);
+
+        let redact = Settings {
+            secret_action: SecretAction::Redact,
+            ..Settings::default()
+        };
+        let value = serde_json::to_value(&redact).unwrap();
+        assert_eq!("redact", value["secretAction"]);
+        assert_eq!(
+            SecretAction::Redact,
+            serde_json::from_value::<Settings>(value)
+                .unwrap()
+                .secret_action
+        );
+    }
+
     #[test]
     fn settings_normalization_clamps_max_text_bytes_to_supported_range() {
         let too_small = Settings {
@@ -490,6 +1161,185 @@ mod tests {
         assert!(!settings.capture_paused);
     }
 
+    #[test]
+    fn full_fidelity_capture_is_opt_in_and_survives_a_store_round_trip() {
+        assert!(!Settings::default().full_fidelity_capture);
+
+        let stored = serde_json::json!({ "fullFidelityCapture": true });
+        let loaded = serde_json::from_value::<Settings>(stored)
+            .unwrap()
+            .normalize_for_load();
+
+        assert!(loaded.full_fidelity_capture);
+    }
+
+    #[test]
+    fn similar_image_collapse_is_opt_in_and_threshold_is_capped() {
+        let defaults = Settings::default();
+        assert!(!defaults.collapse_similar_images);
+        assert_eq!(5, defaults.similar_image_threshold);
+
+        let stored = serde_json::json!({
+            "collapseSimilarImages": true,
+            "similarImageThreshold": 40
+        });
+        let loaded = serde_json::from_value::<Settings>(stored)
+            .unwrap()
+            .normalize_for_load();
+
+        assert!(loaded.collapse_similar_images);
+        assert_eq!(16, loaded.similar_image_threshold);
+    }
+
+    fn secret_pattern(name: &str, pattern: &str) -> SecretPattern {
+        SecretPattern {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[test]
+    fn custom_secret_patterns_are_normalized_and_validated_on_save() {
+        let settings = Settings {
+            custom_secret_patterns: vec![
+                secret_pattern(" Acme key ", r" acme_[0-9a-f]{32} "),
+                secret_pattern("Acme key", "duplicate name"),
+                secret_pattern("", "nameless"),
+                secret_pattern("Empty", "  "),
+            ],
+            ..Settings::default()
+        }
+        .validate_and_normalize()
+        .unwrap();
+        assert_eq!(
+            vec![secret_pattern("Acme key", "acme_[0-9a-f]{32}")],
+            settings.custom_secret_patterns
+        );
+
+        let invalid = Settings {
+            custom_secret_patterns: vec![secret_pattern("Broken", "[a-")],
+            ..Settings::default()
+        };
+        let error = invalid.validate_and_normalize().unwrap_err();
+        assert!(error.contains("Broken"), "{error}");
+
+        let empty_matching = Settings {
+            custom_secret_patterns: vec![secret_pattern("Loose", "x*")],
+            ..Settings::default()
+        };
+        let error = empty_matching.validate_and_normalize().unwrap_err();
+        assert!(error.contains("Loose"), "{error}");
+    }
+
+    #[test]
+    fn invalid_custom_secret_patterns_are_dropped_on_load_and_threshold_is_clamped() {
+        let stored = serde_json::json!({
+            "customSecretPatterns": [
+                { "name": "Broken", "pattern": "(unclosed" },
+                { "name": "Matches everything", "pattern": "(token)?" },
+                { "name": "Internal token", "pattern": "itk_[A-Za-z0-9]{20}" }
+            ],
+            "entropyDetection": true,
+            "entropyThreshold": 9.0
+        });
+        let loaded = serde_json::from_value::<Settings>(stored)
+            .unwrap()
+            .normalize_for_load();
+
+        assert_eq!(
+            vec![secret_pattern("Internal token", "itk_[A-Za-z0-9]{20}")],
+            loaded.custom_secret_patterns
+        );
+        assert!(loaded.entropy_detection);
+        assert_eq!(6.0, loaded.entropy_threshold);
+    }
+
+    #[test]
+    fn settings_manager_rebuilds_the_secret_detector_on_set() {
+        let manager = SettingsManager::new();
+        let token = format!("itk_{}", "Ab1".repeat(7));
+        assert_eq!(None, manager.secret_detector().detect(&token));
+
+        manager.set(Settings {
+            custom_secret_patterns: vec![secret_pattern("Internal token", "itk_[A-Za-z0-9]{20}")],
+            ..Settings::default()
+        });
+        assert_eq!(
+            Some("Internal token".to_string()),
+            manager.secret_detector().detect(&token)
+        );
+    }
+
+    #[test]
+    fn settings_capture_rules_are_trimmed_validated_on_save_and_dropped_on_load() {
+        let rule = |name: &str, pattern: &str| CaptureRule {
+            name: name.to_string(),
+            content_pattern: Some(pattern.to_string()),
+            source_app: Some("  ".to_string()),
+            action: RuleAction::Label {
+                label: " work ".to_string(),
+            },
+            ..CaptureRule::default()
+        };
+
+        let saved = Settings {
+            capture_rules: vec![rule(" Tickets ", r"JIRA-\d+")],
+            ..Settings::default()
+        }
+        .validate_and_normalize()
+        .unwrap();
+        assert_eq!("Tickets", saved.capture_rules[0].name);
+        assert_eq!(None, saved.capture_rules[0].source_app);
+        assert_eq!(
+            RuleAction::Label {
+                label: "work".to_string()
+            },
+            saved.capture_rules[0].action
+        );
+
+        let with_invalid = Settings {
+            capture_rules: vec![rule("broken", "("), rule("fine", "x")],
+            ..Settings::default()
+        };
+        assert!(with_invalid.clone().validate_and_normalize().is_err());
+        let loaded = with_invalid.normalize_for_load();
+        assert_eq!(1, loaded.capture_rules.len());
+        assert_eq!("fine", loaded.capture_rules[0].name);
+    }
+
+    #[test]
+    fn settings_capture_hooks_need_a_command() {
+        let hook = |name: &str, command: &str| CaptureHook {
+            name: name.to_string(),
+            command: command.to_string(),
+            source_app: Some(" ".to_string()),
+            timeout_secs: 0,
+            ..CaptureHook::default()
+        };
+
+        let saved = Settings {
+            capture_hooks: vec![hook(" log urls ", " tee -a urls.txt ")],
+            max_concurrent_hooks: 0,
+            ..Settings::default()
+        }
+        .validate_and_normalize()
+        .unwrap();
+        assert_eq!("log urls", saved.capture_hooks[0].name);
+        assert_eq!("tee -a urls.txt", saved.capture_hooks[0].command);
+        assert_eq!(None, saved.capture_hooks[0].source_app);
+        assert_eq!(1, saved.capture_hooks[0].timeout_secs);
+        assert_eq!(1, saved.max_concurrent_hooks);
+
+        let with_invalid = Settings {
+            capture_hooks: vec![hook("blank", "  "), hook("fine", "true")],
+            ..Settings::default()
+        };
+        assert!(with_invalid.clone().validate_and_normalize().is_err());
+        let loaded = with_invalid.normalize_for_load();
+        assert_eq!(1, loaded.capture_hooks.len());
+        assert_eq!("fine", loaded.capture_hooks[0].name);
+    }
+
     #[test]
     fn settings_normalization_trims_dedupes_and_drops_empty_ignored_apps() {
         let settings = Settings {
@@ -568,6 +1418,35 @@ mod tests {
         assert!(saved_then_reloaded.capture_paused);
     }
 
+    #[test]
+    fn settings_text_normalizers_default_off_and_accept_partial_objects() {
+        assert_eq!(
+            TextNormalizers::default(),
+            Settings::default().text_normalizers
+        );
+
+        let json = serde_json::json!({ "textNormalizers": { "stripTrackingParams": true } });
+        let settings: Settings = serde_json::from_value(json).unwrap();
+        assert!(settings.text_normalizers.strip_tracking_params);
+        assert!(!settings.text_normalizers.trim_whitespace);
+    }
+
+    #[test]
+    fn settings_timed_pause_fields_default_off_and_round_trip() {
+        let defaults: Settings = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(!defaults.persist_timed_pause);
+        assert_eq!(None, defaults.paused_until);
+
+        let json = serde_json::json!({ "persistTimedPause": true, "pausedUntil": 1_700_000_000 });
+        let settings: Settings = serde_json::from_value(json).unwrap();
+        let reloaded = settings
+            .validate_and_normalize()
+            .unwrap()
+            .normalize_for_load();
+        assert!(reloaded.persist_timed_pause);
+        assert_eq!(Some(1_700_000_000), reloaded.paused_until);
+    }
+
     #[test]
     fn settings_store_format_loads_new_object_and_legacy_keys() {
         let new_json = serde_json::json!({
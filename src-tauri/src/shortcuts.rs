@@ -0,0 +1,180 @@
+//! Global shortcuts bound to actions other than opening the QuickBar: paste
+//! a recent or pinned clip directly, toggle the capture pause, open the QuickBar on a
+//! search or a group, and paste through the paste stack.
+//!
+//! Bindings live in `Settings::action_shortcuts` and are registered next to
+//! the QuickBar shortcuts in `commands`. Every registered shortcut
+//! (main, pinned, typing cancel and these) must use distinct keys; keys are
+//! compared the way the OS sees them, ignoring case, spacing and modifier
+//! order.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ShortcutAction {
+    /// Paste the `index`th clip (from 1) of the recent list, as the tray
+    /// shows it, without opening the QuickBar.
+    PasteRecent {
+        index: usize,
+    },
+    /// Paste the clip in pinned slot `slot` (from 1). Slots follow the pinned
+    /// order the QuickBar shows and `reorder_pinned` changes; an empty slot
+    /// pastes nothing.
+    PastePinnedSlot {
+        slot: usize,
+    },
+    /// Paste the most recent clip without its formatting.
+    PastePlainText,
+    TogglePause,
+    /// Open the QuickBar with the search box focused, holding `query`.
+    OpenSearch {
+        #[serde(default)]
+        query: String,
+    },
+    /// Open the QuickBar on the clips capture rules put in `group`.
+    OpenGroup {
+        group: String,
+    },
+    /// Paste the oldest clip of the paste stack and drop it from the stack.
+    PasteStackNext,
+}
+
+impl ShortcutAction {
+    /// Whether the action presses the paste keys.
+    pub fn pastes(&self) -> bool {
+        matches!(
+            self,
+            Self::PasteRecent { .. }
+                | Self::PastePinnedSlot { .. }
+                | Self::PastePlainText
+                | Self::PasteStackNext
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionShortcut {
+    pub shortcut: String,
+    pub action: ShortcutAction,
+}
+
+pub fn validate_action_shortcut(binding: &ActionShortcut) -> Result<(), String> {
+    if binding.shortcut.trim().is_empty() {
+        return Err("Action shortcut cannot be empty".to_string());
+    }
+    match &binding.action {
+        ShortcutAction::PasteRecent { index: 0 } => {
+            Err("Recent clips are numbered from 1".to_string())
+        }
+        ShortcutAction::PastePinnedSlot { slot: 0 } => {
+            Err("Pinned slots are numbered from 1".to_string())
+        }
+        ShortcutAction::OpenGroup { group } if group.trim().is_empty() => {
+            Err("Open-group shortcut needs a group name".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `shortcut` as the keys it presses: case, spacing and the order of the
+/// modifiers do not matter to the OS, so `shift + ctrl+v` and `Ctrl+Shift+V`
+/// are the same binding.
+pub fn shortcut_keys(shortcut: &str) -> String {
+    let mut parts: Vec<String> = shortcut
+        .split('+')
+        .map(|part| part.trim().to_lowercase())
+        .collect();
+    let key = parts.pop().unwrap_or_default();
+    parts.sort_unstable();
+    parts.push(key);
+    parts.join("+")
+}
+
+/// The first binding that uses the same keys as an earlier one, as the
+/// indices of the earlier and the later binding.
+pub fn find_shortcut_conflict(shortcuts: &[&str]) -> Option<(usize, usize)> {
+    let keys: Vec<String> = shortcuts.iter().map(|s| shortcut_keys(s)).collect();
+    keys.iter().enumerate().find_map(|(later, later_keys)| {
+        keys[..later]
+            .iter()
+            .position(|earlier_keys| earlier_keys == later_keys)
+            .map(|earlier| (earlier, later))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcut_keys_ignore_case_spacing_and_modifier_order() {
+        assert_eq!(
+            shortcut_keys("CommandOrControl+Shift+V"),
+            shortcut_keys(" shift + commandorcontrol+v ")
+        );
+        assert_ne!(shortcut_keys("Ctrl+Shift+V"), shortcut_keys("Ctrl+V"));
+        assert_ne!(shortcut_keys("Shift+V"), shortcut_keys("V+Shift"));
+    }
+
+    #[test]
+    fn conflicts_name_the_earlier_and_later_binding() {
+        assert_eq!(None, find_shortcut_conflict(&["Alt+1", "Alt+2", "Escape"]));
+        assert_eq!(
+            Some((1, 3)),
+            find_shortcut_conflict(&["Alt+1", "Alt+2", "Escape", "alt+2", "Alt+1"])
+        );
+    }
+
+    #[test]
+    fn bindings_need_keys_and_valid_arguments() {
+        let binding = |action| ActionShortcut {
+            shortcut: "Alt+1".to_string(),
+            action,
+        };
+        validate_action_shortcut(&binding(ShortcutAction::PasteRecent { index: 1 })).unwrap();
+        assert!(
+            validate_action_shortcut(&binding(ShortcutAction::PasteRecent { index: 0 })).is_err()
+        );
+        assert!(
+            validate_action_shortcut(&binding(ShortcutAction::PastePinnedSlot { slot: 0 }))
+                .is_err()
+        );
+        assert!(
+            validate_action_shortcut(&binding(ShortcutAction::OpenGroup {
+                group: " ".to_string()
+            }))
+            .is_err()
+        );
+        assert!(validate_action_shortcut(&ActionShortcut {
+            shortcut: " ".to_string(),
+            action: ShortcutAction::TogglePause,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn actions_deserialize_from_the_frontend_shape() {
+        let bindings: Vec<ActionShortcut> = serde_json::from_str(
+            r#"[
+                {"shortcut": "Alt+1", "action": {"type": "pasteRecent", "index": 1}},
+                {"shortcut": "Alt+S", "action": {"type": "openSearch"}},
+                {"shortcut": "Alt+G", "action": {"type": "openGroup", "group": "tickets"}}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ShortcutAction::OpenSearch {
+                query: String::new()
+            },
+            bindings[1].action
+        );
+        assert_eq!(
+            ShortcutAction::OpenGroup {
+                group: "tickets".to_string()
+            },
+            bindings[2].action
+        );
+    }
+}
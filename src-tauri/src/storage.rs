@@ -1,5 +1,7 @@
 use rusqlite::{params, Connection, OptionalExtension, Result, Row};
 use serde::{Deserialize, Serialize};
+
+use crate::classify::TextKind;
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::ErrorKind;
@@ -15,7 +17,7 @@ pub enum ContentType {
 }
 
 impl ContentType {
-    fn as_db_value(&self) -> &str {
+    pub fn as_db_value(&self) -> &'static str {
         match self {
             ContentType::Text => "text",
             ContentType::Image => "image",
@@ -78,6 +80,61 @@ pub struct ClipItem {
     pub source_app: Option<String>,
     /// Optional HTML companion to a Text clip's plain-text `content` (D2).
     pub html: Option<String>,
+    /// Optional RTF companion to a Text clip, kept verbatim so native apps
+    /// get their own rich format back on paste.
+    pub rtf: Option<String>,
+    /// dHash of an Image clip (see `phash`), used to find visually identical
+    /// images that the exact content hash treats as distinct.
+    pub perceptual_hash: Option<u64>,
+    /// What a Text clip holds (URL, email, code, ...), from `classify` at
+    /// capture time. `None` for images and files.
+    pub text_kind: Option<TextKind>,
+    /// Best-guess language when `text_kind` is `Code`.
+    pub code_language: Option<String>,
+    /// Set when capture replaced detected secrets in `content` and `html`
+    /// with a placeholder, so the clip is not the text that was copied.
+    #[serde(default)]
+    pub is_redacted: bool,
+    /// Unix time after which a sensitive clip (a detected secret, concealed
+    /// or ignored-app copy kept only briefly) is deleted. `None` for
+    /// ordinary clips, which live until pruned.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    /// Hash of the `CopyMarker` the clipboard monitor computed for the copy
+    /// this clip came from. `content` may differ from what the clipboard
+    /// holds (normalized, redacted, downscaled or rewritten by a plugin), so
+    /// expiry compares the clipboard against this instead. `None` for clips
+    /// stored before markers were recorded.
+    #[serde(default)]
+    pub capture_marker: Option<String>,
+    /// Every raw format the source app offered, captured only in
+    /// full-fidelity mode. Lives in the `clip_formats` child table rather
+    /// than `CLIP_COLUMNS`; only `get_by_id` (the read that feeds a
+    /// clipboard write) loads it.
+    #[serde(default)]
+    pub formats: Vec<ClipFormat>,
+}
+
+impl ClipItem {
+    /// The clipboard marker recorded when this clip was captured, if any.
+    pub fn capture_copy_marker(&self) -> Option<CopyMarker> {
+        self.capture_marker.as_ref().map(|hash| CopyMarker {
+            hash: hash.clone(),
+            content_type: self.content_type.clone(),
+        })
+    }
+}
+
+/// One raw clipboard representation kept alongside a clip in full-fidelity
+/// mode: the platform's own format identifier (a UTI on macOS, a clipboard
+/// format name on Windows) and its untouched bytes. D1 still picks the
+/// clip's representative `content`; these only ride along for restore.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipFormat {
+    pub format: String,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +151,11 @@ pub struct ClipPreviewItem {
     pub group_name: Option<String>,
     pub source_app: Option<String>,
     pub has_html: bool,
+    pub has_rtf: bool,
+    pub text_kind: Option<TextKind>,
+    pub code_language: Option<String>,
+    pub is_redacted: bool,
+    pub expires_at: Option<i64>,
 }
 
 // Frontend-optimized version: converts images to data URLs for zero-cost rendering
@@ -110,6 +172,11 @@ pub struct FrontendClipItem {
     pub group_name: Option<String>,
     pub source_app: Option<String>,
     pub has_html: bool,
+    pub has_rtf: bool,
+    pub text_kind: Option<TextKind>,
+    pub code_language: Option<String>,
+    pub is_redacted: bool,
+    pub expires_at: Option<i64>,
 }
 
 impl ClipPreviewItem {
@@ -140,6 +207,11 @@ impl ClipPreviewItem {
             group_name: item.group_name.clone(),
             source_app: item.source_app.clone(),
             has_html: item.html.is_some(),
+            has_rtf: item.rtf.is_some(),
+            text_kind: item.text_kind,
+            code_language: item.code_language.clone(),
+            is_redacted: item.is_redacted,
+            expires_at: item.expires_at,
         }
     }
 }
@@ -168,6 +240,11 @@ impl FrontendClipItem {
             group_name: item.group_name,
             source_app: item.source_app,
             has_html: item.has_html,
+            has_rtf: item.has_rtf,
+            text_kind: item.text_kind,
+            code_language: item.code_language,
+            is_redacted: item.is_redacted,
+            expires_at: item.expires_at,
         }
     }
 
@@ -184,6 +261,7 @@ impl FrontendClipItem {
 
         let content = BASE64.encode(&item.content);
         let has_html = item.html.is_some();
+        let has_rtf = item.rtf.is_some();
 
         Some(Self {
             id: item.id,
@@ -196,21 +274,123 @@ impl FrontendClipItem {
             group_name: item.group_name,
             source_app: item.source_app,
             has_html,
+            has_rtf,
+            text_kind: item.text_kind,
+            code_language: item.code_language,
+            is_redacted: item.is_redacted,
+            expires_at: item.expires_at,
         })
     }
 }
 
+/// What [`ClipStorage::replace_text`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextReplacement {
+    Replaced,
+    /// Another clip already held the new text; it was refreshed and the
+    /// replaced clip deleted.
+    MergedInto(String),
+    /// The clip is gone, pinned or not Text; nothing changed.
+    Unchanged,
+}
+
+/// What `clip_events` records. Statistics count these as they happen rather
+/// than re-deriving them from whichever clips are still stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipEventKind {
+    Captured,
+    Pasted,
+    SecretSkipped,
+    OversizedSkipped,
+}
+
+impl ClipEventKind {
+    fn as_db_value(self) -> &'static str {
+        match self {
+            ClipEventKind::Captured => "captured",
+            ClipEventKind::Pasted => "pasted",
+            ClipEventKind::SecretSkipped => "secret_skipped",
+            ClipEventKind::OversizedSkipped => "oversized_skipped",
+        }
+    }
+}
+
+/// Events older than this are dropped as new ones are recorded.
+pub const CLIP_EVENT_RETENTION_DAYS: i64 = 90;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayCount {
+    /// Local date, `YYYY-MM-DD`.
+    pub day: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppCount {
+    pub app: Option<String>,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentTypeCount {
+    pub content_type: ContentType,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PastedClip {
+    pub clip: FrontendClipItem,
+    pub pastes: i64,
+}
+
+/// Bytes held by the stored clips, by column. `other` is rtf plus raw
+/// clipboard formats; `database` is the whole file, free pages included.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageBreakdown {
+    pub text: i64,
+    pub html: i64,
+    pub images: i64,
+    pub thumbnails: i64,
+    pub other: i64,
+    pub database: i64,
+}
+
+/// Everything `get_statistics` reports. The counts cover events since
+/// `since`; `storage` and `stored_clips` describe the database as it is now.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipStatistics {
+    pub since: i64,
+    pub clips_per_day: Vec<DayCount>,
+    pub clips_per_app: Vec<AppCount>,
+    pub content_types: Vec<ContentTypeCount>,
+    pub top_pasted: Vec<PastedClip>,
+    pub secrets_skipped: i64,
+    pub oversized_skipped: i64,
+    pub stored_clips: i64,
+    pub storage: StorageBreakdown,
+}
+
 pub struct ClipStorage {
     conn: Connection,
 }
 
 const CLIP_COLUMNS: &str =
-    "id, content, thumbnail, content_type, timestamp, is_pinned, pin_order, label, group_name, source_app, html";
+    "id, content, thumbnail, content_type, timestamp, is_pinned, pin_order, label, group_name, source_app, html, rtf, perceptual_hash,
+     text_kind, code_language, is_redacted, expires_at, capture_marker";
 const CLIP_PREVIEW_COLUMNS: &str = "id,
-     CASE WHEN content_type IN ('text','files') THEN substr(content, 1, 4096) ELSE x'' END AS preview_content,
+     CASE WHEN content_type IN ('text','files') THEN IFNULL(substr(content, 1, 4096), x'') ELSE x'' END AS preview_content,
      thumbnail,
      content_type, timestamp, is_pinned, pin_order, label, group_name, source_app,
-     (html IS NOT NULL) AS has_html";
+     (html IS NOT NULL) AS has_html,
+     (rtf IS NOT NULL) AS has_rtf,
+     text_kind, code_language, is_redacted, expires_at";
 const FTS_REBUILD_BATCH_SIZE: i64 = 100;
 const TEXT_PREVIEW_BYTES: usize = 4096;
 
@@ -226,6 +406,7 @@ impl ClipStorage {
             .map_err(string_to_rusqlite_error)?;
 
         Self::initialize_fts(&conn)?;
+        Self::classify_unclassified_text_with_conn(&conn)?;
         Self::ensure_incremental_auto_vacuum(&conn)?;
 
         let storage = Self { conn };
@@ -239,6 +420,42 @@ impl ClipStorage {
         Ok(storage)
     }
 
+    /// Text clips captured before classification existed have no kind yet;
+    /// classify them once so kind filters cover the whole history. Later
+    /// opens find nothing to do, since every capture stores a kind (`Plain`
+    /// included).
+    fn classify_unclassified_text_with_conn(conn: &Connection) -> Result<()> {
+        let unclassified = {
+            let mut stmt = conn.prepare(
+                "SELECT id, content FROM clips
+                 WHERE content_type = 'text' AND text_kind IS NULL",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?;
+            rows.collect::<Result<Vec<_>>>()?
+        };
+        if unclassified.is_empty() {
+            return Ok(());
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        for (id, content) in &unclassified {
+            let classification = crate::classify::classify_text(&String::from_utf8_lossy(content));
+            tx.execute(
+                "UPDATE clips SET text_kind = ?1, code_language = ?2 WHERE id = ?3",
+                params![
+                    classification.kind.as_db_value(),
+                    classification.code_language,
+                    id
+                ],
+            )?;
+        }
+        tx.commit()?;
+        log::info!("🏷️ Classified {} existing text clip(s)", unclassified.len());
+        Ok(())
+    }
+
     /// One-time migration to incremental auto_vacuum (§4). `auto_vacuum`
     /// only takes effect after a `VACUUM`, so a database created before this
     /// change (or by an older ClipMan version) pays a one-time full rewrite
@@ -287,7 +504,24 @@ impl ClipStorage {
 
     pub fn insert(&self, item: &ClipItem, max_history_items: usize) -> Result<Option<String>> {
         let tx = self.conn.unchecked_transaction()?;
-        let result = Self::insert_with_conn(&tx, item, max_history_items)?;
+        let result = Self::insert_with_conn(&tx, item, max_history_items, None)?;
+        tx.commit()?;
+        self.reclaim_space();
+        Ok(result)
+    }
+
+    /// Like [`Self::insert`], but an Image clip whose perceptual hash is
+    /// within `max_distance` bits of a stored image collapses into that clip
+    /// (timestamp and source refreshed, original pixels kept) exactly as an
+    /// exact duplicate would.
+    pub fn insert_collapsing_similar_images(
+        &self,
+        item: &ClipItem,
+        max_history_items: usize,
+        max_distance: u32,
+    ) -> Result<Option<String>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let result = Self::insert_with_conn(&tx, item, max_history_items, Some(max_distance))?;
         tx.commit()?;
         self.reclaim_space();
         Ok(result)
@@ -297,6 +531,7 @@ impl ClipStorage {
         conn: &Connection,
         item: &ClipItem,
         max_history_items: usize,
+        similar_image_distance: Option<u32>,
     ) -> Result<Option<String>> {
         let content_hash = hash_bytes(&item.content);
 
@@ -316,22 +551,50 @@ impl ClipStorage {
                 "⏭️ Duplicate content detected (hash: {}), updating timestamp",
                 &content_hash[..8]
             );
-            Self::refresh_duplicate_with_conn(
-                conn,
-                &id,
-                item.timestamp,
-                item.html.as_deref(),
-                item.source_app.as_deref(),
-            )?;
+            Self::refresh_duplicate_with_conn(conn, &id, item)?;
+            // Same "present metadata wins" rule as D6: a re-copy that carried
+            // formats replaces the stored set, one without keeps the old set.
+            if !item.formats.is_empty() {
+                Self::replace_clip_formats_with_conn(conn, &id, &item.formats)?;
+            }
             return Ok(Some(id));
         }
 
+        if let (Some(max_distance), Some(perceptual_hash)) =
+            (similar_image_distance, item.perceptual_hash)
+        {
+            let nearest = Self::similar_images_with_conn(conn, perceptual_hash, max_distance)?
+                .into_iter()
+                .next();
+            if let Some((id, distance)) = nearest {
+                log::debug!(
+                    "⏭️ Near-duplicate image detected ({} bit(s) from {}), updating timestamp",
+                    distance,
+                    id
+                );
+                Self::refresh_duplicate_with_conn(conn, &id, item)?;
+                return Ok(Some(id));
+            }
+        }
+
+        // A clip captured pinned (by a capture rule) joins the end of the
+        // pinned list, like one pinned by hand.
+        let pin_order = match (item.is_pinned, item.pin_order) {
+            (true, None) => Some(Self::next_pin_order_with_conn(conn)?),
+            (_, pin_order) => pin_order,
+        };
+
         conn.execute(
             "INSERT INTO clips (
                 id, content, thumbnail, content_hash, content_type, timestamp,
-                is_pinned, pin_order, label, group_name, source_app, html
+                is_pinned, pin_order, label, group_name, source_app, html, rtf,
+                perceptual_hash, text_kind, code_language, is_redacted, expires_at,
+                capture_marker
              )
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+             VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                ?19
+             )",
             params![
                 item.id,
                 item.content,
@@ -340,20 +603,121 @@ impl ClipStorage {
                 item.content_type.as_db_value(),
                 item.timestamp,
                 item.is_pinned as i32,
-                item.pin_order,
+                pin_order,
                 item.label,
                 item.group_name,
                 item.source_app,
                 item.html,
+                item.rtf,
+                item.perceptual_hash.map(|hash| hash as i64),
+                item.text_kind.map(TextKind::as_db_value),
+                item.code_language,
+                item.is_redacted as i32,
+                item.expires_at,
+                item.capture_marker,
             ],
         )?;
 
+        Self::replace_clip_formats_with_conn(conn, &item.id, &item.formats)?;
         Self::sync_fts_for_clip_id_with_conn(conn, &item.id)?;
         Self::prune_history_with_conn(conn, max_history_items)?;
 
         Ok(None)
     }
 
+    fn replace_clip_formats_with_conn(
+        conn: &Connection,
+        id: &str,
+        formats: &[ClipFormat],
+    ) -> Result<()> {
+        conn.execute("DELETE FROM clip_formats WHERE clip_id = ?1", params![id])?;
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO clip_formats (clip_id, format, data) VALUES (?1, ?2, ?3)",
+        )?;
+        for format in formats {
+            stmt.execute(params![id, format.format, format.data])?;
+        }
+        Ok(())
+    }
+
+    /// Image clips within `max_distance` bits of `perceptual_hash`, nearest
+    /// first and newest first among equals. SQLite has no popcount, so the
+    /// distance is computed here over the (small, history-capped) image set.
+    fn similar_images_with_conn(
+        conn: &Connection,
+        perceptual_hash: u64,
+        max_distance: u32,
+    ) -> Result<Vec<(String, u32)>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, perceptual_hash FROM clips
+             WHERE content_type = 'image' AND perceptual_hash IS NOT NULL
+             ORDER BY timestamp DESC, id DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+        })?;
+
+        let mut similar = Vec::new();
+        for row in rows {
+            let (id, hash) = row?;
+            let distance = crate::phash::hamming_distance(perceptual_hash, hash);
+            if distance <= max_distance {
+                similar.push((id, distance));
+            }
+        }
+        // Stable sort keeps the newest-first order among equal distances.
+        similar.sort_by_key(|(_, distance)| *distance);
+        Ok(similar)
+    }
+
+    /// Other image clips that look like clip `id` (within `max_distance`
+    /// bits), nearest first. Empty when `id` is not an image or predates
+    /// perceptual hashing.
+    pub fn get_similar_image_previews(
+        &self,
+        id: &str,
+        max_distance: u32,
+    ) -> Result<Vec<ClipPreviewItem>> {
+        let perceptual_hash: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT perceptual_hash FROM clips WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        let Some(perceptual_hash) = perceptual_hash else {
+            return Ok(Vec::new());
+        };
+
+        let mut previews = Vec::new();
+        for (similar_id, _) in
+            Self::similar_images_with_conn(&self.conn, perceptual_hash as u64, max_distance)?
+        {
+            if similar_id == id {
+                continue;
+            }
+            if let Some(preview) = self.get_preview_by_id(&similar_id)? {
+                previews.push(preview);
+            }
+        }
+        Ok(previews)
+    }
+
+    fn clip_formats_with_conn(conn: &Connection, id: &str) -> Result<Vec<ClipFormat>> {
+        let mut stmt = conn.prepare(
+            "SELECT format, data FROM clip_formats WHERE clip_id = ?1 ORDER BY rowid ASC",
+        )?;
+        let formats = stmt.query_map([id], |row| {
+            Ok(ClipFormat {
+                format: row.get(0)?,
+                data: row.get(1)?,
+            })
+        })?;
+        formats.collect()
+    }
+
     pub fn get_recent_clip_previews(&self, limit: usize) -> Result<Vec<ClipPreviewItem>> {
         let mut stmt = self.conn.prepare(&format!(
             "SELECT {CLIP_PREVIEW_COLUMNS}
@@ -425,19 +789,65 @@ impl ClipStorage {
         items.collect()
     }
 
+    /// The id of the clip in pinned slot `slot` (from 1): the pinned list in
+    /// `reorder_pinned` order, so unpinning or deleting a clip moves the later
+    /// ones up a slot. `None` when fewer clips are pinned.
+    pub fn pinned_slot_clip_id(&self, slot: usize) -> Result<Option<String>> {
+        let Some(offset) = slot.checked_sub(1) else {
+            return Ok(None);
+        };
+        self.conn
+            .query_row(
+                "SELECT id
+                 FROM clips
+                 WHERE is_pinned = 1
+                 ORDER BY pin_order IS NULL, pin_order ASC, timestamp DESC
+                 LIMIT 1 OFFSET ?1",
+                [offset],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// Every clip, pinned or not, that capture rules put in `group`, newest
+    /// first; what the open-group shortcut shows.
+    pub fn get_group_clip_previews(&self, group: &str) -> Result<Vec<ClipPreviewItem>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {CLIP_PREVIEW_COLUMNS}
+             FROM clips
+             WHERE group_name = ?1
+             ORDER BY timestamp DESC, id DESC
+             LIMIT 1000"
+        ))?;
+
+        let items = stmt.query_map([group], Self::preview_from_row)?;
+        items.collect()
+    }
+
     pub fn search_clip_previews(&self, query: &str) -> Result<Vec<ClipPreviewItem>> {
-        log::info!("🔍 Searching previews for: {}", query);
+        self.search_clip_previews_of_kind(query, None)
+    }
 
+    /// Search restricted to Text clips of one `kind` (or unrestricted when
+    /// `None`). An empty query lists every clip of that kind.
+    pub fn search_clip_previews_of_kind(
+        &self,
+        query: &str,
+        kind: Option<TextKind>,
+    ) -> Result<Vec<ClipPreviewItem>> {
+        log::info!("🔍 Searching previews for: {} (kind: {:?})", query, kind);
+
+        let kind = kind.map(TextKind::as_db_value);
         let query = query.trim();
         if query.is_empty() {
-            return self.get_all_previews_for_search();
+            return self.get_all_previews_for_search(kind);
         }
 
         if query.chars().count() < 3 {
-            return self.search_previews_with_like(query);
+            return self.search_previews_with_like(query, kind);
         }
 
-        self.search_previews_with_fts(query)
+        self.search_previews_with_fts(query, kind)
     }
 
     pub fn backup_to_path(&self, destination_db_path: &Path) -> Result<()> {
@@ -506,25 +916,35 @@ impl ClipStorage {
 
     fn update_pin_with_conn(conn: &Connection, id: &str, is_pinned: bool) -> Result<()> {
         let pin_order = if is_pinned {
-            let max_order: Option<i32> = conn.query_row(
-                "SELECT MAX(pin_order) FROM clips WHERE is_pinned = 1",
-                [],
-                |row| row.get(0),
-            )?;
-
-            Some(max_order.unwrap_or(0) + 1)
+            Some(Self::next_pin_order_with_conn(conn)?)
         } else {
             None
         };
 
+        // Pinning is an explicit "keep this", so it also cancels a sensitive
+        // clip's expiry.
         conn.execute(
-            "UPDATE clips SET is_pinned = ?1, pin_order = ?2 WHERE id = ?3",
+            "UPDATE clips
+             SET is_pinned = ?1,
+                 pin_order = ?2,
+                 expires_at = CASE WHEN ?1 = 1 THEN NULL ELSE expires_at END
+             WHERE id = ?3",
             params![is_pinned as i32, pin_order, id],
         )?;
 
         Ok(())
     }
 
+    fn next_pin_order_with_conn(conn: &Connection) -> Result<i32> {
+        let max_order: Option<i32> = conn.query_row(
+            "SELECT MAX(pin_order) FROM clips WHERE is_pinned = 1",
+            [],
+            |row| row.get(0),
+        )?;
+
+        Ok(max_order.unwrap_or(0) + 1)
+    }
+
     pub fn set_clip_label(&self, id: &str, label: Option<String>) -> Result<()> {
         let tx = self.conn.unchecked_transaction()?;
         Self::set_clip_label_with_conn(&tx, id, label)?;
@@ -604,10 +1024,137 @@ impl ClipStorage {
 
     fn delete_with_conn(conn: &Connection, id: &str) -> Result<()> {
         conn.execute("DELETE FROM clips_fts WHERE clip_id = ?1", params![id])?;
+        conn.execute("DELETE FROM clip_formats WHERE clip_id = ?1", params![id])?;
         conn.execute("DELETE FROM clips WHERE id = ?1", params![id])?;
         Ok(())
     }
 
+    /// Swaps an unpinned Text clip's content for `text` in place, as a
+    /// capture of that text would store it: the rich companions and raw
+    /// formats go, the kind is the one given and the search index follows.
+    /// The id, label and statistics events stay; `is_redacted` and
+    /// `expires_at` only ever add a flag or an expiry.
+    ///
+    /// If another Text clip already holds `text`, that clip is refreshed the
+    /// way a re-copy would refresh it and this one is deleted, so the swap
+    /// never leaves two clips with the same content.
+    pub fn replace_text(
+        &self,
+        id: &str,
+        text: &str,
+        text_kind: TextKind,
+        code_language: Option<&str>,
+        is_redacted: bool,
+        expires_at: Option<i64>,
+    ) -> Result<TextReplacement> {
+        let tx = self.conn.unchecked_transaction()?;
+        let current = tx
+            .query_row(
+                &format!(
+                    "SELECT {CLIP_COLUMNS}
+                     FROM clips
+                     WHERE id = ?1 AND content_type = 'text' AND is_pinned = 0"
+                ),
+                [id],
+                Self::clip_from_row,
+            )
+            .optional()?;
+        let Some(current) = current else {
+            return Ok(TextReplacement::Unchanged);
+        };
+
+        let content_hash = hash_bytes(text.as_bytes());
+        let duplicate_id: Option<String> = tx
+            .query_row(
+                "SELECT id FROM clips
+                 WHERE content_hash = ?1 AND content_type = 'text' AND id != ?2
+                 ORDER BY timestamp DESC
+                 LIMIT 1",
+                params![content_hash, id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(duplicate_id) = duplicate_id {
+            let expires_at = expires_at.or(current.expires_at);
+            Self::refresh_duplicate_with_conn(
+                &tx,
+                &duplicate_id,
+                &ClipItem {
+                    html: None,
+                    rtf: None,
+                    expires_at,
+                    ..current
+                },
+            )?;
+            Self::delete_with_conn(&tx, id)?;
+            tx.commit()?;
+            self.reclaim_space();
+            return Ok(TextReplacement::MergedInto(duplicate_id));
+        }
+
+        tx.execute(
+            "UPDATE clips
+             SET content = ?1, content_hash = ?2, html = NULL, rtf = NULL,
+                 text_kind = ?3, code_language = ?4, is_redacted = ?5,
+                 expires_at = COALESCE(?6, expires_at)
+             WHERE id = ?7",
+            params![
+                text.as_bytes(),
+                content_hash,
+                text_kind.as_db_value(),
+                code_language,
+                (current.is_redacted || is_redacted) as i32,
+                expires_at,
+                id
+            ],
+        )?;
+        tx.execute("DELETE FROM clip_formats WHERE clip_id = ?1", params![id])?;
+        Self::sync_fts_for_clip_id_with_conn(&tx, id)?;
+        tx.commit()?;
+        Ok(TextReplacement::Replaced)
+    }
+
+    /// Deletes every clip whose `expires_at` is at or before `now` and
+    /// returns them, so the caller can check whether one is still on the
+    /// system clipboard.
+    pub fn take_expired_clips(&self, now: i64) -> Result<Vec<ClipItem>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let expired = Self::take_expired_clips_with_conn(&tx, now)?;
+        tx.commit()?;
+        if !expired.is_empty() {
+            self.reclaim_space();
+        }
+        Ok(expired)
+    }
+
+    fn take_expired_clips_with_conn(conn: &Connection, now: i64) -> Result<Vec<ClipItem>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {CLIP_COLUMNS}
+             FROM clips
+             WHERE expires_at IS NOT NULL AND expires_at <= ?1"
+        ))?;
+        let expired = stmt
+            .query_map([now], Self::clip_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        for item in &expired {
+            Self::delete_with_conn(conn, &item.id)?;
+        }
+        Ok(expired)
+    }
+
+    /// Distinct pending expiry times, earliest first, used to re-arm expiry
+    /// timers on startup.
+    pub fn pending_expiries(&self) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT expires_at FROM clips
+             WHERE expires_at IS NOT NULL
+             ORDER BY expires_at",
+        )?;
+        let expiries = stmt.query_map([], |row| row.get(0))?.collect();
+        expiries
+    }
+
     pub fn clear_non_pinned(&self) -> Result<()> {
         let tx = self.conn.unchecked_transaction()?;
         Self::clear_non_pinned_with_conn(&tx)?;
@@ -623,11 +1170,17 @@ impl ClipStorage {
              WHERE clip_id IN (SELECT id FROM clips WHERE is_pinned = 0)",
             [],
         )?;
+        conn.execute(
+            "DELETE FROM clip_formats
+             WHERE clip_id IN (SELECT id FROM clips WHERE is_pinned = 0)",
+            [],
+        )?;
         conn.execute("DELETE FROM clips WHERE is_pinned = 0", [])?;
         Ok(())
     }
 
-    /// Get a single clip item by ID (efficient single-row lookup)
+    /// Get a single clip item by ID (efficient single-row lookup), including
+    /// any full-fidelity formats stored alongside it.
     pub fn get_by_id(&self, id: &str) -> Result<Option<ClipItem>> {
         let mut stmt = self.conn.prepare(&format!(
             "SELECT {CLIP_COLUMNS}
@@ -635,7 +1188,11 @@ impl ClipStorage {
              WHERE id = ?1"
         ))?;
 
-        stmt.query_row([id], Self::clip_from_row).optional()
+        let Some(mut item) = stmt.query_row([id], Self::clip_from_row).optional()? else {
+            return Ok(None);
+        };
+        item.formats = Self::clip_formats_with_conn(&self.conn, id)?;
+        Ok(Some(item))
     }
 
     pub fn get_preview_by_id(&self, id: &str) -> Result<Option<ClipPreviewItem>> {
@@ -675,59 +1232,333 @@ impl ClipStorage {
         Ok(())
     }
 
-    /// Refresh a duplicate clip on re-copy: bump its timestamp and let present
-    /// metadata win while missing fields keep the old values via COALESCE (D6).
-    fn refresh_duplicate_with_conn(
-        conn: &Connection,
-        id: &str,
-        new_timestamp: i64,
-        html: Option<&str>,
+    /// Records one statistics event and drops events past the retention
+    /// window.
+    pub fn record_event(
+        &self,
+        kind: ClipEventKind,
+        clip_id: Option<&str>,
+        content_type: Option<&ContentType>,
         source_app: Option<&str>,
+        timestamp: i64,
     ) -> Result<()> {
-        conn.execute(
-            "UPDATE clips
-             SET timestamp = ?1,
-                 html = COALESCE(?2, html),
-                 source_app = COALESCE(?3, source_app)
-             WHERE id = ?4",
-            params![new_timestamp, html, source_app, id],
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO clip_events (kind, timestamp, clip_id, content_type, source_app)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                kind.as_db_value(),
+                timestamp,
+                clip_id,
+                content_type.map(ContentType::as_db_value),
+                source_app,
+            ],
         )?;
-        log::debug!("📍 Refreshed duplicate item {}", id);
-        Ok(())
+        Self::trim_events_with_conn(&tx, timestamp)?;
+        tx.commit()
     }
 
-    fn initialize_schema(conn: &Connection) -> Result<()> {
+    /// Records a paste of each of `ids`, in one transaction.
+    pub fn record_pastes(&self, ids: &[String], timestamp: i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO clip_events (kind, timestamp, clip_id, content_type, source_app)
+                 SELECT ?1, ?2, id, content_type, source_app FROM clips WHERE id = ?3",
+            )?;
+            for id in ids {
+                stmt.execute(params![ClipEventKind::Pasted.as_db_value(), timestamp, id])?;
+            }
+        }
+        Self::trim_events_with_conn(&tx, timestamp)?;
+        tx.commit()
+    }
+
+    fn trim_events_with_conn(conn: &Connection, now: i64) -> Result<()> {
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS clips (
-                id TEXT PRIMARY KEY,
-                content BLOB NOT NULL,
-                thumbnail BLOB,
-                content_hash TEXT,
-                content_type TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                is_pinned INTEGER DEFAULT 0,
-                pin_order INTEGER,
-                label TEXT,
-                group_name TEXT,
-                source_app TEXT,
-                html TEXT
-            )",
-            [],
+            "DELETE FROM clip_events WHERE timestamp < ?1",
+            params![now - CLIP_EVENT_RETENTION_DAYS * SECONDS_PER_DAY],
         )?;
+        Ok(())
+    }
 
-        Self::add_column_if_missing(conn, "content_hash", "TEXT")?;
-        Self::add_column_if_missing(conn, "thumbnail", "BLOB")?;
-        Self::add_column_if_missing(conn, "label", "TEXT")?;
-        Self::add_column_if_missing(conn, "group_name", "TEXT")?;
-        Self::add_column_if_missing(conn, "source_app", "TEXT")?;
-        Self::add_column_if_missing(conn, "html", "TEXT")?;
+    /// Aggregates the events recorded since `since`, with at most `limit`
+    /// source apps and top pasted clips. Pastes of clips that are gone no
+    /// longer rank.
+    pub fn statistics(&self, since: i64, limit: usize) -> Result<ClipStatistics> {
+        let clips_per_day = {
+            let mut stmt = self.conn.prepare(
+                "SELECT date(timestamp, 'unixepoch', 'localtime') AS day, COUNT(*)
+                 FROM clip_events
+                 WHERE kind = ?1 AND timestamp >= ?2
+                 GROUP BY day
+                 ORDER BY day",
+            )?;
+            let rows = stmt.query_map(
+                params![ClipEventKind::Captured.as_db_value(), since],
+                |row| {
+                    Ok(DayCount {
+                        day: row.get(0)?,
+                        count: row.get(1)?,
+                    })
+                },
+            )?;
+            rows.collect::<Result<Vec<_>>>()?
+        };
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_timestamp ON clips(timestamp DESC)",
-            [],
-        )?;
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_pinned ON clips(is_pinned, pin_order)",
+        let clips_per_app = {
+            let mut stmt = self.conn.prepare(
+                "SELECT source_app, COUNT(*) AS count
+                 FROM clip_events
+                 WHERE kind = ?1 AND timestamp >= ?2
+                 GROUP BY source_app
+                 ORDER BY count DESC, source_app
+                 LIMIT ?3",
+            )?;
+            let rows = stmt.query_map(
+                params![ClipEventKind::Captured.as_db_value(), since, limit],
+                |row| {
+                    Ok(AppCount {
+                        app: row.get(0)?,
+                        count: row.get(1)?,
+                    })
+                },
+            )?;
+            rows.collect::<Result<Vec<_>>>()?
+        };
+
+        let content_types = {
+            let mut stmt = self.conn.prepare(
+                "SELECT content_type, COUNT(*) AS count
+                 FROM clip_events
+                 WHERE kind = ?1 AND timestamp >= ?2 AND content_type IS NOT NULL
+                 GROUP BY content_type
+                 ORDER BY count DESC, content_type",
+            )?;
+            let rows = stmt.query_map(
+                params![ClipEventKind::Captured.as_db_value(), since],
+                |row| {
+                    Ok(ContentTypeCount {
+                        content_type: ContentType::from_db_value(&row.get::<_, String>(0)?),
+                        count: row.get(1)?,
+                    })
+                },
+            )?;
+            rows.collect::<Result<Vec<_>>>()?
+        };
+
+        let top_pasted_ids = {
+            let mut stmt = self.conn.prepare(
+                "SELECT e.clip_id, COUNT(*) AS pastes
+                 FROM clip_events e
+                 JOIN clips c ON c.id = e.clip_id
+                 WHERE e.kind = ?1 AND e.timestamp >= ?2
+                 GROUP BY e.clip_id
+                 ORDER BY pastes DESC, MAX(e.timestamp) DESC
+                 LIMIT ?3",
+            )?;
+            let rows = stmt.query_map(
+                params![ClipEventKind::Pasted.as_db_value(), since, limit],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            )?;
+            rows.collect::<Result<Vec<_>>>()?
+        };
+        let mut top_pasted = Vec::with_capacity(top_pasted_ids.len());
+        for (id, pastes) in top_pasted_ids {
+            if let Some(preview) = self.get_preview_by_id(&id)? {
+                top_pasted.push(PastedClip {
+                    clip: FrontendClipItem::from_preview(preview),
+                    pastes,
+                });
+            }
+        }
+
+        let count_events = |kind: ClipEventKind| -> Result<i64> {
+            self.conn.query_row(
+                "SELECT COUNT(*) FROM clip_events WHERE kind = ?1 AND timestamp >= ?2",
+                params![kind.as_db_value(), since],
+                |row| row.get(0),
+            )
+        };
+        let secrets_skipped = count_events(ClipEventKind::SecretSkipped)?;
+        let oversized_skipped = count_events(ClipEventKind::OversizedSkipped)?;
+
+        let (stored_clips, mut storage) = self.conn.query_row(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(CASE WHEN content_type = 'image' THEN 0 ELSE length(content) END), 0),
+                COALESCE(SUM(length(CAST(html AS BLOB))), 0),
+                COALESCE(SUM(CASE WHEN content_type = 'image' THEN length(content) ELSE 0 END), 0),
+                COALESCE(SUM(length(thumbnail)), 0),
+                COALESCE(SUM(length(CAST(rtf AS BLOB))), 0)
+             FROM clips",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    StorageBreakdown {
+                        text: row.get(1)?,
+                        html: row.get(2)?,
+                        images: row.get(3)?,
+                        thumbnails: row.get(4)?,
+                        other: row.get(5)?,
+                        database: 0,
+                    },
+                ))
+            },
+        )?;
+        storage.other += self.conn.query_row(
+            "SELECT COALESCE(SUM(length(data)), 0) FROM clip_formats",
+            [],
+            |row| row.get::<_, i64>(0),
+        )?;
+        let page_count: i64 = self
+            .conn
+            .query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = self
+            .conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        storage.database = page_count * page_size;
+
+        Ok(ClipStatistics {
+            since,
+            clips_per_day,
+            clips_per_app,
+            content_types,
+            top_pasted,
+            secrets_skipped,
+            oversized_skipped,
+            stored_clips,
+            storage,
+        })
+    }
+
+    /// Refresh a duplicate clip on re-copy: bump its timestamp and let present
+    /// metadata win while missing fields keep the old values via COALESCE (D6).
+    /// Images never carry html or rtf, so a near-duplicate image refresh
+    /// shares this path and keeps the stored pixels.
+    fn refresh_duplicate_with_conn(conn: &Connection, id: &str, item: &ClipItem) -> Result<()> {
+        // A sensitive re-copy restarts a sensitive clip's countdown, but never
+        // puts an expiry on a clip that was already kept for good; an
+        // ordinary re-copy makes a sensitive clip permanent. The capture
+        // marker follows the latest copy, which is what the clipboard holds.
+        // A capture rule's label and group only fill ones the clip lacks, so
+        // a label set by hand is never overwritten.
+        conn.execute(
+            "UPDATE clips
+             SET timestamp = ?1,
+                 html = COALESCE(?2, html),
+                 rtf = COALESCE(?3, rtf),
+                 source_app = COALESCE(?4, source_app),
+                 expires_at = CASE WHEN expires_at IS NULL THEN NULL ELSE ?5 END,
+                 capture_marker = COALESCE(?6, capture_marker),
+                 label = COALESCE(label, ?7),
+                 group_name = COALESCE(group_name, ?8)
+             WHERE id = ?9",
+            params![
+                item.timestamp,
+                item.html,
+                item.rtf,
+                item.source_app,
+                item.expires_at,
+                item.capture_marker,
+                item.label,
+                item.group_name,
+                id
+            ],
+        )?;
+        // A rule's pin joins the end of the pinned list, as on insert; a clip
+        // that is already pinned keeps its place.
+        if item.is_pinned {
+            let pin_order = Self::next_pin_order_with_conn(conn)?;
+            conn.execute(
+                "UPDATE clips SET is_pinned = 1, pin_order = ?1 WHERE id = ?2 AND is_pinned = 0",
+                params![pin_order, id],
+            )?;
+        }
+        Self::sync_fts_for_clip_id_with_conn(conn, id)?;
+        log::debug!("📍 Refreshed duplicate item {}", id);
+        Ok(())
+    }
+
+    fn initialize_schema(conn: &Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS clips (
+                id TEXT PRIMARY KEY,
+                content BLOB NOT NULL,
+                thumbnail BLOB,
+                content_hash TEXT,
+                content_type TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                is_pinned INTEGER DEFAULT 0,
+                pin_order INTEGER,
+                label TEXT,
+                group_name TEXT,
+                source_app TEXT,
+                html TEXT,
+                rtf TEXT,
+                perceptual_hash INTEGER,
+                text_kind TEXT,
+                code_language TEXT,
+                is_redacted INTEGER DEFAULT 0,
+                expires_at INTEGER,
+                capture_marker TEXT
+            )",
+            [],
+        )?;
+
+        Self::add_column_if_missing(conn, "content_hash", "TEXT")?;
+        Self::add_column_if_missing(conn, "thumbnail", "BLOB")?;
+        Self::add_column_if_missing(conn, "label", "TEXT")?;
+        Self::add_column_if_missing(conn, "group_name", "TEXT")?;
+        Self::add_column_if_missing(conn, "source_app", "TEXT")?;
+        Self::add_column_if_missing(conn, "html", "TEXT")?;
+        Self::add_column_if_missing(conn, "rtf", "TEXT")?;
+        Self::add_column_if_missing(conn, "perceptual_hash", "INTEGER")?;
+        Self::add_column_if_missing(conn, "text_kind", "TEXT")?;
+        Self::add_column_if_missing(conn, "code_language", "TEXT")?;
+        Self::add_column_if_missing(conn, "is_redacted", "INTEGER DEFAULT 0")?;
+        Self::add_column_if_missing(conn, "expires_at", "INTEGER")?;
+        Self::add_column_if_missing(conn, "capture_marker", "TEXT")?;
+
+        // Full-fidelity representations are a child table rather than
+        // columns: the set of formats is open-ended (custom MIME types,
+        // UTIs), and most clips have none. Like the html column (D4), this
+        // is additive and needs no user_version bump.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS clip_formats (
+                clip_id TEXT NOT NULL,
+                format TEXT NOT NULL,
+                data BLOB NOT NULL,
+                PRIMARY KEY (clip_id, format)
+            )",
+            [],
+        )?;
+
+        // Statistics events. Rows outlive the clips they name (a deleted
+        // clip was still captured), so there is no foreign key; the table
+        // is trimmed to CLIP_EVENT_RETENTION_DAYS instead.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS clip_events (
+                kind TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                clip_id TEXT,
+                content_type TEXT,
+                source_app TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_clip_events_kind_ts ON clip_events(kind, timestamp)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_timestamp ON clips(timestamp DESC)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_pinned ON clips(is_pinned, pin_order)",
             [],
         )?;
         conn.execute(
@@ -821,6 +1652,14 @@ impl ClipStorage {
             group_name: row.get(8)?,
             source_app: row.get(9)?,
             html: row.get(10)?,
+            rtf: row.get(11)?,
+            perceptual_hash: row.get::<_, Option<i64>>(12)?.map(|hash| hash as u64),
+            text_kind: text_kind_from_db(row.get(13)?),
+            code_language: row.get(14)?,
+            is_redacted: row.get::<_, i32>(15)? != 0,
+            expires_at: row.get(16)?,
+            capture_marker: row.get(17)?,
+            formats: Vec::new(),
         })
     }
 
@@ -837,22 +1676,32 @@ impl ClipStorage {
             group_name: row.get(8)?,
             source_app: row.get(9)?,
             has_html: row.get::<_, i32>(10)? != 0,
+            has_rtf: row.get::<_, i32>(11)? != 0,
+            text_kind: text_kind_from_db(row.get(12)?),
+            code_language: row.get(13)?,
+            is_redacted: row.get::<_, i32>(14)? != 0,
+            expires_at: row.get(15)?,
         })
     }
 
-    fn get_all_previews_for_search(&self) -> Result<Vec<ClipPreviewItem>> {
+    fn get_all_previews_for_search(&self, kind: Option<&str>) -> Result<Vec<ClipPreviewItem>> {
         let mut stmt = self.conn.prepare(&format!(
             "SELECT {CLIP_PREVIEW_COLUMNS}
              FROM clips
+             WHERE ?1 IS NULL OR text_kind = ?1
              ORDER BY timestamp DESC
              LIMIT 1000"
         ))?;
 
-        let items = stmt.query_map([], Self::preview_from_row)?;
+        let items = stmt.query_map([kind], Self::preview_from_row)?;
         items.collect()
     }
 
-    fn search_previews_with_fts(&self, query: &str) -> Result<Vec<ClipPreviewItem>> {
+    fn search_previews_with_fts(
+        &self,
+        query: &str,
+        kind: Option<&str>,
+    ) -> Result<Vec<ClipPreviewItem>> {
         let fts_query = escape_fts_query(query);
         let mut stmt = self.conn.prepare(&format!(
             "SELECT {CLIP_PREVIEW_COLUMNS}
@@ -860,29 +1709,37 @@ impl ClipStorage {
              WHERE (rowid, id) IN (
                 SELECT rowid, clip_id FROM clips_fts WHERE clips_fts MATCH ?1
              )
+             AND (?2 IS NULL OR text_kind = ?2)
              ORDER BY timestamp DESC
              LIMIT 1000"
         ))?;
 
-        let items = stmt.query_map([fts_query], Self::preview_from_row)?;
+        let items = stmt.query_map(params![fts_query, kind], Self::preview_from_row)?;
         items.collect()
     }
 
-    fn search_previews_with_like(&self, query: &str) -> Result<Vec<ClipPreviewItem>> {
+    fn search_previews_with_like(
+        &self,
+        query: &str,
+        kind: Option<&str>,
+    ) -> Result<Vec<ClipPreviewItem>> {
         let like_query = format!("%{}%", escape_like_query(query));
         let mut stmt = self.conn.prepare(&format!(
             "SELECT {CLIP_PREVIEW_COLUMNS}
              FROM clips
              WHERE (
-                content_type IN ('text','files')
-                AND CAST(substr(content, 1, {TEXT_PREVIEW_BYTES}) AS TEXT) LIKE ?1 ESCAPE '\\'
+                (
+                    content_type IN ('text','files')
+                    AND CAST(substr(content, 1, {TEXT_PREVIEW_BYTES}) AS TEXT) LIKE ?1 ESCAPE '\\'
+                )
+                OR COALESCE(label, '') LIKE ?1 ESCAPE '\\'
              )
-             OR COALESCE(label, '') LIKE ?1 ESCAPE '\\'
+             AND (?2 IS NULL OR text_kind = ?2)
              ORDER BY timestamp DESC
              LIMIT 1000"
         ))?;
 
-        let items = stmt.query_map([like_query], Self::preview_from_row)?;
+        let items = stmt.query_map(params![like_query, kind], Self::preview_from_row)?;
         items.collect()
     }
 
@@ -971,6 +1828,16 @@ impl ClipStorage {
     }
 
     fn prune_history_with_conn(conn: &Connection, max_history_items: usize) -> Result<usize> {
+        conn.execute(
+            "DELETE FROM clip_formats
+             WHERE clip_id IN (
+                SELECT id FROM clips
+                WHERE is_pinned = 0
+                ORDER BY timestamp DESC, id DESC
+                LIMIT -1 OFFSET ?1
+             )",
+            params![max_history_items],
+        )?;
         conn.execute(
             "DELETE FROM clips_fts
              WHERE clip_id IN (
@@ -1035,6 +1902,10 @@ fn search_text_for_fts(content: &[u8], content_type: &ContentType) -> String {
     }
 }
 
+fn text_kind_from_db(value: Option<String>) -> Option<TextKind> {
+    value.as_deref().and_then(TextKind::from_db_value)
+}
+
 fn normalize_label(label: Option<String>) -> Option<String> {
     label
         .map(|value| value.trim().to_string())
@@ -1242,6 +2113,14 @@ mod tests {
             group_name: None,
             source_app: None,
             html: None,
+            rtf: None,
+            perceptual_hash: None,
+            text_kind: None,
+            code_language: None,
+            is_redacted: false,
+            expires_at: None,
+            capture_marker: None,
+            formats: Vec::new(),
         }
     }
 
@@ -1261,6 +2140,14 @@ mod tests {
         }
     }
 
+    fn image_item(id: &str, content: &[u8], perceptual_hash: u64, timestamp: i64) -> ClipItem {
+        ClipItem {
+            content_type: ContentType::Image,
+            perceptual_hash: Some(perceptual_hash),
+            ..test_item(id, content, timestamp, false, None)
+        }
+    }
+
     fn query_plan_details(storage: &ClipStorage, sql: &str) -> Vec<String> {
         let mut stmt = storage.conn.prepare(sql).unwrap();
         stmt.query_map([], |row| row.get::<_, String>(3))
@@ -1474,6 +2361,29 @@ mod tests {
         cleanup_db(&db_path);
     }
 
+    #[test]
+    fn clip_inserted_pinned_without_order_joins_end_of_pinned_list() {
+        let db_path = temp_db_path("insert_pinned");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        storage
+            .insert(&test_item("pinned", b"pinned", 10, true, Some(3)), 100)
+            .unwrap();
+        storage
+            .insert(&test_item("rule-pinned", b"by rule", 20, true, None), 100)
+            .unwrap();
+
+        let pin_order = storage
+            .get_preview_by_id("rule-pinned")
+            .unwrap()
+            .unwrap()
+            .pin_order;
+        assert_eq!(Some(4), pin_order);
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
     #[test]
     fn touch_timestamps_bumps_all_given_ids_in_one_transaction() {
         let db_path = temp_db_path("touch_batch");
@@ -1578,6 +2488,96 @@ mod tests {
         cleanup_db(&db_path);
     }
 
+    #[test]
+    fn duplicate_insert_applies_capture_rule_actions() {
+        let db_path = temp_db_path("duplicate_rule_actions");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        storage
+            .insert(&test_item("first-pin", b"first pin", 5, true, Some(1)), 100)
+            .unwrap();
+        storage
+            .insert(&test_item("plain", b"JIRA-42", 10, false, None), 100)
+            .unwrap();
+        let mut labelled = test_item("labelled", b"by hand", 10, false, None);
+        labelled.label = Some("mine".to_string());
+        storage.insert(&labelled, 100).unwrap();
+
+        // Re-copies a matching rule pinned, labelled and grouped.
+        let ruled = |id: &str, content: &[u8]| ClipItem {
+            label: Some("jira".to_string()),
+            group_name: Some("tickets".to_string()),
+            ..test_item(id, content, 20, true, None)
+        };
+        storage.insert(&ruled("again", b"JIRA-42"), 100).unwrap();
+        storage.insert(&ruled("again-2", b"by hand"), 100).unwrap();
+
+        let plain = storage.get_by_id("plain").unwrap().unwrap();
+        assert!(plain.is_pinned);
+        assert_eq!(Some(2), plain.pin_order);
+        assert_eq!(Some("jira"), plain.label.as_deref());
+        assert_eq!(Some("tickets"), plain.group_name.as_deref());
+        let found: Vec<String> = storage
+            .search_clip_previews("jira")
+            .unwrap()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        assert!(found.contains(&"plain".to_string()));
+
+        let labelled = storage.get_by_id("labelled").unwrap().unwrap();
+        assert_eq!(Some("mine"), labelled.label.as_deref());
+        assert_eq!(Some(3), labelled.pin_order);
+
+        // Already pinned: the re-copy keeps its place.
+        storage
+            .insert(&ruled("again-3", b"first pin"), 100)
+            .unwrap();
+        let first = storage.get_by_id("first-pin").unwrap().unwrap();
+        assert_eq!(Some(1), first.pin_order);
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn group_previews_list_only_that_group_newest_first() {
+        let db_path = temp_db_path("group_previews");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        let grouped = |id: &str, timestamp: i64, is_pinned: bool, group: Option<&str>| ClipItem {
+            group_name: group.map(str::to_string),
+            ..test_item(id, id.as_bytes(), timestamp, is_pinned, None)
+        };
+        storage
+            .insert(&grouped("old-ticket", 10, true, Some("tickets")), 100)
+            .unwrap();
+        storage
+            .insert(&grouped("new-ticket", 20, false, Some("tickets")), 100)
+            .unwrap();
+        storage
+            .insert(&grouped("snippet", 30, false, Some("snippets")), 100)
+            .unwrap();
+        storage
+            .insert(&grouped("plain", 40, false, None), 100)
+            .unwrap();
+
+        let ids: Vec<String> = storage
+            .get_group_clip_previews("tickets")
+            .unwrap()
+            .into_iter()
+            .map(|preview| preview.id)
+            .collect();
+        assert_eq!(vec!["new-ticket", "old-ticket"], ids);
+        assert!(storage
+            .get_group_clip_previews("missing")
+            .unwrap()
+            .is_empty());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
     #[test]
     fn reorder_pinned_swaps_adjacent_items_and_renumbers_slots() {
         let db_path = temp_db_path("reorder_pinned");
@@ -1624,6 +2624,40 @@ mod tests {
         cleanup_db(&db_path);
     }
 
+    #[test]
+    fn pinned_slots_follow_pin_order_and_close_gaps() {
+        let db_path = temp_db_path("pinned_slots");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        storage
+            .insert(&test_item("first", b"first", 30, true, Some(10)), 100)
+            .unwrap();
+        storage
+            .insert(&test_item("second", b"second", 20, true, Some(20)), 100)
+            .unwrap();
+        storage
+            .insert(&test_item("recent", b"recent", 40, false, None), 100)
+            .unwrap();
+
+        storage.reorder_pinned("second", "up").unwrap();
+        assert_eq!(
+            Some("second".to_string()),
+            storage.pinned_slot_clip_id(1).unwrap()
+        );
+        assert_eq!(None, storage.pinned_slot_clip_id(3).unwrap());
+        assert_eq!(None, storage.pinned_slot_clip_id(0).unwrap());
+
+        storage.delete("second").unwrap();
+        assert_eq!(
+            Some("first".to_string()),
+            storage.pinned_slot_clip_id(1).unwrap()
+        );
+        assert_eq!(None, storage.pinned_slot_clip_id(2).unwrap());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
     #[test]
     fn delete_removes_clip_and_fts_row_together() {
         let db_path = temp_db_path("delete_atomicity");
@@ -2104,6 +3138,14 @@ mod tests {
                     group_name: None,
                     source_app: None,
                     html: None,
+                    rtf: None,
+                    perceptual_hash: None,
+                    text_kind: None,
+                    code_language: None,
+                    is_redacted: false,
+                    expires_at: None,
+                    capture_marker: None,
+                    formats: Vec::new(),
                 },
                 100,
             )
@@ -2466,38 +3508,625 @@ mod tests {
         cleanup_db(&db_path);
     }
 
-    fn database_byte_size(storage: &ClipStorage) -> i64 {
-        let page_count: i64 = storage
-            .conn
-            .query_row("PRAGMA page_count", [], |row| row.get(0))
+    #[test]
+    fn rtf_column_roundtrips_flags_previews_and_coalesces_on_duplicate() {
+        let db_path = temp_db_path("rtf_coalesce");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let rtf = "{\\rtf1\\ansi {\\b hello}\\par}";
+
+        storage
+            .insert(
+                &ClipItem {
+                    rtf: Some(rtf.to_string()),
+                    ..test_item("rich", b"hello", 1, false, None)
+                },
+                100,
+            )
             .unwrap();
-        let page_size: i64 = storage
-            .conn
-            .query_row("PRAGMA page_size", [], |row| row.get(0))
+        assert!(storage.get_preview_by_id("rich").unwrap().unwrap().has_rtf);
+
+        storage
+            .insert(&test_item("plain-dup", b"hello", 2, false, None), 100)
             .unwrap();
-        page_count * page_size
+        let after_plain = storage.get_by_id("rich").unwrap().unwrap();
+        assert_eq!(Some(rtf.to_string()), after_plain.rtf);
+        assert_eq!(2, after_plain.timestamp);
+
+        storage
+            .insert(&test_item("plain", b"plain", 3, false, None), 100)
+            .unwrap();
+        assert!(!storage.get_preview_by_id("plain").unwrap().unwrap().has_rtf);
+
+        drop(storage);
+        cleanup_db(&db_path);
     }
 
     #[test]
-    fn new_database_enables_incremental_auto_vacuum() {
-        let db_path = temp_db_path("auto_vacuum_new");
+    fn kind_filter_applies_to_listing_like_and_fts_search() {
+        let db_path = temp_db_path("kind_filter");
         let storage = ClipStorage::new(&db_path).unwrap();
 
-        let mode: i64 = storage
-            .conn
-            .query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+        for (id, content, kind, timestamp) in [
+            ("url", "https://example.com/docs", TextKind::Url, 1),
+            ("mail", "docs@example.com", TextKind::Email, 2),
+            ("prose", "read the docs at example.com", TextKind::Plain, 3),
+            ("url-2", "https://ex.io", TextKind::Url, 4),
+        ] {
+            storage
+                .insert(
+                    &ClipItem {
+                        text_kind: Some(kind),
+                        ..test_item(id, content.as_bytes(), timestamp, false, None)
+                    },
+                    100,
+                )
+                .unwrap();
+        }
+        storage
+            .insert(&files_item("files", &["/docs/example.com"], 5), 100)
             .unwrap();
-        assert_eq!(2, mode);
+
+        let search = |query: &str, kind: Option<TextKind>| -> Vec<String> {
+            storage
+                .search_clip_previews_of_kind(query, kind)
+                .unwrap()
+                .into_iter()
+                .map(|item| item.id)
+                .collect()
+        };
+
+        assert_eq!(vec!["url-2", "url"], search("", Some(TextKind::Url)));
+        assert_eq!(vec!["url"], search("docs", Some(TextKind::Url)));
+        assert_eq!(vec!["url-2"], search("io", Some(TextKind::Url)));
+        assert_eq!(vec!["files", "prose", "mail", "url"], search("docs", None));
+        assert!(search("docs", Some(TextKind::Code)).is_empty());
+
+        let preview = storage.get_preview_by_id("mail").unwrap().unwrap();
+        assert_eq!(Some(TextKind::Email), preview.text_kind);
 
         drop(storage);
         cleanup_db(&db_path);
     }
 
     #[test]
-    fn opening_legacy_none_auto_vacuum_database_upgrades_to_incremental() {
-        let db_path = temp_db_path("auto_vacuum_legacy");
-        {
-            let conn = Connection::open(&db_path).unwrap();
+    fn opening_storage_classifies_text_clips_captured_before_kinds_existed() {
+        let db_path = temp_db_path("kind_backfill");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        storage
+            .insert(
+                &test_item(
+                    "code",
+                    b"fn main() {\n    let mut x = 1;\n    x += 1;\n}",
+                    1,
+                    false,
+                    None,
+                ),
+                100,
+            )
+            .unwrap();
+        storage
+            .insert(&test_item("color", b"#ff8800", 2, false, None), 100)
+            .unwrap();
+        storage
+            .insert(&files_item("files", &["/tmp/a"], 3), 100)
+            .unwrap();
+        assert_eq!(None, storage.get_by_id("color").unwrap().unwrap().text_kind);
+        drop(storage);
+
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let code = storage.get_by_id("code").unwrap().unwrap();
+        assert_eq!(Some(TextKind::Code), code.text_kind);
+        assert_eq!(Some("rust".to_string()), code.code_language);
+        assert_eq!(
+            Some(TextKind::Color),
+            storage.get_by_id("color").unwrap().unwrap().text_kind
+        );
+        assert_eq!(None, storage.get_by_id("files").unwrap().unwrap().text_kind);
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn redacted_flag_round_trips_through_full_and_preview_reads() {
+        let db_path = temp_db_path("redacted_flag");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        storage
+            .insert(
+                &ClipItem {
+                    is_redacted: true,
+                    ..test_item("redacted", b"token = [REDACTED]", 2, false, None)
+                },
+                100,
+            )
+            .unwrap();
+        storage
+            .insert(&test_item("plain", b"ordinary", 1, false, None), 100)
+            .unwrap();
+
+        assert!(storage.get_by_id("redacted").unwrap().unwrap().is_redacted);
+        assert!(!storage.get_by_id("plain").unwrap().unwrap().is_redacted);
+        let previews = storage.get_recent_clip_previews(10).unwrap();
+        assert!(
+            previews
+                .iter()
+                .find(|p| p.id == "redacted")
+                .unwrap()
+                .is_redacted
+        );
+        assert!(
+            !previews
+                .iter()
+                .find(|p| p.id == "plain")
+                .unwrap()
+                .is_redacted
+        );
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    fn sensitive_item(id: &str, content: &[u8], timestamp: i64, expires_at: i64) -> ClipItem {
+        ClipItem {
+            expires_at: Some(expires_at),
+            ..test_item(id, content, timestamp, false, None)
+        }
+    }
+
+    #[test]
+    fn take_expired_clips_deletes_due_clips_from_storage_and_fts() {
+        let db_path = temp_db_path("take_expired");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        storage
+            .insert(&sensitive_item("due", b"hunter2 password", 1, 100), 100)
+            .unwrap();
+        storage
+            .insert(&sensitive_item("later", b"later password", 2, 200), 100)
+            .unwrap();
+        storage
+            .insert(&test_item("plain", b"plain password", 3, false, None), 100)
+            .unwrap();
+        assert_eq!(vec![100, 200], storage.pending_expiries().unwrap());
+
+        let expired = storage.take_expired_clips(150).unwrap();
+        assert_eq!(
+            vec!["due"],
+            expired
+                .iter()
+                .map(|item| item.id.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(b"hunter2 password".to_vec(), expired[0].content);
+        assert!(storage.get_by_id("due").unwrap().is_none());
+
+        let ids: Vec<String> = storage
+            .search_clip_previews("password")
+            .unwrap()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(vec!["plain", "later"], ids);
+        let fts_rows: i64 = storage
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM clips_fts WHERE clip_id = 'due'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(0, fts_rows);
+
+        assert!(storage.take_expired_clips(150).unwrap().is_empty());
+        assert_eq!(vec![200], storage.pending_expiries().unwrap());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn replace_text_updates_clip_in_place_with_its_index_and_events() {
+        let db_path = temp_db_path("replace_text");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        let item = ClipItem {
+            html: Some("<b>draft</b>".to_string()),
+            label: Some("notes".to_string()),
+            formats: vec![ClipFormat {
+                format: "text/plain".to_string(),
+                data: b"draft".to_vec(),
+            }],
+            ..test_item("clip", b"draft", 1, false, None)
+        };
+        storage.insert(&item, 10).unwrap();
+        storage
+            .record_event(
+                ClipEventKind::Captured,
+                Some("clip"),
+                Some(&ContentType::Text),
+                None,
+                1,
+            )
+            .unwrap();
+
+        assert_eq!(
+            TextReplacement::Replaced,
+            storage
+                .replace_text("clip", "final", TextKind::Plain, None, true, Some(60))
+                .unwrap()
+        );
+
+        let stored = storage.get_by_id("clip").unwrap().unwrap();
+        assert_eq!(b"final".to_vec(), stored.content);
+        assert_eq!(None, stored.html);
+        assert!(stored.formats.is_empty());
+        assert_eq!(Some("notes"), stored.label.as_deref());
+        assert!(stored.is_redacted);
+        assert_eq!(Some(60), stored.expires_at);
+        let found = |query: &str| -> Vec<String> {
+            storage
+                .search_clip_previews(query)
+                .unwrap()
+                .into_iter()
+                .map(|item| item.id)
+                .collect()
+        };
+        assert_eq!(vec!["clip"], found("final"));
+        assert!(found("draft").is_empty());
+        let stats = storage.statistics(0, 10).unwrap();
+        assert_eq!(1, stats.clips_per_day[0].count);
+
+        assert_eq!(
+            TextReplacement::Unchanged,
+            storage
+                .replace_text("missing", "final", TextKind::Plain, None, false, None)
+                .unwrap()
+        );
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn replace_text_leaves_pinned_clips_and_merges_into_a_duplicate() {
+        let db_path = temp_db_path("replace_text_pinned_duplicate");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        storage
+            .insert(&test_item("pinned", b"keep me", 1, true, None), 10)
+            .unwrap();
+        assert_eq!(
+            TextReplacement::Unchanged,
+            storage
+                .replace_text("pinned", "changed", TextKind::Plain, None, false, None)
+                .unwrap()
+        );
+        assert_eq!(
+            b"keep me".to_vec(),
+            storage.get_by_id("pinned").unwrap().unwrap().content
+        );
+
+        storage
+            .insert(&test_item("older", b"final", 2, false, None), 10)
+            .unwrap();
+        let captured = ClipItem {
+            label: Some("from rule".to_string()),
+            ..test_item("new", b"draft", 3, false, None)
+        };
+        storage.insert(&captured, 10).unwrap();
+
+        assert_eq!(
+            TextReplacement::MergedInto("older".to_string()),
+            storage
+                .replace_text("new", "final", TextKind::Plain, None, false, None)
+                .unwrap()
+        );
+        assert!(storage.get_by_id("new").unwrap().is_none());
+        let older = storage.get_by_id("older").unwrap().unwrap();
+        assert_eq!(3, older.timestamp);
+        assert_eq!(Some("from rule"), older.label.as_deref());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn expired_normalized_clip_keeps_the_marker_of_the_copied_text() {
+        let db_path = temp_db_path("expired_normalized_marker");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        // The clipboard holds the raw text; the clip stores the cleaned one.
+        let raw = "token = s3cr3t   \r\n";
+        let normalizers = crate::normalize::TextNormalizers {
+            trim_whitespace: true,
+            normalize_line_endings: true,
+            ..Default::default()
+        };
+        let stored = crate::normalize::normalize_text(raw, normalizers);
+        assert_ne!(raw, stored);
+        let clipboard_marker = CopyMarker::from_payload(ContentType::Text, raw.as_bytes());
+        storage
+            .insert(
+                &ClipItem {
+                    capture_marker: Some(clipboard_marker.hash.clone()),
+                    ..sensitive_item("secret", stored.as_bytes(), 1, 100)
+                },
+                100,
+            )
+            .unwrap();
+
+        let expired = storage.take_expired_clips(100).unwrap();
+        assert_eq!(1, expired.len());
+        assert_eq!(Some(clipboard_marker), expired[0].capture_copy_marker());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn recopy_extends_sensitive_expiry_but_ordinary_copy_or_pin_keeps_clip() {
+        let db_path = temp_db_path("sensitive_recopy");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let expires_at = |id: &str| storage.get_by_id(id).unwrap().unwrap().expires_at;
+
+        storage
+            .insert(&sensitive_item("secret", b"s3cret", 1, 100), 100)
+            .unwrap();
+        storage
+            .insert(&sensitive_item("again", b"s3cret", 2, 300), 100)
+            .unwrap();
+        assert_eq!(Some(300), expires_at("secret"));
+
+        storage
+            .insert(&test_item("ordinary", b"s3cret", 3, false, None), 100)
+            .unwrap();
+        assert_eq!(None, expires_at("secret"));
+
+        storage
+            .insert(&sensitive_item("sensitive-later", b"s3cret", 4, 500), 100)
+            .unwrap();
+        assert_eq!(
+            None,
+            expires_at("secret"),
+            "a kept clip must not start expiring"
+        );
+
+        storage
+            .insert(&sensitive_item("pinned", b"token", 5, 100), 100)
+            .unwrap();
+        storage.update_pin("pinned", true).unwrap();
+        assert_eq!(None, expires_at("pinned"));
+        assert!(storage.take_expired_clips(1_000).unwrap().is_empty());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn similar_images_collapse_only_when_requested_and_within_the_threshold() {
+        let db_path = temp_db_path("similar_images_collapse");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        // The top bit exercises hashes that only fit SQLite's i64 as negatives.
+        let hash = 0x8F0F_00FF_1234_5678u64;
+
+        storage
+            .insert(&image_item("shot", b"pixels-1", hash, 1), 100)
+            .unwrap();
+
+        // Plain insert keeps exact-hash semantics: a near copy is a new clip.
+        storage
+            .insert(&image_item("plain", b"pixels-2", hash ^ 0b1, 2), 100)
+            .unwrap();
+        assert_eq!(
+            Some(hash ^ 0b1),
+            storage.get_by_id("plain").unwrap().unwrap().perceptual_hash
+        );
+        storage.delete("plain").unwrap();
+
+        let collapsed = storage
+            .insert_collapsing_similar_images(
+                &ClipItem {
+                    source_app: Some("Preview".to_string()),
+                    ..image_item("retake", b"pixels-3", hash ^ 0b111, 3)
+                },
+                100,
+                3,
+            )
+            .unwrap();
+        assert_eq!(Some("shot".to_string()), collapsed);
+        let shot = storage.get_by_id("shot").unwrap().unwrap();
+        assert_eq!(b"pixels-1".to_vec(), shot.content);
+        assert_eq!(3, shot.timestamp);
+        assert_eq!(Some("Preview".to_string()), shot.source_app);
+        assert!(storage.get_by_id("retake").unwrap().is_none());
+
+        let distinct = storage
+            .insert_collapsing_similar_images(
+                &image_item("other", b"pixels-4", hash ^ 0b1111, 4),
+                100,
+                3,
+            )
+            .unwrap();
+        assert_eq!(None, distinct);
+        assert!(storage.get_by_id("other").unwrap().is_some());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn similar_image_previews_group_by_distance_and_skip_text_and_self() {
+        let db_path = temp_db_path("similar_images_group");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let hash = 0xFFFF_0000_FFFF_0000u64;
+
+        for item in [
+            image_item("base", b"a", hash, 1),
+            image_item("two-bits", b"b", hash ^ 0b11, 2),
+            image_item("one-bit-old", b"c", hash ^ 0b1, 3),
+            image_item("one-bit-new", b"d", hash ^ 0b10, 4),
+            image_item("far", b"e", !hash, 5),
+            test_item("text", b"text", 6, false, None),
+        ] {
+            storage.insert(&item, 100).unwrap();
+        }
+
+        let ids: Vec<String> = storage
+            .get_similar_image_previews("base", 2)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(vec!["one-bit-new", "one-bit-old", "two-bits"], ids);
+        assert!(storage
+            .get_similar_image_previews("text", 64)
+            .unwrap()
+            .is_empty());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    fn clip_format(format: &str, data: &[u8]) -> ClipFormat {
+        ClipFormat {
+            format: format.to_string(),
+            data: data.to_vec(),
+        }
+    }
+
+    fn clip_format_row_count(storage: &ClipStorage) -> i64 {
+        storage
+            .conn
+            .query_row("SELECT COUNT(*) FROM clip_formats", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn full_fidelity_formats_roundtrip_in_order_and_refresh_like_html() {
+        let db_path = temp_db_path("clip_formats_roundtrip");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let formats = vec![
+            clip_format("public.utf8-plain-text", b"a\tb"),
+            clip_format(
+                "public.html",
+                b"<table><tr><td>a</td><td>b</td></tr></table>",
+            ),
+            clip_format("public.png", b"\x89PNG-cells"),
+        ];
+
+        storage
+            .insert(
+                &ClipItem {
+                    formats: formats.clone(),
+                    ..test_item("sheet", b"a\tb", 1, false, None)
+                },
+                100,
+            )
+            .unwrap();
+        assert_eq!(
+            formats,
+            storage.get_by_id("sheet").unwrap().unwrap().formats
+        );
+
+        // A re-copy without formats (full fidelity switched off) keeps the
+        // stored set, exactly like a plain re-copy keeps html (D6)...
+        storage
+            .insert(&test_item("plain-again", b"a\tb", 2, false, None), 100)
+            .unwrap();
+        assert_eq!(
+            formats,
+            storage.get_by_id("sheet").unwrap().unwrap().formats
+        );
+
+        // ...while a re-copy that carried formats replaces them wholesale.
+        let newer = vec![clip_format("public.utf8-plain-text", b"a\tb")];
+        storage
+            .insert(
+                &ClipItem {
+                    formats: newer.clone(),
+                    ..test_item("sheet-again", b"a\tb", 3, false, None)
+                },
+                100,
+            )
+            .unwrap();
+        assert_eq!(newer, storage.get_by_id("sheet").unwrap().unwrap().formats);
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn clip_formats_are_removed_with_their_clip_on_delete_clear_and_prune() {
+        let db_path = temp_db_path("clip_formats_cleanup");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let with_formats = |id: &str, content: &[u8], timestamp: i64, pinned: bool| ClipItem {
+            formats: vec![clip_format("public.rtf", b"{\\rtf1 x}")],
+            ..test_item(id, content, timestamp, pinned, pinned.then_some(1))
+        };
+
+        storage
+            .insert(&with_formats("deleted", b"one", 1, false), 100)
+            .unwrap();
+        storage.delete("deleted").unwrap();
+        assert_eq!(0, clip_format_row_count(&storage));
+
+        storage
+            .insert(&with_formats("pinned", b"two", 2, true), 100)
+            .unwrap();
+        storage
+            .insert(&with_formats("cleared", b"three", 3, false), 100)
+            .unwrap();
+        storage.clear_non_pinned().unwrap();
+        assert_eq!(1, clip_format_row_count(&storage));
+
+        storage
+            .insert(&with_formats("old", b"four", 4, false), 1)
+            .unwrap();
+        storage
+            .insert(&with_formats("new", b"five", 5, false), 1)
+            .unwrap();
+        // Pinned survives pruning; of the two recent clips only "new" fits.
+        assert_eq!(2, clip_format_row_count(&storage));
+        assert!(storage.get_by_id("old").unwrap().is_none());
+        assert_eq!(1, storage.get_by_id("new").unwrap().unwrap().formats.len());
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    fn database_byte_size(storage: &ClipStorage) -> i64 {
+        let page_count: i64 = storage
+            .conn
+            .query_row("PRAGMA page_count", [], |row| row.get(0))
+            .unwrap();
+        let page_size: i64 = storage
+            .conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))
+            .unwrap();
+        page_count * page_size
+    }
+
+    #[test]
+    fn new_database_enables_incremental_auto_vacuum() {
+        let db_path = temp_db_path("auto_vacuum_new");
+        let storage = ClipStorage::new(&db_path).unwrap();
+
+        let mode: i64 = storage
+            .conn
+            .query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(2, mode);
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn opening_legacy_none_auto_vacuum_database_upgrades_to_incremental() {
+        let db_path = temp_db_path("auto_vacuum_legacy");
+        {
+            let conn = Connection::open(&db_path).unwrap();
             ClipStorage::initialize_schema(&conn).unwrap();
             conn.execute(
                 "INSERT INTO clips (id, content, thumbnail, content_hash, content_type, timestamp, is_pinned, pin_order, label, group_name)
@@ -2571,4 +4200,108 @@ mod tests {
         drop(storage);
         cleanup_db(&db_path);
     }
+
+    #[test]
+    fn statistics_count_recorded_events_in_window() {
+        let db_path = temp_db_path("statistics");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let day = SECONDS_PER_DAY;
+        let now = 100 * day;
+
+        storage
+            .insert(&test_item("a", b"alpha", now, false, None), 10)
+            .unwrap();
+        storage
+            .insert(&files_item("f", &["/tmp/x"], now), 10)
+            .unwrap();
+        let captures = [
+            ("a", ContentType::Text, Some("Editor"), now - day),
+            ("a", ContentType::Text, Some("Editor"), now),
+            ("f", ContentType::Files, None, now),
+            // Outside the window.
+            ("a", ContentType::Text, Some("Editor"), now - 40 * day),
+        ];
+        for (id, content_type, app, timestamp) in &captures {
+            storage
+                .record_event(
+                    ClipEventKind::Captured,
+                    Some(id),
+                    Some(content_type),
+                    *app,
+                    *timestamp,
+                )
+                .unwrap();
+        }
+        storage
+            .record_event(
+                ClipEventKind::SecretSkipped,
+                None,
+                None,
+                Some("Terminal"),
+                now,
+            )
+            .unwrap();
+        storage
+            .record_pastes(&["f".to_string(), "a".to_string(), "a".to_string()], now)
+            .unwrap();
+
+        let stats = storage.statistics(now - 30 * day, 10).unwrap();
+
+        assert_eq!(
+            vec![1, 2],
+            stats
+                .clips_per_day
+                .iter()
+                .map(|d| d.count)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some("Editor"), stats.clips_per_app[0].app.as_deref());
+        assert_eq!(2, stats.clips_per_app[0].count);
+        assert_eq!(ContentType::Text, stats.content_types[0].content_type);
+        assert_eq!(1, stats.content_types[1].count);
+        assert_eq!(
+            vec![("a", 2), ("f", 1)],
+            stats
+                .top_pasted
+                .iter()
+                .map(|p| (p.clip.id.as_str(), p.pastes))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, stats.secrets_skipped);
+        assert_eq!(0, stats.oversized_skipped);
+        assert_eq!(2, stats.stored_clips);
+        assert_eq!(("alpha".len() + "/tmp/x".len()) as i64, stats.storage.text);
+        assert!(stats.storage.database > 0);
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn events_past_retention_are_trimmed_and_deleted_clips_stop_ranking() {
+        let db_path = temp_db_path("statistics_retention");
+        let storage = ClipStorage::new(&db_path).unwrap();
+        let now = 200 * SECONDS_PER_DAY;
+
+        storage
+            .insert(&test_item("a", b"alpha", now, false, None), 10)
+            .unwrap();
+        storage
+            .record_event(ClipEventKind::OversizedSkipped, None, None, None, 0)
+            .unwrap();
+        storage.record_pastes(&["a".to_string()], now).unwrap();
+        let remaining: i64 = storage
+            .conn
+            .query_row("SELECT COUNT(*) FROM clip_events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(1, remaining);
+
+        storage.delete("a").unwrap();
+        let stats = storage.statistics(0, 10).unwrap();
+        assert!(stats.top_pasted.is_empty());
+        assert_eq!(0, stats.oversized_skipped);
+
+        drop(storage);
+        cleanup_db(&db_path);
+    }
 }
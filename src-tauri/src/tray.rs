@@ -2,11 +2,14 @@
 use lru::LruCache;
 use std::num::NonZeroUsize;
 use std::sync::Mutex;
+use std::time::Duration;
 use tauri::menu::{
     CheckMenuItemBuilder, IconMenuItemBuilder, MenuBuilder, MenuEvent, MenuItemBuilder,
+    SubmenuBuilder,
 };
 use tauri::{AppHandle, Manager};
 
+use crate::pause::TimedPause;
 use crate::storage::{ClipPreviewItem, ContentType};
 use crate::AppState;
 
@@ -24,6 +27,17 @@ pub struct TrayI18n {
     pub image: &'static str,
     pub clear: &'static str,
     pub pause_capture: &'static str,
+    pub pause_5_minutes: &'static str,
+    pub pause_1_hour: &'static str,
+    pub pause_until_restart: &'static str,
+    pub pause_until_resumed: &'static str,
+    pub resume_capture: &'static str,
+    pub capture_paused: &'static str,
+    /// `{}` is replaced by the remaining time.
+    pub capture_paused_remaining: &'static str,
+    pub capture_paused_until_restart: &'static str,
+    pub minutes: &'static str,
+    pub hours: &'static str,
     pub settings: &'static str,
     pub quit: &'static str,
 }
@@ -37,6 +51,16 @@ impl TrayI18n {
                 image: "图片",
                 clear: "清除",
                 pause_capture: "暂停采集",
+                pause_5_minutes: "暂停 5 分钟",
+                pause_1_hour: "暂停 1 小时",
+                pause_until_restart: "暂停至重启",
+                pause_until_resumed: "暂停至手动恢复",
+                resume_capture: "恢复采集",
+                capture_paused: "采集已暂停",
+                capture_paused_remaining: "采集已暂停 · 剩余 {}",
+                capture_paused_until_restart: "采集已暂停 · 至重启",
+                minutes: "分钟",
+                hours: "小时",
                 settings: "设置",
                 quit: "退出",
             }
@@ -47,6 +71,16 @@ impl TrayI18n {
                 image: "Image",
                 clear: "Clear",
                 pause_capture: "Pause Capture",
+                pause_5_minutes: "For 5 Minutes",
+                pause_1_hour: "For 1 Hour",
+                pause_until_restart: "Until Restart",
+                pause_until_resumed: "Until Resumed",
+                resume_capture: "Resume Capture",
+                capture_paused: "Capture Paused",
+                capture_paused_remaining: "Capture Paused · {} left",
+                capture_paused_until_restart: "Capture Paused · until restart",
+                minutes: "min",
+                hours: "h",
                 settings: "Settings",
                 quit: "Quit",
             }
@@ -168,6 +202,37 @@ pub fn truncate_content(
     }
 }
 
+/// Remaining pause time rounded up to whole minutes, e.g. "5 min" or
+/// "1 h 30 min".
+pub fn format_remaining(secs: i64, i18n: &TrayI18n) -> String {
+    let minutes = ((secs + 59) / 60).max(1);
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    match (hours, minutes) {
+        (0, minutes) => format!("{} {}", minutes, i18n.minutes),
+        (hours, 0) => format!("{} {}", hours, i18n.hours),
+        (hours, minutes) => format!("{} {} {} {}", hours, i18n.hours, minutes, i18n.minutes),
+    }
+}
+
+/// Title of the pause submenu, which doubles as the pause status line.
+fn pause_menu_title(
+    manual_pause: bool,
+    timed_pause: Option<TimedPause>,
+    now: i64,
+    i18n: &TrayI18n,
+) -> String {
+    match timed_pause {
+        Some(pause) => match pause.remaining_secs(now) {
+            Some(secs) => i18n
+                .capture_paused_remaining
+                .replace("{}", &format_remaining(secs, i18n)),
+            None => i18n.capture_paused_until_restart.to_string(),
+        },
+        None if manual_pause => i18n.capture_paused.to_string(),
+        None => i18n.pause_capture.to_string(),
+    }
+}
+
 /// Helper function to add a clip menu item
 fn add_clip_menu_item(
     app: &AppHandle,
@@ -257,17 +322,37 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<tauri::menu::Menu<tauri::Wry>,
         }
     }
 
-    // Bottom actions. The pause-capture item is a checkbox reflecting
-    // `capture_paused`; main.rs's menu event handler flips the setting,
-    // persists it, and rebuilds this menu so the check mark stays in sync.
-    let pause_capture_item = CheckMenuItemBuilder::with_id("pause_capture", i18n.pause_capture)
-        .checked(settings.capture_paused)
-        .build(app)?;
+    // Bottom actions. The pause submenu's title shows the pause status and
+    // remaining time; `pause`'s ticker rebuilds this menu while a timed pause
+    // runs. "Until Resumed" is a checkbox reflecting `capture_paused`.
+    let now = chrono::Utc::now().timestamp();
+    let timed_pause = state.capture_pause.current(now);
+    let is_paused = settings.capture_paused || timed_pause.is_some();
+    let pause_menu = SubmenuBuilder::with_id(
+        app,
+        "pause_menu",
+        pause_menu_title(settings.capture_paused, timed_pause, now, &i18n),
+    )
+    .item(&MenuItemBuilder::with_id("pause_5_minutes", i18n.pause_5_minutes).build(app)?)
+    .item(&MenuItemBuilder::with_id("pause_1_hour", i18n.pause_1_hour).build(app)?)
+    .item(&MenuItemBuilder::with_id("pause_until_restart", i18n.pause_until_restart).build(app)?)
+    .item(
+        &CheckMenuItemBuilder::with_id("pause_capture", i18n.pause_until_resumed)
+            .checked(settings.capture_paused)
+            .build(app)?,
+    )
+    .separator()
+    .item(
+        &MenuItemBuilder::with_id("resume_capture", i18n.resume_capture)
+            .enabled(is_paused)
+            .build(app)?,
+    )
+    .build()?;
 
     menu_builder = menu_builder
         .separator()
         .item(&MenuItemBuilder::with_id("clear_non_pinned", i18n.clear).build(app)?)
-        .item(&pause_capture_item)
+        .item(&pause_menu)
         .item(&MenuItemBuilder::with_id("settings", i18n.settings).build(app)?)
         .item(&MenuItemBuilder::with_id("quit", i18n.quit).build(app)?);
 
@@ -303,28 +388,11 @@ pub fn handle_tray_menu_event(app: &AppHandle, event: MenuEvent) {
                 log::error!("Failed to open settings window: {}", e);
             }
         }
-        "pause_capture" => {
-            let state: tauri::State<AppState> = app.state();
-            // Serialize against `update_settings` so a settings-page save and
-            // this tray toggle can't race each other and clobber one another's
-            // change. `capture_paused` is owned exclusively by this toggle.
-            let _settings_write_guard = crate::safe_lock(&state.settings_write_lock);
-
-            let mut settings = state.settings.get();
-            settings.capture_paused = !settings.capture_paused;
-            let now_paused = settings.capture_paused;
-            state.settings.set(settings);
-
-            if let Err(e) = state.settings.save(app) {
-                log::error!("Failed to persist capture_paused toggle: {}", e);
-            }
-            log::info!(
-                "Clipboard capture {} via tray menu",
-                if now_paused { "paused" } else { "resumed" }
-            );
-
-            update_tray_menu(app);
-        }
+        "pause_capture" => crate::pause::toggle_manual(app),
+        "pause_5_minutes" => crate::pause::pause_for(app, Duration::from_secs(5 * 60)),
+        "pause_1_hour" => crate::pause::pause_for(app, Duration::from_secs(60 * 60)),
+        "pause_until_restart" => crate::pause::pause_until_restart(app),
+        "resume_capture" => crate::pause::resume(app),
         id if id.starts_with("clip:") => {
             let clip_id = id.strip_prefix("clip:").unwrap().to_string();
             log::info!("Clip item clicked: {}", clip_id);
@@ -381,6 +449,34 @@ mod tests {
         assert_eq!(i18n.quit, "Quit");
     }
 
+    #[test]
+    fn test_format_remaining_rounds_up_to_minutes() {
+        let en = TrayI18n::new("en");
+        assert_eq!("1 min", format_remaining(0, &en));
+        assert_eq!("1 min", format_remaining(59, &en));
+        assert_eq!("5 min", format_remaining(300, &en));
+        assert_eq!("1 h", format_remaining(3_600, &en));
+        assert_eq!("1 h 30 min", format_remaining(5_370, &en));
+
+        let zh = TrayI18n::new("zh-CN");
+        assert_eq!("1 小时 1 分钟", format_remaining(3_601, &zh));
+    }
+
+    #[test]
+    fn test_pause_menu_title_reflects_pause_state() {
+        let i18n = TrayI18n::new("en");
+        assert_eq!("Pause Capture", pause_menu_title(false, None, 0, &i18n));
+        assert_eq!("Capture Paused", pause_menu_title(true, None, 0, &i18n));
+        assert_eq!(
+            "Capture Paused · 4 min left",
+            pause_menu_title(false, Some(TimedPause::Until(1_000)), 790, &i18n)
+        );
+        assert_eq!(
+            "Capture Paused · until restart",
+            pause_menu_title(true, Some(TimedPause::UntilRestart), 0, &i18n)
+        );
+    }
+
     #[test]
     fn test_truncate_content_text() {
         let i18n = TrayI18n::new("en");
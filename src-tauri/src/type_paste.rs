@@ -0,0 +1,207 @@
+//! Type-out paste (`PasteMode::Type`): a text clip is sent as synthesized
+//! keystrokes instead of through the clipboard, for remote consoles and VMs
+//! that ignore Ctrl+V. The system clipboard is never read or written.
+//!
+//! Characters go through enigo's text input, which injects Unicode directly
+//! (CGEvent on macOS, KEYEVENTF_UNICODE on Windows, a remapped keycode on
+//! X11), so the user's layout does not matter. Line breaks and tabs are sent
+//! as Return and Tab key presses, which consoles expect rather than the raw
+//! control characters.
+//!
+//! While typing, a cancel shortcut is registered globally; pressing it stops
+//! before the next character.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use enigo::{Direction::Click, Enigo, Key, Keyboard, Settings as EnigoSettings};
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// Only one clip is typed at a time: a second would interleave keystrokes
+/// and both would claim the cancel shortcut.
+static TYPING: AtomicBool = AtomicBool::new(false);
+
+/// One synthesized input step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Keystroke {
+    Char(char),
+    Return,
+    Tab,
+}
+
+/// How a typing run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypingOutcome {
+    Typed,
+    Cancelled,
+}
+
+/// The keystrokes that type `text`. CRLF and lone CR become one Return;
+/// other control characters have no key to type and are dropped.
+pub fn keystrokes(text: &str) -> Vec<Keystroke> {
+    let mut strokes = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                strokes.push(Keystroke::Return);
+            }
+            '\n' => strokes.push(Keystroke::Return),
+            '\t' => strokes.push(Keystroke::Tab),
+            ch if ch.is_control() => {}
+            ch => strokes.push(Keystroke::Char(ch)),
+        }
+    }
+    strokes
+}
+
+/// Refuses text longer than `max_chars` characters: typing it would take
+/// too long to be what the user meant, and could not be undone quickly.
+pub fn check_length(text: &str, max_chars: usize) -> Result<(), String> {
+    let chars = text.chars().count();
+    if chars > max_chars {
+        return Err(format!(
+            "Clip is too long to type ({} characters, limit {})",
+            chars, max_chars
+        ));
+    }
+    Ok(())
+}
+
+/// A typing run in progress: holds the single-run guard and the registered
+/// cancel shortcut, releasing both when dropped.
+pub struct TypingSession {
+    app: AppHandle,
+    cancel_shortcut: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TypingSession {
+    pub fn start(app: &AppHandle, cancel_shortcut: &str) -> Result<Self, String> {
+        if TYPING.swap(true, Ordering::SeqCst) {
+            return Err("Another clip is still being typed".to_string());
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        let registered =
+            app.global_shortcut()
+                .on_shortcut(cancel_shortcut, move |_app, _shortcut, event| {
+                    if matches!(event.state, ShortcutState::Pressed) {
+                        log::info!("Typing cancelled from the keyboard");
+                        flag.store(true, Ordering::SeqCst);
+                    }
+                });
+        if let Err(e) = registered {
+            TYPING.store(false, Ordering::SeqCst);
+            return Err(format!(
+                "Failed to register typing cancel shortcut '{}': {}",
+                cancel_shortcut, e
+            ));
+        }
+
+        Ok(Self {
+            app: app.clone(),
+            cancel_shortcut: cancel_shortcut.to_string(),
+            cancelled,
+        })
+    }
+
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+}
+
+impl Drop for TypingSession {
+    fn drop(&mut self) {
+        if let Err(e) = self
+            .app
+            .global_shortcut()
+            .unregister(self.cancel_shortcut.as_str())
+        {
+            log::warn!(
+                "Failed to unregister typing cancel shortcut '{}': {}",
+                self.cancel_shortcut,
+                e
+            );
+        }
+        TYPING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Types `strokes` one by one, `delay` apart, until done or `cancelled` is
+/// set. Blocks for the whole run.
+pub fn type_keystrokes(
+    strokes: &[Keystroke],
+    delay: Duration,
+    cancelled: &AtomicBool,
+) -> Result<TypingOutcome, String> {
+    let mut enigo = Enigo::new(&EnigoSettings::default())
+        .map_err(|e| format!("Failed to initialize input simulation: {e}"))?;
+
+    let mut buf = [0u8; 4];
+    for (index, stroke) in strokes.iter().enumerate() {
+        if cancelled.load(Ordering::SeqCst) {
+            log::info!(
+                "Stopped typing after {} of {} keystrokes",
+                index,
+                strokes.len()
+            );
+            return Ok(TypingOutcome::Cancelled);
+        }
+        if index > 0 && !delay.is_zero() {
+            thread::sleep(delay);
+        }
+
+        match stroke {
+            Keystroke::Char(ch) => enigo.text(ch.encode_utf8(&mut buf)),
+            Keystroke::Return => enigo.key(Key::Return, Click),
+            Keystroke::Tab => enigo.key(Key::Tab, Click),
+        }
+        .map_err(|e| format!("Failed to type keystroke {}: {e}", index + 1))?;
+    }
+    Ok(TypingOutcome::Typed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_breaks_and_tabs_become_keys_and_other_controls_are_dropped() {
+        assert_eq!(
+            vec![
+                Keystroke::Char('a'),
+                Keystroke::Return,
+                Keystroke::Char('b'),
+                Keystroke::Return,
+                Keystroke::Return,
+                Keystroke::Tab,
+                Keystroke::Char('c'),
+            ],
+            keystrokes("a\r\nb\n\r\tc\u{7}\u{1b}")
+        );
+    }
+
+    #[test]
+    fn unicode_is_typed_per_character() {
+        assert_eq!(
+            vec![
+                Keystroke::Char('é'),
+                Keystroke::Char('中'),
+                Keystroke::Char('😀'),
+            ],
+            keystrokes("é中😀")
+        );
+    }
+
+    #[test]
+    fn length_guard_counts_characters_not_bytes() {
+        assert!(check_length("中文字", 3).is_ok());
+        assert!(check_length("中文字!", 3).is_err());
+    }
+}
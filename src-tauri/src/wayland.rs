@@ -0,0 +1,378 @@
+//! Native clipboard change notifications on Wayland, through the
+//! data-control protocols: `ext-data-control-v1`, or wlroots'
+//! `wlr-data-control-unstable-v1` on compositors that predate it.
+//!
+//! An ordinary Wayland client only sees the selection while it has focus,
+//! so clipboard-master cannot watch it and arboard's polling fallback
+//! either fails or spins. A data-control device is sent every selection
+//! change, focused or not. The watcher only reports *that* the selection
+//! changed and which mime types it offers; the content is still read
+//! through arboard (built with its data-control backend), like on every
+//! other platform.
+//!
+//! GNOME's compositor implements neither protocol: [`SelectionWatcher::connect`]
+//! then fails and the monitor keeps clipboard-master and its polling fallback.
+
+use std::collections::HashMap;
+use std::os::fd::BorrowedFd;
+use std::time::{Duration, Instant};
+
+use wayland_client::backend::{ObjectId, WaylandError};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry::WlRegistry, wl_seat::WlSeat};
+use wayland_client::{
+    delegate_noop, event_created_child, Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+};
+use wayland_protocols::ext::data_control::v1::client::{
+    ext_data_control_device_v1::{self, ExtDataControlDeviceV1},
+    ext_data_control_manager_v1::ExtDataControlManagerV1,
+    ext_data_control_offer_v1::{self, ExtDataControlOfferV1},
+};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+    zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+    zwlr_data_control_offer_v1::{self, ZwlrDataControlOfferV1},
+};
+
+/// Mime type KDE's password managers (KeePassXC, KWallet) add to a copied
+/// password; the Wayland counterpart of the macOS concealed type.
+const PASSWORD_MANAGER_HINT: &str = "x-kde-passwordManagerHint";
+
+/// Whether this process runs in a Wayland session.
+pub fn session_available() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionChange {
+    /// A client took the selection, offering these mime types.
+    Set { mime_types: Vec<String> },
+    /// The selection was cleared, or its owner went away.
+    Cleared,
+}
+
+impl SelectionChange {
+    /// Whether the source marked the content as a password.
+    pub fn is_concealed(&self) -> bool {
+        match self {
+            SelectionChange::Set { mime_types } => mime_types
+                .iter()
+                .any(|mime_type| mime_type == PASSWORD_MANAGER_HINT),
+            SelectionChange::Cleared => false,
+        }
+    }
+}
+
+/// A data offer from either protocol.
+enum Offer {
+    Ext(ExtDataControlOfferV1),
+    Wlr(ZwlrDataControlOfferV1),
+}
+
+impl Offer {
+    fn id(&self) -> ObjectId {
+        match self {
+            Offer::Ext(offer) => offer.id(),
+            Offer::Wlr(offer) => offer.id(),
+        }
+    }
+
+    fn destroy(&self) {
+        match self {
+            Offer::Ext(offer) => offer.destroy(),
+            Offer::Wlr(offer) => offer.destroy(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct WatcherState {
+    /// Mime types announced so far by each offer that is not yet the
+    /// selection. The compositor sends them right after the offer itself.
+    offer_mime_types: HashMap<ObjectId, Vec<String>>,
+    /// The offer currently holding the selection, destroyed once replaced.
+    selection: Option<Offer>,
+    /// Latest change not yet returned by `wait_for_change`; a burst of
+    /// changes coalesces into the last one.
+    pending: Option<SelectionChange>,
+    /// The compositor destroyed the device (e.g. the seat went away).
+    finished: bool,
+}
+
+impl WatcherState {
+    fn add_mime_type(&mut self, offer: ObjectId, mime_type: String) {
+        if let Some(mime_types) = self.offer_mime_types.get_mut(&offer) {
+            mime_types.push(mime_type);
+        }
+    }
+
+    fn set_selection(&mut self, offer: Option<Offer>) {
+        self.pending = Some(match &offer {
+            Some(offer) => SelectionChange::Set {
+                mime_types: self
+                    .offer_mime_types
+                    .remove(&offer.id())
+                    .unwrap_or_default(),
+            },
+            None => SelectionChange::Cleared,
+        });
+        if let Some(previous) = std::mem::replace(&mut self.selection, offer) {
+            previous.destroy();
+        }
+    }
+
+    /// The primary selection (middle-click paste) is not captured.
+    fn discard_offer(&mut self, offer: Offer) {
+        self.offer_mime_types.remove(&offer.id());
+        offer.destroy();
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for WatcherState {
+    fn event(
+        _state: &mut Self,
+        _registry: &WlRegistry,
+        _event: <WlRegistry as Proxy>::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(WatcherState: ignore WlSeat);
+delegate_noop!(WatcherState: ExtDataControlManagerV1);
+delegate_noop!(WatcherState: ZwlrDataControlManagerV1);
+
+/// The two protocols are event-for-event identical; only the types differ.
+macro_rules! dispatch_data_control {
+    ($device:ty, $device_mod:ident, $offer:ty, $offer_mod:ident, $variant:ident) => {
+        impl Dispatch<$device, ()> for WatcherState {
+            fn event(
+                state: &mut Self,
+                _device: &$device,
+                event: $device_mod::Event,
+                _data: &(),
+                _conn: &Connection,
+                _qh: &QueueHandle<Self>,
+            ) {
+                match event {
+                    $device_mod::Event::DataOffer { id } => {
+                        state.offer_mime_types.insert(id.id(), Vec::new());
+                    }
+                    $device_mod::Event::Selection { id } => {
+                        state.set_selection(id.map(Offer::$variant));
+                    }
+                    $device_mod::Event::PrimarySelection { id: Some(id) } => {
+                        state.discard_offer(Offer::$variant(id));
+                    }
+                    $device_mod::Event::Finished => state.finished = true,
+                    _ => {}
+                }
+            }
+
+            event_created_child!(WatcherState, $device, [
+                $device_mod::EVT_DATA_OFFER_OPCODE => ($offer, ()),
+            ]);
+        }
+
+        impl Dispatch<$offer, ()> for WatcherState {
+            fn event(
+                state: &mut Self,
+                offer: &$offer,
+                event: $offer_mod::Event,
+                _data: &(),
+                _conn: &Connection,
+                _qh: &QueueHandle<Self>,
+            ) {
+                if let $offer_mod::Event::Offer { mime_type } = event {
+                    state.add_mime_type(offer.id(), mime_type);
+                }
+            }
+        }
+    };
+}
+
+dispatch_data_control!(
+    ExtDataControlDeviceV1,
+    ext_data_control_device_v1,
+    ExtDataControlOfferV1,
+    ext_data_control_offer_v1,
+    Ext
+);
+dispatch_data_control!(
+    ZwlrDataControlDeviceV1,
+    zwlr_data_control_device_v1,
+    ZwlrDataControlOfferV1,
+    zwlr_data_control_offer_v1,
+    Wlr
+);
+
+/// A data-control device on the first seat, reporting selection changes.
+pub struct SelectionWatcher {
+    queue: EventQueue<WatcherState>,
+    state: WatcherState,
+}
+
+impl SelectionWatcher {
+    /// Connect to `WAYLAND_DISPLAY` and start watching the selection. The
+    /// selection already present is reported as the first change, just as
+    /// the polling fallback's first read captures it.
+    pub fn connect() -> Result<Self, String> {
+        let connection = Connection::connect_to_env()
+            .map_err(|e| format!("Failed to connect to the Wayland display: {}", e))?;
+        let (globals, mut queue) = registry_queue_init::<WatcherState>(&connection)
+            .map_err(|e| format!("Failed to list Wayland globals: {}", e))?;
+        let qh = queue.handle();
+
+        let seat: WlSeat = globals
+            .bind(&qh, 1..=1, ())
+            .map_err(|e| format!("No Wayland seat to watch: {}", e))?;
+        if let Ok(manager) = globals.bind::<ExtDataControlManagerV1, _, _>(&qh, 1..=1, ()) {
+            manager.get_data_device(&seat, &qh, ());
+            log::info!("Watching the Wayland clipboard through ext-data-control");
+        } else if let Ok(manager) = globals.bind::<ZwlrDataControlManagerV1, _, _>(&qh, 1..=2, ()) {
+            manager.get_data_device(&seat, &qh, ());
+            log::info!("Watching the Wayland clipboard through wlr-data-control");
+        } else {
+            return Err(
+                "The Wayland compositor supports neither ext- nor wlr-data-control".to_string(),
+            );
+        }
+
+        let mut state = WatcherState::default();
+        queue
+            .roundtrip(&mut state)
+            .map_err(|e| format!("Failed to set up the Wayland data-control device: {}", e))?;
+        Ok(Self { queue, state })
+    }
+
+    /// Wait up to `timeout` for the selection to change. `Ok(None)` means
+    /// nothing changed; an error means the watcher is unusable and the
+    /// caller should fall back.
+    pub fn wait_for_change(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<SelectionChange>, String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.queue
+                .dispatch_pending(&mut self.state)
+                .map_err(|e| format!("Failed to dispatch Wayland events: {}", e))?;
+            if self.state.finished {
+                return Err("The Wayland data-control device was destroyed".to_string());
+            }
+            if let Some(change) = self.state.pending.take() {
+                return Ok(Some(change));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+
+            self.queue
+                .flush()
+                .map_err(|e| format!("Failed to flush the Wayland connection: {}", e))?;
+            // `None` means events were queued meanwhile: dispatch them first.
+            let Some(guard) = self.queue.prepare_read() else {
+                continue;
+            };
+            if poll_readable(guard.connection_fd(), remaining)? {
+                match guard.read() {
+                    Ok(_) => {}
+                    Err(WaylandError::Io(e)) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                    Err(e) => return Err(format!("Failed to read Wayland events: {}", e)),
+                }
+            }
+        }
+    }
+}
+
+fn poll_readable(fd: BorrowedFd<'_>, timeout: Duration) -> Result<bool, String> {
+    use rustix::event::{poll, PollFd, PollFlags};
+
+    let mut fds = [PollFd::new(&fd, PollFlags::IN)];
+    // Round up, so a sub-millisecond remainder waits instead of spinning.
+    let timeout_ms = timeout.as_millis().clamp(1, i32::MAX as u128) as i32;
+    match poll(&mut fds, timeout_ms) {
+        Ok(ready) => Ok(ready > 0),
+        Err(rustix::io::Errno::INTR) => Ok(false),
+        Err(e) => Err(format!("Failed to poll the Wayland connection: {}", e)),
+    }
+}
+
+/// These need a compositor that implements data-control, e.g. a headless
+/// sway: `WLR_BACKENDS=headless WLR_LIBINPUT_NO_DEVICES=1 sway &`, then
+/// `WAYLAND_DISPLAY=wayland-1 cargo test wayland -- --ignored`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    fn wait_for_set(watcher: &mut SelectionWatcher) -> Vec<String> {
+        let deadline = Instant::now() + WAIT;
+        while Instant::now() < deadline {
+            if let Some(SelectionChange::Set { mime_types }) =
+                watcher.wait_for_change(WAIT).unwrap()
+            {
+                return mime_types;
+            }
+        }
+        panic!("no selection change within {:?}", WAIT);
+    }
+
+    #[test]
+    fn concealed_only_when_the_password_hint_is_offered() {
+        let set = |mime_types: &[&str]| SelectionChange::Set {
+            mime_types: mime_types.iter().map(|m| m.to_string()).collect(),
+        };
+
+        assert!(set(&["text/plain", PASSWORD_MANAGER_HINT]).is_concealed());
+        assert!(!set(&["text/plain"]).is_concealed());
+        assert!(!SelectionChange::Cleared.is_concealed());
+    }
+
+    #[test]
+    #[ignore = "needs a Wayland compositor with data-control"]
+    fn watcher_reports_a_copy_by_another_client() {
+        let mut watcher = SelectionWatcher::connect().unwrap();
+        // Drain the selection present at connect time.
+        while watcher
+            .wait_for_change(Duration::from_millis(200))
+            .unwrap()
+            .is_some()
+        {}
+
+        let mut clipboard = arboard::Clipboard::new().unwrap();
+        clipboard.set_text("copied on wayland").unwrap();
+
+        let mime_types = wait_for_set(&mut watcher);
+        assert!(mime_types.iter().any(|mime| mime.starts_with("text/plain")));
+        assert_eq!(
+            "copied on wayland",
+            clipboard.get_text().unwrap(),
+            "arboard must read the selection through data-control, unfocused"
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a Wayland compositor with data-control"]
+    fn watcher_reports_a_cleared_selection() {
+        let mut watcher = SelectionWatcher::connect().unwrap();
+        let mut clipboard = arboard::Clipboard::new().unwrap();
+        clipboard.set_text("about to be cleared").unwrap();
+        wait_for_set(&mut watcher);
+
+        clipboard.clear().unwrap();
+
+        let deadline = Instant::now() + WAIT;
+        while Instant::now() < deadline {
+            if watcher.wait_for_change(WAIT).unwrap() == Some(SelectionChange::Cleared) {
+                return;
+            }
+        }
+        panic!("selection was not cleared within {:?}", WAIT);
+    }
+}
@@ -3,14 +3,13 @@ use std::sync::{Arc, Mutex};
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
 
+use crate::placement::{
+    quickbar_logical_size, quickbar_offset, quickbar_position, QuickBarPlacement, WorkArea,
+};
+use crate::AppState;
+
 pub const QUICKBAR_WINDOW_LABEL: &str = "main";
 pub const SETTINGS_WINDOW_LABEL: &str = "settings";
-// Adaptive sizing caps (logical px): comfortable max on large screens, scaled
-// down proportionally on smaller ones. The initial window size lives in
-// `tauri.conf.json` (820×600); the real size is computed per-monitor in
-// `position_quickbar`.
-const QUICKBAR_MAX_WIDTH: f64 = 820.0;
-const QUICKBAR_MAX_HEIGHT: f64 = 600.0;
 pub const QUICKBAR_HIDDEN_EVENT: &str = "quickbar-hidden";
 
 pub type ForegroundWindowStore = Arc<Mutex<Option<ForegroundWindow>>>;
@@ -30,21 +29,48 @@ impl QuickBarPanel {
     }
 }
 
+/// What the QuickBar shows when it opens: a panel, optionally with the
+/// search box holding `query`, or narrowed to the clips of one `group`.
+#[derive(Debug, Clone)]
+pub struct QuickBarView {
+    pub panel: QuickBarPanel,
+    pub query: Option<String>,
+    pub group: Option<String>,
+}
+
+impl From<QuickBarPanel> for QuickBarView {
+    fn from(panel: QuickBarPanel) -> Self {
+        Self {
+            panel,
+            query: None,
+            group: None,
+        }
+    }
+}
+
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct QuickBarOpenedPayload {
     panel: &'static str,
+    query: Option<String>,
+    group: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ForegroundWindow {
     raw: isize,
+    /// What the paste key profiles match against; see `paste_keys`.
+    app_name: Option<String>,
 }
 
 impl ForegroundWindow {
-    pub fn raw(self) -> isize {
+    pub fn raw(&self) -> isize {
         self.raw
     }
+
+    pub fn app_name(&self) -> Option<&str> {
+        self.app_name.as_deref()
+    }
 }
 
 pub fn setup_windows(app: &AppHandle) -> Result<(), String> {
@@ -72,11 +98,19 @@ pub fn show_quickbar_with_panel(
     app: &AppHandle,
     foreground_store: &ForegroundWindowStore,
     panel: QuickBarPanel,
+) -> Result<(), String> {
+    show_quickbar_with_view(app, foreground_store, panel.into())
+}
+
+pub fn show_quickbar_with_view(
+    app: &AppHandle,
+    foreground_store: &ForegroundWindowStore,
+    view: QuickBarView,
 ) -> Result<(), String> {
     let quickbar = get_window(app, QUICKBAR_WINDOW_LABEL)?;
 
     remember_foreground_window(foreground_store, &quickbar);
-    position_quickbar(&quickbar)?;
+    position_quickbar(&quickbar, foreground_store)?;
 
     quickbar.unminimize().map_err(to_string)?;
     quickbar.show().map_err(to_string)?;
@@ -85,7 +119,9 @@ pub fn show_quickbar_with_panel(
     app.emit(
         "quickbar-opened",
         QuickBarOpenedPayload {
-            panel: panel.as_str(),
+            panel: view.panel.as_str(),
+            query: view.query,
+            group: view.group,
         },
     )
     .map_err(to_string)?;
@@ -93,6 +129,17 @@ pub fn show_quickbar_with_panel(
     Ok(())
 }
 
+/// Records the window that has focus now as the paste target, for pastes
+/// made without opening the QuickBar over it.
+pub fn record_foreground_window(
+    app: &AppHandle,
+    foreground_store: &ForegroundWindowStore,
+) -> Result<(), String> {
+    let quickbar = get_window(app, QUICKBAR_WINDOW_LABEL)?;
+    remember_foreground_window(foreground_store, &quickbar);
+    Ok(())
+}
+
 pub fn hide_quickbar(app: &AppHandle) -> Result<(), String> {
     let quickbar = get_window(app, QUICKBAR_WINDOW_LABEL)?;
     hide_quickbar_window(&quickbar)
@@ -110,7 +157,7 @@ pub fn open_settings_window(app: &AppHandle) -> Result<(), String> {
 
 #[cfg(windows)]
 pub fn recorded_foreground_window(store: &ForegroundWindowStore) -> Option<ForegroundWindow> {
-    *crate::safe_lock(store)
+    crate::safe_lock(store).clone()
 }
 
 #[cfg(windows)]
@@ -124,7 +171,8 @@ pub fn restore_recorded_foreground_window(store: &ForegroundWindowStore) -> Resu
 pub fn restore_recorded_foreground_window(store: &ForegroundWindowStore) -> Result<(), String> {
     use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
 
-    let target = (*crate::safe_lock(store))
+    let target = crate::safe_lock(store)
+        .clone()
         .ok_or_else(|| "No foreground app was recorded before QuickBar opened".to_string())?;
     let pid = target.raw() as i32;
 
@@ -139,10 +187,14 @@ pub fn restore_recorded_foreground_window(store: &ForegroundWindowStore) -> Resu
     Ok(())
 }
 
-#[cfg(all(not(windows), not(target_os = "macos")))]
-#[allow(dead_code)]
-pub fn restore_recorded_foreground_window(_store: &ForegroundWindowStore) -> Result<(), String> {
-    Ok(())
+/// Wayland sessions record nothing: no protocol tells a client which window
+/// has focus, so there is nothing to restore.
+#[cfg(target_os = "linux")]
+pub fn restore_recorded_foreground_window(store: &ForegroundWindowStore) -> Result<(), String> {
+    let Some(target) = crate::safe_lock(store).clone() else {
+        return Ok(());
+    };
+    crate::x11_focus::activate(target.raw() as u32)
 }
 
 fn setup_quickbar_window(window: &WebviewWindow) -> Result<(), String> {
@@ -191,6 +243,9 @@ fn invalidate_quickbar_shadow(window: &WebviewWindow) {
 fn invalidate_quickbar_shadow(_window: &WebviewWindow) {}
 
 fn hide_quickbar_window(window: &WebviewWindow) -> Result<(), String> {
+    if window.is_visible().unwrap_or(false) {
+        remember_quickbar_position(window);
+    }
     window.hide().map_err(to_string)?;
     window.emit(QUICKBAR_HIDDEN_EVENT, ()).map_err(to_string)?;
     Ok(())
@@ -222,63 +277,204 @@ fn ns_window(window: &WebviewWindow) -> Result<&objc2_app_kit::NSWindow, String>
     }
 }
 
-fn position_quickbar(window: &WebviewWindow) -> Result<(), String> {
-    let Some(monitor) = quickbar_monitor(window) else {
+fn position_quickbar(
+    window: &WebviewWindow,
+    foreground_store: &ForegroundWindowStore,
+) -> Result<(), String> {
+    let settings = window.state::<AppState>().settings.get();
+    let placement = settings.quickbar_placement;
+    let anchor = quickbar_anchor(window, placement, foreground_store);
+    let Some(monitor) = quickbar_monitor(window, anchor, foreground_store) else {
         log::warn!("No monitor detected for QuickBar positioning");
         return Ok(());
     };
+    let area = monitor_work_area(&monitor);
 
-    let work_area = monitor.work_area();
-    let work_width = work_area.size.width as i32;
-    let work_height = work_area.size.height as i32;
-
-    // Size and centering both derive from the *target* monitor's scale (not the
+    // Size and position both derive from the *target* monitor's scale (not the
     // window's current one), so mixed-DPI / primary-monitor fallback stays
     // correct. We compute a logical size, then set the exact physical box.
     let scale = monitor.scale_factor().max(0.1);
-    let (logical_w, logical_h) =
-        quickbar_logical_size(work_width as f64 / scale, work_height as f64 / scale);
+    let (logical_w, logical_h) = quickbar_logical_size(
+        area.width as f64 / scale,
+        area.height as f64 / scale,
+        settings.quickbar_width as f64,
+        settings.quickbar_height as f64,
+    );
 
-    let width = (logical_w * scale).round() as i32;
-    let height = (logical_h * scale).round() as i32;
+    let width = ((logical_w * scale).round() as i32).max(1);
+    let height = ((logical_h * scale).round() as i32).max(1);
 
     window
-        .set_size(PhysicalSize::new(width.max(1) as u32, height.max(1) as u32))
+        .set_size(PhysicalSize::new(width as u32, height as u32))
         .map_err(to_string)?;
 
-    let x = work_area.position.x + ((work_width - width) / 2).max(0);
-    let y = work_area.position.y + ((work_height - height) / 3).max(0);
-
+    let (x, y) = quickbar_position(
+        placement,
+        area,
+        (width, height),
+        scale,
+        anchor,
+        settings.quickbar_last_position,
+    );
     window
         .set_position(PhysicalPosition::new(x, y))
         .map_err(to_string)
 }
 
-/// Pick the monitor the user is actually working on: the one under the cursor,
-/// falling back to the window's current monitor, then the primary monitor.
-/// `current_monitor()` alone tracks where the *window* last sat, not the active
-/// screen, so on multi-monitor setups it would keep misplacing the QuickBar.
-fn quickbar_monitor(window: &WebviewWindow) -> Option<tauri::Monitor> {
-    if let Ok(cursor) = window.cursor_position() {
-        if let Ok(Some(monitor)) = window.monitor_from_point(cursor.x, cursor.y) {
-            return Some(monitor);
+/// The point an anchored placement opens at: the caret of the recorded
+/// foreground window when asked for and exposed, otherwise the cursor.
+fn quickbar_anchor(
+    window: &WebviewWindow,
+    placement: QuickBarPlacement,
+    foreground_store: &ForegroundWindowStore,
+) -> Option<(i32, i32)> {
+    if !placement.is_anchored() {
+        return None;
+    }
+    if placement == QuickBarPlacement::Caret {
+        if let Some(caret) = caret_position(foreground_store) {
+            return Some(caret);
         }
     }
+    let cursor = window.cursor_position().ok()?;
+    Some((cursor.x.round() as i32, cursor.y.round() as i32))
+}
+
+/// Pick the monitor the user is actually working on: the one holding the
+/// anchor point, else the one under the cursor, else the one holding the
+/// window the QuickBar was opened over, falling back to the window's current
+/// monitor, then the primary monitor. `current_monitor()` alone tracks where
+/// the *window* last sat, not the active screen, so on multi-monitor setups it
+/// would keep misplacing the QuickBar.
+fn quickbar_monitor(
+    window: &WebviewWindow,
+    anchor: Option<(i32, i32)>,
+    foreground_store: &ForegroundWindowStore,
+) -> Option<tauri::Monitor> {
+    let monitor_at = |(x, y): (f64, f64)| window.monitor_from_point(x, y).ok().flatten();
+
+    anchor
+        .and_then(|(x, y)| monitor_at((x as f64, y as f64)))
+        .or_else(|| {
+            let cursor = window.cursor_position().ok()?;
+            monitor_at((cursor.x, cursor.y))
+        })
+        .or_else(|| foreground_window_center(foreground_store).and_then(monitor_at))
+        .or_else(|| window.current_monitor().ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
 
-    window
-        .current_monitor()
+fn monitor_work_area(monitor: &tauri::Monitor) -> WorkArea {
+    let work_area = monitor.work_area();
+    WorkArea {
+        x: work_area.position.x,
+        y: work_area.position.y,
+        width: work_area.size.width as i32,
+        height: work_area.size.height as i32,
+    }
+}
+
+/// Saves where the QuickBar is as it closes, for `LastPosition`. Written
+/// under the settings write lock, like `pause` does, and only when it moved.
+fn remember_quickbar_position(window: &WebviewWindow) {
+    let state = window.state::<AppState>();
+    if state.settings.get().quickbar_placement != QuickBarPlacement::LastPosition {
+        return;
+    }
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let Some(monitor) = window.current_monitor().ok().flatten() else {
+        return;
+    };
+    let offset = quickbar_offset(
+        monitor_work_area(&monitor),
+        (position.x, position.y),
+        monitor.scale_factor().max(0.1),
+    );
+
+    let _settings_write_guard = crate::safe_lock(&state.settings_write_lock);
+    let mut settings = state.settings.get();
+    if settings.quickbar_last_position == Some(offset) {
+        return;
+    }
+    settings.quickbar_last_position = Some(offset);
+    state.settings.set(settings);
+    if let Err(e) = state.settings.save(window.app_handle()) {
+        log::error!("Failed to persist the QuickBar position: {}", e);
+    }
+}
+
+/// Screen position of the text caret in the recorded foreground window, just
+/// below its baseline. Only Win32 exposes another app's caret.
+#[cfg(windows)]
+fn caret_position(store: &ForegroundWindowStore) -> Option<(i32, i32)> {
+    use std::ffi::c_void;
+    use windows::Win32::Foundation::{HWND, POINT};
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetGUIThreadInfo, GetWindowThreadProcessId, GUITHREADINFO,
+    };
+
+    let target = recorded_foreground_window(store)?;
+    let hwnd = HWND(target.raw() as *mut c_void);
+    unsafe {
+        let thread = GetWindowThreadProcessId(hwnd, None);
+        if thread == 0 {
+            return None;
+        }
+        let mut info = GUITHREADINFO {
+            cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+            ..Default::default()
+        };
+        GetGUIThreadInfo(thread, &mut info).ok()?;
+        if info.hwndCaret.is_invalid() {
+            return None;
+        }
+        let mut point = POINT {
+            x: info.rcCaret.left,
+            y: info.rcCaret.bottom,
+        };
+        if !ClientToScreen(info.hwndCaret, &mut point).as_bool() {
+            return None;
+        }
+        Some((point.x, point.y))
+    }
+}
+
+#[cfg(not(windows))]
+fn caret_position(_store: &ForegroundWindowStore) -> Option<(i32, i32)> {
+    None
+}
+
+/// Center of the recorded foreground window, for picking its monitor when
+/// the cursor position is unavailable.
+#[cfg(windows)]
+fn foreground_window_center(store: &ForegroundWindowStore) -> Option<(f64, f64)> {
+    use std::ffi::c_void;
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+    let target = recorded_foreground_window(store)?;
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(HWND(target.raw() as *mut c_void), &mut rect) }.ok()?;
+    Some((
+        (rect.left + rect.right) as f64 / 2.0,
+        (rect.top + rect.bottom) as f64 / 2.0,
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn foreground_window_center(store: &ForegroundWindowStore) -> Option<(f64, f64)> {
+    let target = crate::safe_lock(store).clone()?;
+    crate::x11_focus::window_center(target.raw() as u32)
+        .map_err(|e| log::debug!("Could not read the X11 window geometry: {}", e))
         .ok()
-        .flatten()
-        .or_else(|| window.primary_monitor().ok().flatten())
 }
 
-/// Compute the QuickBar logical size from a (logical) work-area: scale to a
-/// fraction of the screen, capped at a comfortable max. The fraction guarantees
-/// the result never exceeds the work area, so no floor is needed.
-fn quickbar_logical_size(work_width: f64, work_height: f64) -> (f64, f64) {
-    let width = (work_width * 0.92).min(QUICKBAR_MAX_WIDTH);
-    let height = (work_height * 0.7).min(QUICKBAR_MAX_HEIGHT);
-    (width, height)
+#[cfg(target_os = "macos")]
+fn foreground_window_center(_store: &ForegroundWindowStore) -> Option<(f64, f64)> {
+    None
 }
 
 #[cfg(target_os = "macos")]
@@ -382,10 +578,47 @@ fn remember_foreground_window(store: &ForegroundWindowStore, quickbar: &WebviewW
 
     *crate::safe_lock(store) = Some(ForegroundWindow {
         raw: foreground.0 as isize,
+        app_name: window_process_name(foreground),
     });
     log::debug!("Recorded Windows foreground window before QuickBar show");
 }
 
+/// File name, without extension, of the executable that owns `hwnd`.
+#[cfg(windows)]
+fn window_process_name(hwnd: windows::Win32::Foundation::HWND) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    if pid == 0 {
+        return None;
+    }
+
+    let mut buffer = [0u16; 1024];
+    let mut len = buffer.len() as u32;
+    let path = unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let queried = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        queried.ok()?;
+        String::from_utf16_lossy(&buffer[..len as usize])
+    };
+    std::path::Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
 #[cfg(target_os = "macos")]
 fn remember_foreground_window(store: &ForegroundWindowStore, _quickbar: &WebviewWindow) {
     use objc2_app_kit::NSWorkspace;
@@ -402,12 +635,47 @@ fn remember_foreground_window(store: &ForegroundWindowStore, _quickbar: &Webview
         return;
     }
 
-    *crate::safe_lock(store) = Some(ForegroundWindow { raw: pid as isize });
+    *crate::safe_lock(store) = Some(ForegroundWindow {
+        raw: pid as isize,
+        app_name: front.localizedName().map(|name| name.to_string()),
+    });
     log::debug!("Recorded frontmost macOS app pid {pid} before QuickBar show");
 }
 
-#[cfg(all(not(windows), not(target_os = "macos")))]
-fn remember_foreground_window(_store: &ForegroundWindowStore, _quickbar: &WebviewWindow) {}
+#[cfg(target_os = "linux")]
+fn remember_foreground_window(store: &ForegroundWindowStore, _quickbar: &WebviewWindow) {
+    if crate::wayland::session_available() {
+        return;
+    }
+
+    let active = match crate::x11_focus::active_window() {
+        Ok(Some(active)) => active,
+        Ok(None) => {
+            log::warn!("No active X11 window to record");
+            *crate::safe_lock(store) = None;
+            return;
+        }
+        Err(e) => {
+            log::warn!("Could not read the active X11 window: {}", e);
+            *crate::safe_lock(store) = None;
+            return;
+        }
+    };
+    if active.pid == Some(std::process::id()) {
+        log::debug!("ClipMan is already the active X11 window; keeping previous paste target");
+        return;
+    }
+
+    log::debug!(
+        "Recorded active X11 window {:#x} ({:?}) before QuickBar show",
+        active.id,
+        active.class
+    );
+    *crate::safe_lock(store) = Some(ForegroundWindow {
+        raw: active.id as isize,
+        app_name: active.class,
+    });
+}
 
 #[cfg(windows)]
 fn restore_foreground_window(target: ForegroundWindow) -> Result<(), String> {
@@ -504,10 +772,11 @@ fn to_string(error: impl std::fmt::Display) -> String {
 #[cfg(test)]
 mod size_tests {
     use super::*;
+    use crate::placement::{QUICKBAR_MAX_HEIGHT, QUICKBAR_MAX_WIDTH};
 
     #[test]
     fn caps_at_max_on_large_screens() {
-        let (w, h) = quickbar_logical_size(2560.0, 1440.0);
+        let (w, h) = quickbar_logical_size(2560.0, 1440.0, QUICKBAR_MAX_WIDTH, QUICKBAR_MAX_HEIGHT);
         assert_eq!(w, QUICKBAR_MAX_WIDTH);
         assert_eq!(h, QUICKBAR_MAX_HEIGHT);
     }
@@ -516,7 +785,7 @@ mod size_tests {
     fn never_exceeds_work_area_on_small_screens() {
         // A tiny work area must not produce a window larger than itself.
         let (work_w, work_h) = (390.0, 320.0);
-        let (w, h) = quickbar_logical_size(work_w, work_h);
+        let (w, h) = quickbar_logical_size(work_w, work_h, QUICKBAR_MAX_WIDTH, QUICKBAR_MAX_HEIGHT);
         assert!(w <= work_w && h <= work_h);
         assert!(w <= QUICKBAR_MAX_WIDTH && h <= QUICKBAR_MAX_HEIGHT);
     }
@@ -0,0 +1,284 @@
+//! The X11 window the QuickBar was opened over, and giving it focus back
+//! before a paste, so Ctrl+V does not depend on where the window manager
+//! happens to move focus when the QuickBar hides.
+//!
+//! The active window is read from the EWMH `_NET_ACTIVE_WINDOW` root
+//! property and reactivated by asking the window manager through a
+//! `_NET_ACTIVE_WINDOW` client message. Without an EWMH window manager (bare
+//! Xvfb, some tiling setups) the input focus is read and set directly.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    AtomEnum, ClientMessageEvent, ConfigureWindowAux, ConnectionExt, EventMask, InputFocus,
+    StackMode, Window,
+};
+use x11rb::rust_connection::RustConnection;
+
+/// How long the window manager gets to act on an activation request.
+const ACTIVATION_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// `_NET_ACTIVE_WINDOW` source indication for pagers and taskbars, which
+/// window managers honor without the focus-stealing checks they apply to
+/// applications.
+const SOURCE_PAGER: u32 = 2;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_SUPPORTED,
+        _NET_WM_PID,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveWindow {
+    pub id: Window,
+    /// `_NET_WM_PID`, when the client sets it.
+    pub pid: Option<u32>,
+    /// The class half of `WM_CLASS`, e.g. `kitty` or `Gnome-terminal`.
+    pub class: Option<String>,
+}
+
+struct Session {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl Session {
+    fn connect() -> Result<Self, String> {
+        let (conn, screen) =
+            x11rb::connect(None).map_err(|e| format!("Failed to connect to X11: {}", e))?;
+        let root = conn.setup().roots[screen].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("Failed to intern X11 atoms: {}", e))?;
+        Ok(Self { conn, root, atoms })
+    }
+
+    fn ewmh_active_window_supported(&self) -> bool {
+        self.property32(
+            self.root,
+            self.atoms._NET_SUPPORTED,
+            AtomEnum::ATOM,
+            u32::MAX,
+        )
+        .is_some_and(|supported| supported.contains(&self.atoms._NET_ACTIVE_WINDOW))
+    }
+
+    fn active_window_id(&self) -> Result<Option<Window>, String> {
+        if self.ewmh_active_window_supported() {
+            let active = self
+                .property32(
+                    self.root,
+                    self.atoms._NET_ACTIVE_WINDOW,
+                    AtomEnum::WINDOW,
+                    1,
+                )
+                .and_then(|values| values.first().copied())
+                .filter(|&window| window != x11rb::NONE);
+            return Ok(active);
+        }
+
+        let focus = self
+            .conn
+            .get_input_focus()
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("Failed to read the X11 input focus: {}", e))?
+            .focus;
+        // `PointerRoot` (1) and `None` (0) are not windows.
+        Ok(Some(focus).filter(|&window| window > 1 && window != self.root))
+    }
+
+    fn property32(
+        &self,
+        window: Window,
+        property: u32,
+        kind: AtomEnum,
+        length: u32,
+    ) -> Option<Vec<u32>> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, kind, 0, length)
+            .ok()?
+            .reply()
+            .ok()?;
+        Some(reply.value32()?.collect())
+    }
+
+    fn wm_class(&self, window: Window) -> Option<String> {
+        let reply = self
+            .conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+            .ok()?
+            .reply()
+            .ok()?;
+        wm_class_name(&reply.value)
+    }
+}
+
+/// The window that has focus right now, if any.
+pub fn active_window() -> Result<Option<ActiveWindow>, String> {
+    let session = Session::connect()?;
+    let Some(id) = session.active_window_id()? else {
+        return Ok(None);
+    };
+    let pid = session
+        .property32(id, session.atoms._NET_WM_PID, AtomEnum::CARDINAL, 1)
+        .and_then(|values| values.first().copied());
+    Ok(Some(ActiveWindow {
+        id,
+        pid,
+        class: session.wm_class(id),
+    }))
+}
+
+/// Gives `window` focus and waits, briefly, for the window manager to do so.
+pub fn activate(window: Window) -> Result<(), String> {
+    let session = Session::connect()?;
+    let conn = &session.conn;
+    conn.get_window_attributes(window)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|_| format!("X11 window {:#x} no longer exists", window))?;
+
+    if session.ewmh_active_window_supported() {
+        let event = ClientMessageEvent::new(
+            32,
+            window,
+            session.atoms._NET_ACTIVE_WINDOW,
+            [SOURCE_PAGER, x11rb::CURRENT_TIME, 0, 0, 0],
+        );
+        conn.send_event(
+            false,
+            session.root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        )
+        .map_err(|e| format!("Failed to ask the window manager to activate: {}", e))?;
+    } else {
+        conn.configure_window(
+            window,
+            &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
+        )
+        .and_then(|_| conn.set_input_focus(InputFocus::PARENT, window, x11rb::CURRENT_TIME))
+        .map_err(|e| format!("Failed to focus X11 window {:#x}: {}", window, e))?;
+    }
+    conn.flush().map_err(|e| e.to_string())?;
+
+    let deadline = Instant::now() + ACTIVATION_TIMEOUT;
+    loop {
+        if session.active_window_id()? == Some(window) {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "X11 window {:#x} was not activated within {:?}",
+                window, ACTIVATION_TIMEOUT
+            ));
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Center of `window` in root (screen) coordinates.
+pub fn window_center(window: Window) -> Result<(f64, f64), String> {
+    let session = Session::connect()?;
+    let conn = &session.conn;
+    let geometry = conn
+        .get_geometry(window)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|_| format!("X11 window {:#x} no longer exists", window))?;
+    let origin = conn
+        .translate_coordinates(window, session.root, 0, 0)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| format!("Failed to locate X11 window {:#x}: {}", window, e))?;
+    Ok((
+        origin.dst_x as f64 + geometry.width as f64 / 2.0,
+        origin.dst_y as f64 + geometry.height as f64 / 2.0,
+    ))
+}
+
+/// `WM_CLASS` holds two NUL-terminated strings, instance then class.
+fn wm_class_name(value: &[u8]) -> Option<String> {
+    let class = value.split(|&byte| byte == 0).nth(1)?;
+    (!class.is_empty()).then(|| String::from_utf8_lossy(class).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x11rb::protocol::xproto::{CreateWindowAux, PropMode, WindowClass};
+    use x11rb::wrapper::ConnectionExt as _;
+    use x11rb::COPY_DEPTH_FROM_PARENT;
+
+    #[test]
+    fn wm_class_name_is_the_class_half() {
+        assert_eq!(
+            Some("Gnome-terminal".to_string()),
+            wm_class_name(b"gnome-terminal-server\0Gnome-terminal\0")
+        );
+        assert_eq!(None, wm_class_name(b"instance-only\0"));
+        assert_eq!(None, wm_class_name(b""));
+    }
+
+    #[test]
+    #[ignore = "needs an X server without a window manager; run under xvfb-run"]
+    fn focus_is_recorded_and_restored() {
+        let (conn, screen) = x11rb::connect(None).unwrap();
+        let root = conn.setup().roots[screen].root;
+        let create = |class: &[u8]| {
+            let window = conn.generate_id().unwrap();
+            conn.create_window(
+                COPY_DEPTH_FROM_PARENT,
+                window,
+                root,
+                0,
+                0,
+                10,
+                10,
+                0,
+                WindowClass::INPUT_OUTPUT,
+                0,
+                &CreateWindowAux::new(),
+            )
+            .unwrap();
+            conn.change_property8(
+                PropMode::REPLACE,
+                window,
+                AtomEnum::WM_CLASS,
+                AtomEnum::STRING,
+                class,
+            )
+            .unwrap();
+            conn.map_window(window).unwrap();
+            window
+        };
+        let target = create(b"xterm\0XTerm\0");
+        let other = create(b"other\0Other\0");
+        conn.sync().unwrap();
+
+        conn.set_input_focus(InputFocus::PARENT, target, x11rb::CURRENT_TIME)
+            .unwrap();
+        conn.sync().unwrap();
+        let recorded = active_window().unwrap().unwrap();
+        assert_eq!(target, recorded.id);
+        assert_eq!(Some("XTerm"), recorded.class.as_deref());
+
+        conn.set_input_focus(InputFocus::PARENT, other, x11rb::CURRENT_TIME)
+            .unwrap();
+        conn.sync().unwrap();
+        activate(recorded.id).unwrap();
+        assert_eq!(
+            target,
+            conn.get_input_focus().unwrap().reply().unwrap().focus
+        );
+    }
+}
@@ -53,6 +53,18 @@ export interface Translations {
 
   // Search
   searchPlaceholder: string;
+  kindFilter: string;
+  kindAll: string;
+  kindUrl: string;
+  kindEmail: string;
+  kindPhone: string;
+  kindColor: string;
+  kindPath: string;
+  kindJson: string;
+  kindCode: string;
+  kindNumber: string;
+  kindDate: string;
+  kindPlain: string;
 
   // Preview pane
   selectToPreview: string;
@@ -78,6 +90,90 @@ export interface Translations {
   advancedManualInput: string;
   pinnedShortcut: string;
   pinnedShortcutDesc: string;
+  actionShortcuts: string;
+  actionShortcutsDesc: string;
+  actionShortcutKeys: string;
+  actionPasteRecent: string;
+  actionPastePinnedSlot: string;
+  actionPastePlainText: string;
+  actionTogglePause: string;
+  actionOpenSearch: string;
+  actionOpenGroup: string;
+  actionPasteStackNext: string;
+  actionRecentIndex: string;
+  actionPinnedSlot: string;
+  addPinnedSlotShortcuts: string;
+  automationApi: string;
+  automationApiDesc: string;
+  apiPort: string;
+  apiToken: string;
+  apiTokenDesc: string;
+  regenerateApiToken: string;
+  statistics: string;
+  statisticsDesc: string;
+  statisticsCaptured: string;
+  statisticsStored: string;
+  statisticsOversizedSkipped: string;
+  statisticsSecretsSkipped: string;
+  statisticsPerDay: string;
+  statisticsPerApp: string;
+  statisticsUnknownApp: string;
+  statisticsContentTypes: string;
+  statisticsTopPasted: string;
+  statisticsStorage: string;
+  statisticsThumbnails: string;
+  statisticsOther: string;
+  statisticsDatabase: string;
+  plugins: string;
+  pluginsDesc: string;
+  reloadPlugins: string;
+  reloadPluginsFailed: string;
+  openPluginsFolder: string;
+  noPlugins: string;
+  actionSearchQuery: string;
+  actionGroupName: string;
+  addActionShortcut: string;
+  removeActionShortcut: string;
+  captureRules: string;
+  captureRulesDesc: string;
+  addCaptureRule: string;
+  noCaptureRules: string;
+  captureRuleEnabled: string;
+  captureRuleName: string;
+  moveCaptureRuleUp: string;
+  moveCaptureRuleDown: string;
+  removeCaptureRule: string;
+  captureRuleSourceApp: string;
+  captureRuleContentType: string;
+  anyContentType: string;
+  captureRuleContentPattern: string;
+  captureRuleMinBytes: string;
+  captureRuleMaxBytes: string;
+  captureRuleTimeWindow: string;
+  captureRuleTimeWindowStart: string;
+  captureRuleTimeWindowEnd: string;
+  captureRuleAction: string;
+  ruleActionSkip: string;
+  ruleActionPin: string;
+  ruleActionLabel: string;
+  ruleActionGroup: string;
+  ruleActionSensitive: string;
+  captureRuleLabel: string;
+  captureHooks: string;
+  captureHooksDesc: string;
+  addCaptureHook: string;
+  noCaptureHooks: string;
+  captureHookEnabled: string;
+  captureHookName: string;
+  captureHookCommand: string;
+  moveCaptureHookUp: string;
+  moveCaptureHookDown: string;
+  removeCaptureHook: string;
+  captureHookTransform: string;
+  captureHookTransformDesc: string;
+  captureHookTimeout: string;
+  maxConcurrentHooks: string;
+  maxConcurrentHooksDesc: string;
   disableHotkeyFailed: string;
 
   // Clipboard settings
@@ -85,6 +181,35 @@ export interface Translations {
   maxHistoryItemsDesc: string;
   autoPaste: string;
   autoPasteDesc: string;
+  restoreClipboardAfterPaste: string;
+  restoreClipboardAfterPasteDesc: string;
+  typePasteCharDelay: string;
+  typePasteCharDelayDesc: string;
+  typePasteMaxChars: string;
+  typePasteMaxCharsDesc: string;
+  typePasteCancelShortcut: string;
+  typePasteCancelShortcutDesc: string;
+  mergeFormat: string;
+  mergeFormatDesc: string;
+  mergeFormatPlain: string;
+  mergeFormatNumberedList: string;
+  mergeFormatMarkdownBullets: string;
+  mergeFormatCsvRow: string;
+  mergeFormatCsvColumn: string;
+  mergeFormatJsonArray: string;
+  mergeFormatTemplate: string;
+  mergeTemplateDesc: string;
+  mergeImages: string;
+  mergeImagesDesc: string;
+  fullFidelityCapture: string;
+  fullFidelityCaptureDesc: string;
+  fullFidelityCaptureUnsupported: string;
+  pasteKeyProfiles: string;
+  pasteKeyProfilesDesc: string;
+  pasteKeyProfileApp: string;
+  pasteKeyProfileKeys: string;
+  addPasteKeyProfile: string;
+  removePasteKeyProfile: string;
   ignoreConcealed: string;
   ignoreConcealedDesc: string;
 
@@ -95,6 +220,21 @@ export interface Translations {
   themePink: string;
   themeSystem: string;
   language: string;
+  quickbarPlacement: string;
+  quickbarPlacementDesc: string;
+  quickbarPlacementCenter: string;
+  quickbarPlacementCursor: string;
+  quickbarPlacementCaret: string;
+  quickbarPlacementLastPosition: string;
+  quickbarPlacementCorner: string;
+  screenCornerTopLeft: string;
+  screenCornerTopRight: string;
+  screenCornerBottomLeft: string;
+  screenCornerBottomRight: string;
+  quickbarSize: string;
+  quickbarSizeDesc: string;
+  quickbarWidth: string;
+  quickbarHeight: string;
 
   // Tray settings
   trayTextLength: string;
@@ -103,6 +243,8 @@ export interface Translations {
   maxPinnedInTrayDesc: string;
   maxRecentInTray: string;
   maxRecentInTrayDesc: string;
+  persistTimedPause: string;
+  persistTimedPauseDesc: string;
 
   // Storage settings
   dataLocationDesc: string;
@@ -143,6 +285,10 @@ export interface Translations {
   saveSettingsFailed: string;
   copyFailed: string;
   pasteFailed: string;
+  pasteUnsupported: string;
+  typeFailed: string;
+  typingCancelled: string;
+  typingUnsupported: string;
   clearFailed: string;
 
   // Loading
@@ -176,11 +322,13 @@ export interface Translations {
   close: string;
   reorder: string;
   pastePlain: string;
+  typeOut: string;
 
   // Files / rich text
   files: string;
   fileCount: string;
   richTextBadge: string;
+  redactedBadge: string;
 
   // Ignored apps (SPEC-4 §3)
   ignoredApps: string;
@@ -189,21 +337,67 @@ export interface Translations {
   addIgnoredApp: string;
   removeIgnoredApp: string;
   noIgnoredApps: string;
+  concealedAsSensitive: string;
+  concealedAsSensitiveDesc: string;
+  ignoredAppsAsSensitive: string;
+  ignoredAppsAsSensitiveDesc: string;
+  sensitiveClipTtl: string;
+  sensitiveClipTtlDesc: string;
 
   // Secret detection setting (SPEC-4 §2 UI)
   skipSecrets: string;
   skipSecretsDesc: string;
+  secretAction: string;
+  secretActionDesc: string;
+  secretActionSkip: string;
+  secretActionRedact: string;
+  secretActionExpire: string;
+  customSecretPatterns: string;
+  customSecretPatternsDesc: string;
+  secretPatternName: string;
+  secretPatternRegex: string;
+  addSecretPattern: string;
+  removeSecretPattern: string;
+  entropyDetection: string;
+  entropyDetectionDesc: string;
+  entropyThreshold: string;
+  entropyThresholdDesc: string;
+  secretSkipped: string;
 
   // Capture size limits (SPEC-3 §5 UI)
   maxTextBytes: string;
   maxTextBytesDesc: string;
   maxImageDimension: string;
   maxImageDimensionDesc: string;
+  collapseSimilarImages: string;
+  collapseSimilarImagesDesc: string;
+  similarImageThreshold: string;
+  similarImageThresholdDesc: string;
+  textNormalizers: string;
+  textNormalizersDesc: string;
+  stripAnsiEscapes: string;
+  stripAnsiEscapesDesc: string;
+  stripZeroWidth: string;
+  stripZeroWidthDesc: string;
+  normalizeLineEndings: string;
+  normalizeLineEndingsDesc: string;
+  stripTrackingParams: string;
+  stripTrackingParamsDesc: string;
+  trimWhitespace: string;
+  trimWhitespaceDesc: string;
 
   // Multi-select merge paste
   selectedCount: string;
   mergePasteHint: string;
   clearSelection: string;
+
+  // Paste stack and group view
+  addToPasteStack: string;
+  pasteStackQueued: string;
+  clearPasteStack: string;
+  clearGroupFilter: string;
+  similarImages: string;
+  clearSimilarImages: string;
 }
 
 const zh: Translations = {
@@ -256,6 +450,18 @@ const zh: Translations = {
 
   // Search
   searchPlaceholder: '搜索剪切板内容...',
+  kindFilter: '按类型筛选',
+  kindAll: '全部类型',
+  kindUrl: '链接',
+  kindEmail: '邮箱',
+  kindPhone: '电话',
+  kindColor: '颜色',
+  kindPath: '路径',
+  kindJson: 'JSON',
+  kindCode: '代码',
+  kindNumber: '数字',
+  kindDate: '日期',
+  kindPlain: '纯文本',
 
   // Preview pane
   selectToPreview: '选择一项查看完整内容',
@@ -281,6 +487,93 @@ const zh: Translations = {
   advancedManualInput: '高级：手动输入...',
   pinnedShortcut: '常用快捷键',
   pinnedShortcutDesc: '可选。设置后直接打开常用面板；留空则不绑定。',
+  actionShortcuts: '动作快捷键',
+  actionShortcutsDesc:
+    '不打开 QuickBar 直接执行的全局快捷键。各快捷键不能与其他快捷键重复。',
+  actionShortcutKeys: '按键，如 Alt+1',
+  actionPasteRecent: '粘贴第 N 条历史',
+  actionPastePinnedSlot: '粘贴常用第 N 项',
+  actionPastePlainText: '以纯文本粘贴最新一条',
+  actionTogglePause: '暂停 / 恢复记录',
+  actionOpenSearch: '打开搜索',
+  actionOpenGroup: '打开分组',
+  actionPasteStackNext: '粘贴粘贴栈中的下一条',
+  actionRecentIndex: '序号，从 1 开始',
+  actionPinnedSlot: '常用序号，从 1 开始',
+  addPinnedSlotShortcuts: '用 {keys}1–9 粘贴常用 1–9 项',
+  automationApi: '自动化 API',
+  automationApiDesc: '在 127.0.0.1 上提供 HTTP/JSON 接口，供脚本读取和写入剪贴板历史',
+  apiPort: '端口',
+  apiToken: '访问令牌',
+  apiTokenDesc: '每个请求都需携带 Authorization: Bearer <令牌> 请求头',
+  regenerateApiToken: '重新生成',
+  statistics: '统计',
+  statisticsDesc: '最近 30 天的记录，可据此调整历史条数和大小限制',
+  statisticsCaptured: '已采集',
+  statisticsStored: '已保存 / 上限',
+  statisticsOversizedSkipped: '因超出大小限制而跳过',
+  statisticsSecretsSkipped: '因含敏感信息而跳过',
+  statisticsPerDay: '每日采集',
+  statisticsPerApp: '来源应用',
+  statisticsUnknownApp: '未知',
+  statisticsContentTypes: '内容类型',
+  statisticsTopPasted: '最常粘贴',
+  statisticsStorage: '存储占用',
+  statisticsThumbnails: '缩略图',
+  statisticsOther: '其他格式',
+  statisticsDatabase: '数据库文件',
+  plugins: '插件',
+  pluginsDesc: '从数据目录的 plugins 文件夹加载 WebAssembly 插件，在采集和粘贴时处理文本',
+  reloadPlugins: '重新加载',
+  reloadPluginsFailed: '重新加载插件失败',
+  openPluginsFolder: '打开插件文件夹',
+  noPlugins: '尚未安装插件。保存设置后，将插件放入插件文件夹并重新加载。',
+  actionSearchQuery: '预填搜索词（可选）',
+  actionGroupName: '分组名称',
+  addActionShortcut: '添加',
+  removeActionShortcut: '移除',
+  captureRules: '采集规则',
+  captureRulesDesc:
+    '每次复制时按从上到下的顺序检查。条件留空表示不限；匹配的规则依次生效，“跳过”会结束检查，标签和分组取第一条匹配的规则。',
+  addCaptureRule: '添加规则',
+  noCaptureRules: '暂无采集规则',
+  captureRuleEnabled: '启用规则',
+  captureRuleName: '规则名称',
+  moveCaptureRuleUp: '上移',
+  moveCaptureRuleDown: '下移',
+  removeCaptureRule: '移除规则',
+  captureRuleSourceApp: '来源应用（任意）',
+  captureRuleContentType: '内容类型',
+  anyContentType: '任意类型',
+  captureRuleContentPattern: '内容正则（任意）',
+  captureRuleMinBytes: '最小字节数',
+  captureRuleMaxBytes: '最大字节数',
+  captureRuleTimeWindow: '时间段',
+  captureRuleTimeWindowStart: '开始时间',
+  captureRuleTimeWindowEnd: '结束时间',
+  captureRuleAction: '动作',
+  ruleActionSkip: '跳过',
+  ruleActionPin: '固定',
+  ruleActionLabel: '添加标签',
+  ruleActionGroup: '加入分组',
+  ruleActionSensitive: '作为敏感记录',
+  captureRuleLabel: '标签',
+  captureHooks: '采集钩子',
+  captureHooksDesc:
+    '每条新记录保存后按顺序运行的命令：内容通过标准输入传入，元数据在 CLIPMAN_* 环境变量中。退出码 3 会删除该记录。',
+  addCaptureHook: '添加钩子',
+  noCaptureHooks: '暂无采集钩子',
+  captureHookEnabled: '启用钩子',
+  captureHookName: '钩子名称',
+  captureHookCommand: '命令，如 tee -a ~/clips.log',
+  moveCaptureHookUp: '上移',
+  moveCaptureHookDown: '下移',
+  removeCaptureHook: '移除钩子',
+  captureHookTransform: '替换文本',
+  captureHookTransformDesc: '命令输出非空时，用它替换文本记录的内容。',
+  captureHookTimeout: '超时（秒）',
+  maxConcurrentHooks: '同时运行的钩子数',
+  maxConcurrentHooksDesc: '最多同时运行多少个钩子命令（1–16）。',
   disableHotkeyFailed: '无法禁用快捷键',
 
   // Clipboard settings
@@ -288,6 +581,37 @@ const zh: Translations = {
   maxHistoryItemsDesc: '保留的最大历史记录数量',
   autoPaste: '自动粘贴',
   autoPasteDesc: '从 QuickBar 取用时自动粘回当前应用；关闭后只复制。',
+  restoreClipboardAfterPaste: '粘贴后恢复剪贴板',
+  restoreClipboardAfterPasteDesc: '自动粘贴完成后，把剪贴板恢复为粘贴前的内容。',
+  typePasteCharDelay: '逐字输入间隔（毫秒）',
+  typePasteCharDelayDesc: '以逐字输入方式粘贴时，每个按键之间的等待时间（0–1000）。',
+  typePasteMaxChars: '逐字输入最大字数',
+  typePasteMaxCharsDesc: '超过此字数的内容不会逐字输入。',
+  typePasteCancelShortcut: '停止逐字输入',
+  typePasteCancelShortcutDesc: '逐字输入进行中可按此快捷键中止，不能与其他快捷键重复。',
+  mergeFormat: '合并粘贴格式',
+  mergeFormatDesc: '多选粘贴时，各条内容的排列方式。',
+  mergeFormatPlain: '逐行拼接',
+  mergeFormatNumberedList: '编号列表',
+  mergeFormatMarkdownBullets: 'Markdown 列表',
+  mergeFormatCsvRow: 'CSV 单行',
+  mergeFormatCsvColumn: 'CSV 单列',
+  mergeFormatJsonArray: 'JSON 数组',
+  mergeFormatTemplate: '自定义模板',
+  mergeTemplateDesc: '每条内容套用一次模板，可用 {index}、{content}、{source_app}、{timestamp}。',
+  mergeImages: '合并图片',
+  mergeImagesDesc: '只选中图片时，把它们从上到下拼成一张图片。',
+  fullFidelityCapture: '保留全部剪贴板格式',
+  fullFidelityCaptureDesc:
+    '随每条记录保存复制时提供的所有格式，粘贴时全部还原。会占用更多存储空间。',
+  fullFidelityCaptureUnsupported: '当前系统不支持保留全部格式，仅支持 macOS 和 Windows。',
+  pasteKeyProfiles: '按应用设置粘贴按键',
+  pasteKeyProfilesDesc:
+    '终端等不用 Ctrl+V 粘贴的应用，改按这里的组合键。其他应用仍用 Ctrl+V（macOS 为 ⌘V）。',
+  pasteKeyProfileApp: '应用名称',
+  pasteKeyProfileKeys: '按键，如 Ctrl+Shift+V',
+  addPasteKeyProfile: '添加',
+  removePasteKeyProfile: '移除',
   ignoreConcealed: '忽略密码类剪贴板',
   ignoreConcealedDesc: '跳过系统标记为密码、临时或不可记录的剪贴板内容。',
 
@@ -298,6 +622,21 @@ const zh: Translations = {
   themePink: '粉色',
   themeSystem: '跟随系统',
   language: '语言',
+  quickbarPlacement: '快捷面板位置',
+  quickbarPlacementDesc: '始终在鼠标所在的显示器上打开。',
+  quickbarPlacementCenter: '屏幕中央',
+  quickbarPlacementCursor: '鼠标位置',
+  quickbarPlacementCaret: '文本光标（仅 Windows，其余跟随鼠标）',
+  quickbarPlacementLastPosition: '上次关闭的位置',
+  quickbarPlacementCorner: '屏幕角落',
+  screenCornerTopLeft: '左上角',
+  screenCornerTopRight: '右上角',
+  screenCornerBottomLeft: '左下角',
+  screenCornerBottomRight: '右下角',
+  quickbarSize: '快捷面板尺寸',
+  quickbarSizeDesc: '最大 820×600，屏幕较小时会自动缩小。可拖动搜索栏移动面板。',
+  quickbarWidth: '宽度',
+  quickbarHeight: '高度',
 
   // Tray settings
   trayTextLength: '托盘文本长度',
@@ -306,6 +645,8 @@ const zh: Translations = {
   maxPinnedInTrayDesc: '托盘菜单中显示的置顶项数量',
   maxRecentInTray: '托盘最近数量',
   maxRecentInTrayDesc: '托盘菜单中显示的最近项数量',
+  persistTimedPause: '重启后保留定时暂停',
+  persistTimedPauseDesc: '从托盘菜单暂停 5 分钟或 1 小时后，重启应用暂停仍然有效，直到到期。',
 
   // Storage settings
   dataLocationDesc: '选择 ClipMan 数据的存储位置',
@@ -346,6 +687,10 @@ const zh: Translations = {
   saveSettingsFailed: '保存失败',
   copyFailed: '复制失败',
   pasteFailed: '粘贴失败',
+  pasteUnsupported: '当前环境无法自动粘贴，已复制到剪贴板，请手动粘贴',
+  typeFailed: '逐字输入失败',
+  typingCancelled: '已取消逐字输入',
+  typingUnsupported: '当前环境无法模拟键盘输入',
   clearFailed: '清除失败',
 
   // Loading
@@ -380,11 +725,13 @@ const zh: Translations = {
   close: '关闭',
   reorder: '排序',
   pastePlain: '纯文本粘贴',
+  typeOut: '逐字输入',
 
   // Files / rich text
   files: '文件',
   fileCount: '{n} 个文件',
   richTextBadge: '富文本',
+  redactedBadge: '已隐去疑似密钥',
 
   // Ignored apps
   ignoredApps: '忽略的应用',
@@ -393,21 +740,72 @@ const zh: Translations = {
   addIgnoredApp: '添加',
   removeIgnoredApp: '移除',
   noIgnoredApps: '暂无忽略的应用',
+  concealedAsSensitive: '短暂保留隐藏内容',
+  concealedAsSensitiveDesc:
+    '密码管理器等标记为隐藏或临时的复制内容不再跳过，而是作为敏感记录保存，到期后删除。',
+  ignoredAppsAsSensitive: '短暂保留忽略应用的内容',
+  ignoredAppsAsSensitiveDesc: '来自忽略应用的复制内容不再跳过，而是作为敏感记录保存，到期后删除。',
+  sensitiveClipTtl: '敏感记录保留时长（秒）',
+  sensitiveClipTtlDesc: '敏感记录在此时长后删除，若仍在剪贴板中也会一并清除（5–86400）。',
 
   // Secret detection
   skipSecrets: '自动跳过疑似密钥',
   skipSecretsDesc: '检测到私钥、API 密钥、JWT 等高置信度敏感内容时跳过采集。',
+  secretAction: '检测到密钥时',
+  secretActionDesc: '跳过整条记录、保存脱敏后的文本，或将其作为敏感记录短暂保留后删除。',
+  secretActionSkip: '跳过',
+  secretActionRedact: '脱敏保存',
+  secretActionExpire: '到期删除',
+  customSecretPatterns: '自定义密钥规则',
+  customSecretPatternsDesc:
+    '与内置规则一起检测的正则表达式，如公司内部令牌格式；匹配时以名称报告。',
+  secretPatternName: '名称',
+  secretPatternRegex: '正则表达式',
+  addSecretPattern: '添加',
+  removeSecretPattern: '移除规则',
+  entropyDetection: '检测随机令牌',
+  entropyDetectionDesc: '把较长且看起来随机的字符串也视为密钥。可能误判哈希值等内容。',
+  entropyThreshold: '熵阈值',
+  entropyThresholdDesc: '每个字符达到多少位熵（3–6）才算随机；越低越敏感。',
+  secretSkipped: '已跳过疑似密钥：',
 
   // Capture size limits
   maxTextBytes: '文本大小上限 (MB)',
   maxTextBytesDesc: '超过此大小的文本或文件路径列表将不会被记录。',
   maxImageDimension: '图片尺寸上限 (px)',
   maxImageDimensionDesc: '超过此边长的图片会被等比缩小后再保存；设为 0 可关闭缩放。',
+  collapseSimilarImages: '合并相似图片',
+  collapseSimilarImagesDesc:
+    '复制的图片与已保存的图片看起来相同时（如重复截图），只刷新已有记录，不新增一条。',
+  similarImageThreshold: '相似度阈值',
+  similarImageThresholdDesc:
+    '两张图片的感知哈希最多相差多少位（0–16）仍算相同，用于合并和查找相似图片。',
+  textNormalizers: '文本清理',
+  textNormalizersDesc:
+    '保存新复制的文本前，按从上到下的顺序执行已开启的清理，去重和搜索都基于清理后的文本。',
+  stripAnsiEscapes: '去除终端颜色代码',
+  stripAnsiEscapesDesc: '移除从终端复制时带上的 ANSI 转义序列。',
+  stripZeroWidth: '去除零宽字符',
+  stripZeroWidthDesc: '移除零宽空格、字连接符和 BOM；表情和文字所需的连接符会保留。',
+  normalizeLineEndings: '统一换行符',
+  normalizeLineEndingsDesc: '把 CRLF 换行转换为 LF。',
+  stripTrackingParams: '去除链接跟踪参数',
+  stripTrackingParamsDesc: '移除链接中的 utm_* 和点击 ID 参数，如 fbclid、gclid。',
+  trimWhitespace: '清理首尾空白',
+  trimWhitespaceDesc: '去掉每行末尾的空白和文本前后的空行，保留缩进。',
 
   // Multi-select merge paste
   selectedCount: '已选 {n} 项',
   mergePasteHint: '合并粘贴（换行分隔）',
   clearSelection: '清除选择',
+
+  // Paste stack and group view
+  addToPasteStack: '入栈',
+  pasteStackQueued: '粘贴栈中共 {n} 项',
+  clearPasteStack: '清空粘贴栈',
+  clearGroupFilter: '显示全部',
+  similarImages: '相似图片',
+  clearSimilarImages: '显示全部',
 };
 
 const en: Translations = {
@@ -460,6 +858,18 @@ const en: Translations = {
 
   // Search
   searchPlaceholder: 'Search clipboard...',
+  kindFilter: 'Filter by kind',
+  kindAll: 'All kinds',
+  kindUrl: 'Links',
+  kindEmail: 'Emails',
+  kindPhone: 'Phone numbers',
+  kindColor: 'Colors',
+  kindPath: 'Paths',
+  kindJson: 'JSON',
+  kindCode: 'Code',
+  kindNumber: 'Numbers',
+  kindDate: 'Dates',
+  kindPlain: 'Plain text',
 
   // Preview pane
   selectToPreview: 'Select an item to see the full content',
@@ -486,6 +896,95 @@ const en: Translations = {
   pinnedShortcut: 'Pinned shortcut',
   pinnedShortcutDesc:
     'Optional. Opens QuickBar directly on the pinned panel; leave empty to disable.',
+  actionShortcuts: 'Action shortcuts',
+  actionShortcutsDesc:
+    'Global shortcuts that act without opening QuickBar. Each must use keys no other shortcut uses.',
+  actionShortcutKeys: 'Keys, e.g. Alt+1',
+  actionPasteRecent: 'Paste recent clip #N',
+  actionPastePinnedSlot: 'Paste pinned slot #N',
+  actionPastePlainText: 'Paste latest as plain text',
+  actionTogglePause: 'Pause / resume capture',
+  actionOpenSearch: 'Open search',
+  actionOpenGroup: 'Open group',
+  actionPasteStackNext: 'Paste next from stack',
+  actionRecentIndex: 'Position, from 1',
+  actionPinnedSlot: 'Pinned slot, from 1',
+  addPinnedSlotShortcuts: 'Paste pinned slots 1–9 with {keys}1–9',
+  automationApi: 'Automation API',
+  automationApiDesc: 'Serve an HTTP/JSON API on 127.0.0.1 so scripts can read and add history',
+  apiPort: 'Port',
+  apiToken: 'Access token',
+  apiTokenDesc: 'Every request must send an Authorization: Bearer <token> header',
+  regenerateApiToken: 'Regenerate',
+  statistics: 'Statistics',
+  statisticsDesc: 'The last 30 days, to help tune the history and size limits',
+  statisticsCaptured: 'Captured',
+  statisticsStored: 'Stored / limit',
+  statisticsOversizedSkipped: 'Skipped for size',
+  statisticsSecretsSkipped: 'Skipped as secrets',
+  statisticsPerDay: 'Captures per day',
+  statisticsPerApp: 'Source apps',
+  statisticsUnknownApp: 'Unknown',
+  statisticsContentTypes: 'Content types',
+  statisticsTopPasted: 'Most pasted',
+  statisticsStorage: 'Storage',
+  statisticsThumbnails: 'Thumbnails',
+  statisticsOther: 'Other formats',
+  statisticsDatabase: 'Database file',
+  plugins: 'Plugins',
+  pluginsDesc:
+    'Load WebAssembly plugins from the plugins folder in the data directory to process text on capture and paste',
+  reloadPlugins: 'Reload',
+  reloadPluginsFailed: 'Failed to reload plugins',
+  openPluginsFolder: 'Open plugins folder',
+  noPlugins:
+    'No plugins installed. After saving settings, put plugins in the plugins folder and reload.',
+  actionSearchQuery: 'Search text (optional)',
+  actionGroupName: 'Group name',
+  addActionShortcut: 'Add',
+  removeActionShortcut: 'Remove',
+  captureRules: 'Capture rules',
+  captureRulesDesc:
+    'Checked top to bottom on every copy. Blank conditions match anything; every matching rule applies in order, a skip ends the check, and the first matching label or group wins.',
+  addCaptureRule: 'Add rule',
+  noCaptureRules: 'No capture rules',
+  captureRuleEnabled: 'Rule enabled',
+  captureRuleName: 'Rule name',
+  moveCaptureRuleUp: 'Move up',
+  moveCaptureRuleDown: 'Move down',
+  removeCaptureRule: 'Remove rule',
+  captureRuleSourceApp: 'Source app (any)',
+  captureRuleContentType: 'Content type',
+  anyContentType: 'Any type',
+  captureRuleContentPattern: 'Content regex (any)',
+  captureRuleMinBytes: 'Min bytes',
+  captureRuleMaxBytes: 'Max bytes',
+  captureRuleTimeWindow: 'Between',
+  captureRuleTimeWindowStart: 'Start time',
+  captureRuleTimeWindowEnd: 'End time',
+  captureRuleAction: 'Action',
+  ruleActionSkip: 'Skip',
+  ruleActionPin: 'Pin',
+  ruleActionLabel: 'Set label',
+  ruleActionGroup: 'Add to group',
+  ruleActionSensitive: 'Keep as sensitive',
+  captureRuleLabel: 'Label',
+  captureHooks: 'Capture hooks',
+  captureHooksDesc:
+    'Commands run in order on every new clip: the content arrives on stdin and metadata in CLIPMAN_* environment variables. Exit status 3 deletes the clip.',
+  addCaptureHook: 'Add hook',
+  noCaptureHooks: 'No capture hooks',
+  captureHookEnabled: 'Hook enabled',
+  captureHookName: 'Hook name',
+  captureHookCommand: 'Command, e.g. tee -a ~/clips.log',
+  moveCaptureHookUp: 'Move up',
+  moveCaptureHookDown: 'Move down',
+  removeCaptureHook: 'Remove hook',
+  captureHookTransform: 'Replace text',
+  captureHookTransformDesc: 'Non-empty output replaces the text of a text clip.',
+  captureHookTimeout: 'Timeout (seconds)',
+  maxConcurrentHooks: 'Concurrent hooks',
+  maxConcurrentHooksDesc: 'How many hook commands may run at the same time (1–16).',
   disableHotkeyFailed: 'Failed to disable hotkey',
 
   // Clipboard settings
@@ -493,6 +992,40 @@ const en: Translations = {
   maxHistoryItemsDesc: 'Maximum number of history items to keep',
   autoPaste: 'Auto-paste',
   autoPasteDesc: 'Paste selected QuickBar items back into the current app; off means copy only.',
+  restoreClipboardAfterPaste: 'Restore clipboard after paste',
+  restoreClipboardAfterPasteDesc: 'After an auto-paste, put back what the clipboard held before.',
+  typePasteCharDelay: 'Type-out delay (ms)',
+  typePasteCharDelayDesc:
+    'Pause between keystrokes when a clip is pasted by typing it out (0–1000).',
+  typePasteMaxChars: 'Type-out length limit',
+  typePasteMaxCharsDesc: 'Longer clips are not typed out.',
+  typePasteCancelShortcut: 'Stop typing out',
+  typePasteCancelShortcutDesc:
+    'Shortcut that stops a type-out paste in progress; must not clash with other shortcuts.',
+  mergeFormat: 'Merge-paste format',
+  mergeFormatDesc: 'How multi-selected clips are laid out when pasted together.',
+  mergeFormatPlain: 'One per line',
+  mergeFormatNumberedList: 'Numbered list',
+  mergeFormatMarkdownBullets: 'Markdown bullets',
+  mergeFormatCsvRow: 'CSV row',
+  mergeFormatCsvColumn: 'CSV column',
+  mergeFormatJsonArray: 'JSON array',
+  mergeFormatTemplate: 'Custom template',
+  mergeTemplateDesc: 'Applied to each clip. Fields: {index}, {content}, {source_app}, {timestamp}.',
+  mergeImages: 'Merge images',
+  mergeImagesDesc: 'When only images are selected, stack them into one image, top to bottom.',
+  fullFidelityCapture: 'Keep every clipboard format',
+  fullFidelityCaptureDesc:
+    'Store every format offered with each copy and restore them all on paste. Uses more storage.',
+  fullFidelityCaptureUnsupported:
+    'Not available on this system; only macOS and Windows can keep every format.',
+  pasteKeyProfiles: 'Paste keys per app',
+  pasteKeyProfilesDesc:
+    'Apps such as terminals that do not paste on Ctrl+V get these keys instead. Others keep Ctrl+V (⌘V on macOS).',
+  pasteKeyProfileApp: 'App name',
+  pasteKeyProfileKeys: 'Keys, e.g. Ctrl+Shift+V',
+  addPasteKeyProfile: 'Add',
+  removePasteKeyProfile: 'Remove',
   ignoreConcealed: 'Ignore concealed clipboard content',
   ignoreConcealedDesc:
     'Skip clipboard payloads marked as passwords, transient, or excluded from history.',
@@ -504,6 +1037,22 @@ const en: Translations = {
   themePink: 'Pink',
   themeSystem: 'System',
   language: 'Language',
+  quickbarPlacement: 'QuickBar position',
+  quickbarPlacementDesc: 'Always opens on the monitor under the mouse cursor.',
+  quickbarPlacementCenter: 'Screen center',
+  quickbarPlacementCursor: 'At the mouse cursor',
+  quickbarPlacementCaret: 'At the text caret (Windows; elsewhere the cursor)',
+  quickbarPlacementLastPosition: 'Where it last closed',
+  quickbarPlacementCorner: 'Screen corner',
+  screenCornerTopLeft: 'Top left',
+  screenCornerTopRight: 'Top right',
+  screenCornerBottomLeft: 'Bottom left',
+  screenCornerBottomRight: 'Bottom right',
+  quickbarSize: 'QuickBar size',
+  quickbarSizeDesc:
+    'Up to 820×600; shrinks on smaller screens. Drag the search bar to move the QuickBar.',
+  quickbarWidth: 'Width',
+  quickbarHeight: 'Height',
 
   // Tray settings
   trayTextLength: 'Tray text length',
@@ -512,6 +1061,9 @@ const en: Translations = {
   maxPinnedInTrayDesc: 'Number of pinned items shown in tray menu',
   maxRecentInTray: 'Recent in tray',
   maxRecentInTrayDesc: 'Number of recent items shown in tray menu',
+  persistTimedPause: 'Keep timed pauses across restarts',
+  persistTimedPauseDesc:
+    'A pause for 5 minutes or 1 hour set from the tray menu stays in effect after the app restarts, until it ends.',
 
   // Storage settings
   dataLocationDesc: 'Choose where ClipMan stores its data',
@@ -552,6 +1104,10 @@ const en: Translations = {
   saveSettingsFailed: 'Failed to save',
   copyFailed: 'Copy failed',
   pasteFailed: 'Paste failed',
+  pasteUnsupported: "Auto-paste isn't available here; the clip was copied, paste it manually",
+  typeFailed: 'Typing failed',
+  typingCancelled: 'Typing cancelled',
+  typingUnsupported: "Typing isn't available in this session",
   clearFailed: 'Failed to clear',
 
   // Loading
@@ -589,11 +1145,13 @@ const en: Translations = {
   close: 'Close',
   reorder: 'Reorder',
   pastePlain: 'Paste as plain text',
+  typeOut: 'Type out',
 
   // Files / rich text
   files: 'Files',
   fileCount: '{n} files',
   richTextBadge: 'Rich text',
+  redactedBadge: 'Secrets redacted',
 
   // Ignored apps
   ignoredApps: 'Ignored apps',
@@ -603,11 +1161,40 @@ const en: Translations = {
   addIgnoredApp: 'Add',
   removeIgnoredApp: 'Remove',
   noIgnoredApps: 'No ignored apps',
+  concealedAsSensitive: 'Keep concealed copies briefly',
+  concealedAsSensitiveDesc:
+    'Instead of skipping copies marked concealed or transient (e.g. by password managers), keep them as sensitive clips that expire.',
+  ignoredAppsAsSensitive: 'Keep copies from ignored apps briefly',
+  ignoredAppsAsSensitiveDesc:
+    'Instead of skipping copies from ignored apps, keep them as sensitive clips that expire.',
+  sensitiveClipTtl: 'Sensitive clip lifetime (seconds)',
+  sensitiveClipTtlDesc:
+    'Sensitive clips are deleted after this long, and cleared from the clipboard if still there (5–86400).',
 
   // Secret detection
   skipSecrets: 'Skip likely secrets',
   skipSecretsDesc:
     'Skip capturing high-confidence secrets such as private keys, API tokens, or JWTs.',
+  secretAction: 'When a secret is found',
+  secretActionDesc:
+    'Skip the clip, keep it with the secret redacted, or keep it briefly as a sensitive clip.',
+  secretActionSkip: 'Skip',
+  secretActionRedact: 'Redact',
+  secretActionExpire: 'Expire',
+  customSecretPatterns: 'Custom secret patterns',
+  customSecretPatternsDesc:
+    'Regular expressions checked alongside the built-in patterns, such as internal token formats; a match is reported by its name.',
+  secretPatternName: 'Name',
+  secretPatternRegex: 'Regular expression',
+  addSecretPattern: 'Add',
+  removeSecretPattern: 'Remove pattern',
+  entropyDetection: 'Detect random-looking tokens',
+  entropyDetectionDesc:
+    'Also treat long, random-looking strings as secrets. May catch hashes and similar values.',
+  entropyThreshold: 'Entropy threshold',
+  entropyThresholdDesc:
+    'Bits of entropy per character (3–6) at which a token counts as random; lower catches more.',
+  secretSkipped: 'Skipped likely secret: ',
 
   // Capture size limits
   maxTextBytes: 'Max text size (MB)',
@@ -615,11 +1202,41 @@ const en: Translations = {
   maxImageDimension: 'Max image dimension (px)',
   maxImageDimensionDesc:
     'Images larger than this on their longest side are downscaled before saving; set to 0 to disable.',
+  collapseSimilarImages: 'Collapse similar images',
+  collapseSimilarImagesDesc:
+    'A copied image that looks the same as a stored one (such as a repeated screenshot) refreshes that clip instead of adding a new one.',
+  similarImageThreshold: 'Similarity threshold',
+  similarImageThresholdDesc:
+    "How many bits (0–16) two images' perceptual hashes may differ by and still count as the same, for collapsing and for finding similar images.",
+  textNormalizers: 'Text cleanup',
+  textNormalizersDesc:
+    'Cleanups applied to newly copied text before it is stored, in order from top to bottom. Dedup and search see the cleaned text.',
+  stripAnsiEscapes: 'Strip terminal color codes',
+  stripAnsiEscapesDesc: 'Remove ANSI escape sequences picked up when copying from a terminal.',
+  stripZeroWidth: 'Strip zero-width characters',
+  stripZeroWidthDesc:
+    'Remove zero-width spaces, word joiners and BOMs; joiners needed by emoji and scripts are kept.',
+  normalizeLineEndings: 'Normalize line endings',
+  normalizeLineEndingsDesc: 'Turn CRLF line endings into LF.',
+  stripTrackingParams: 'Strip link tracking parameters',
+  stripTrackingParamsDesc:
+    'Drop utm_* and click-ID parameters such as fbclid and gclid from links.',
+  trimWhitespace: 'Trim whitespace',
+  trimWhitespaceDesc:
+    'Remove trailing whitespace on each line and blank lines around the text; indentation is kept.',
 
   // Multi-select merge paste
   selectedCount: '{n} selected',
   mergePasteHint: 'Merge paste (newlines)',
   clearSelection: 'Clear selection',
+
+  // Paste stack and group view
+  addToPasteStack: 'Stack',
+  pasteStackQueued: '{n} on the paste stack',
+  clearPasteStack: 'Clear the paste stack',
+  clearGroupFilter: 'Show all clips',
+  similarImages: 'Similar images',
+  clearSimilarImages: 'Show all clips',
 };
 
 const translations: Record<Locale, Translations> = { 'zh-CN': zh, en };
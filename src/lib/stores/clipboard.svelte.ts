@@ -3,7 +3,15 @@ import { listen } from '@tauri-apps/api/event';
 import { SvelteSet } from 'svelte/reactivity';
 import { toastStore } from './toast.svelte';
 import { i18n } from '$lib/i18n';
-import type { ClipItem, PasteMode, ReorderDirection } from '$lib/types';
+import type {
+  ClipItem,
+  MergeFormat,
+  PasteMode,
+  PasteOutcome,
+  ReorderDirection,
+  SecretSkippedEvent,
+  TextKind,
+} from '$lib/types';
 import {
   applyClipboardChanged,
   getPinnedDisplayItems,
@@ -29,6 +37,7 @@ interface IncomingItemEvent {
 }
 
 const QUICKBAR_HIDDEN_EVENT = 'quickbar-hidden';
+const PASTE_STACK_CHANGED_EVENT = 'paste-stack-changed';
 
 class ClipboardStore {
   recentItems = $state.raw<ClipItem[]>([]);
@@ -50,6 +59,20 @@ class ClipboardStore {
   // reads reactive so the list and footer update as items toggle. Cleared at the
   // deliberate reset points (panel switch / quickbar refresh / search / hide).
   selectedIds = new SvelteSet<string>();
+  // Set when the QuickBar was opened on a capture-rule group (an "open group"
+  // action shortcut); the lists then show only `groupItems`. Cleared on hide.
+  groupFilter = $state<string | null>(null);
+  groupItems = $state.raw<ClipItem[]>([]);
+  // Set by ⌘I on an image: the lists then show that image followed by the
+  // stored images that look like it, nearest first. Cleared on hide.
+  similarTo = $state<string | null>(null);
+  similarItems = $state.raw<ClipItem[]>([]);
+  // Narrows search to text clips of one kind; with an empty query the lists
+  // show every clip of that kind. Cleared on hide.
+  kindFilter = $state<TextKind | null>(null);
+  // Clips queued for the "paste next from stack" shortcut, mirrored from the
+  // backend's `paste-stack-changed` events.
+  pasteStackCount = $state(0);
   private static readonly PAGE_SIZE = 100;
   private historyRequests = new RequestSequencer();
   private searchRequests = new RequestSequencer();
@@ -72,6 +95,10 @@ class ClipboardStore {
       searchResults: this.searchResults,
       recentItems: this.recentItems,
       pinnedItems: this.pinnedItems,
+      groupFilter: this.groupFilter,
+      groupItems: this.groupItems,
+      similarItems: this.similarTo === null ? null : this.similarItems,
+      kindFilter: this.kindFilter,
     })
   );
 
@@ -81,6 +108,10 @@ class ClipboardStore {
       searchResults: this.searchResults,
       recentItems: this.recentItems,
       pinnedItems: this.pinnedItems,
+      groupFilter: this.groupFilter,
+      groupItems: this.groupItems,
+      similarItems: this.similarTo === null ? null : this.similarItems,
+      kindFilter: this.kindFilter,
     })
   );
 
@@ -97,7 +128,7 @@ class ClipboardStore {
     await this.loadHistory();
 
     await listen<ClipItem>('clipboard-changed', async (event) => {
-      if (this.searchQuery.trim()) {
+      if (this.searchQuery.trim() || this.groupFilter !== null || this.kindFilter !== null) {
         await this.reloadFromBackend();
         return;
       }
@@ -105,6 +136,28 @@ class ClipboardStore {
       this.applyIncomingItem(event.payload);
     });
 
+    await listen<SecretSkippedEvent>('secret-skipped', (event) => {
+      toastStore.add(`${i18n.t.secretSkipped}${event.payload.rule}`, 'info');
+    });
+
+    await listen<string[]>('clips-expired', (event) => {
+      for (const id of event.payload) {
+        this.removeClipLocally(id);
+      }
+    });
+
+    // Deleted through the automation API or vetoed by a capture hook.
+    await listen<string[]>('clips-deleted', (event) => {
+      for (const id of event.payload) {
+        this.removeClipLocally(id);
+      }
+    });
+
+    // Rewritten in place by a capture hook.
+    await listen<ClipItem>('clip-updated', (event) => {
+      this.updateClipLocally(event.payload);
+    });
+
     await listen('history-cleared', async () => {
       this.fullClipCache.clear();
       this.clearSelection();
@@ -113,8 +166,20 @@ class ClipboardStore {
 
     await listen(QUICKBAR_HIDDEN_EVENT, () => {
       this.clearSelection();
+      this.clearGroupFilter();
+      this.clearSimilarImages();
+      this.kindFilter = null;
       void this.clearSearch({ reload: false });
     });
+
+    await listen<number>(PASTE_STACK_CHANGED_EVENT, (event) => {
+      this.pasteStackCount = event.payload;
+    });
+    try {
+      this.pasteStackCount = await invoke<number>('get_paste_stack_count');
+    } catch (error) {
+      console.error('Failed to read the paste stack:', error);
+    }
   }
 
   async loadHistory(options: LoadHistoryOptions = {}) {
@@ -160,7 +225,7 @@ class ClipboardStore {
         this.pinnedItems = nextItems.pinnedItems;
         this.hasMoreRecent = hasMore;
         this.isLoadingMore = false;
-        if (!this.searchQuery.trim()) {
+        if (!this.searchQuery.trim() && this.kindFilter === null) {
           this.searchResults = [];
         }
       }
@@ -283,7 +348,7 @@ class ClipboardStore {
     if (query.trim() && this.searchQuery !== query) {
       return;
     }
-    if (!query.trim()) {
+    if (!query.trim() && this.kindFilter === null) {
       return this.clearSearch();
     }
     if (!hasTauriRuntime()) {
@@ -304,7 +369,10 @@ class ClipboardStore {
     // `isCurrent` alone guarantees `searchQuery` is still `query`: every
     // assignment to `searchQuery` bumps the sequencer first.
     try {
-      const results = await invoke<ClipItem[]>('search_clips', { query });
+      const results = await invoke<ClipItem[]>('search_clips', {
+        query,
+        kind: this.kindFilter,
+      });
       if (this.searchRequests.isCurrent(requestId)) {
         this.searchResults = results;
         this.activeSearchQuery = query;
@@ -328,11 +396,80 @@ class ClipboardStore {
     this.isSearchPending = false;
     this.isLoading = false;
 
-    if (options.reload ?? true) {
+    if (!(options.reload ?? true)) {
+      return;
+    }
+    // Emptying the query under a kind filter lists every clip of that kind.
+    if (this.kindFilter !== null) {
+      await this.search('');
+    } else {
       await this.loadHistory({ showLoading: false });
     }
   }
 
+  /** Restrict search to text clips of `kind`, or lift the restriction with `null`. */
+  async setKindFilter(kind: TextKind | null) {
+    this.kindFilter = kind;
+    if (kind === null && !this.searchQuery.trim()) {
+      await this.clearSearch();
+    } else {
+      await this.search(this.searchQuery);
+    }
+  }
+
+  /** Narrow both lists to the clips capture rules put in `group`. */
+  async openGroup(group: string) {
+    this.groupFilter = group;
+    await this.loadGroupItems();
+  }
+
+  clearGroupFilter() {
+    this.groupFilter = null;
+    this.groupItems = [];
+  }
+
+  /** Narrow both lists to image `item` and the stored images that look like it. */
+  async showSimilarImages(item: ClipItem) {
+    if (item.contentType !== 'image' || !hasTauriRuntime()) return;
+
+    this.similarTo = item.id;
+    try {
+      const similar = await invoke<ClipItem[]>('get_similar_images', { id: item.id });
+      // Another image may have been picked (or the view closed) meanwhile.
+      if (this.similarTo === item.id) {
+        this.similarItems = [item, ...similar];
+      }
+    } catch (error) {
+      console.error('Failed to load similar images:', error);
+    }
+  }
+
+  clearSimilarImages() {
+    this.similarTo = null;
+    this.similarItems = [];
+  }
+
+  /** Queue clips, in order, for the "paste next from stack" shortcut. */
+  async pushToPasteStack(ids: string[]) {
+    if (ids.length === 0) return;
+
+    try {
+      this.pasteStackCount = await invoke<number>('push_paste_stack', { ids });
+      this.clearSelection();
+    } catch (error) {
+      console.error('[ERROR] Failed to queue clips on the paste stack:', error);
+    }
+  }
+
+  async clearPasteStack() {
+    try {
+      await invoke('clear_paste_stack');
+      this.pasteStackCount = 0;
+    } catch (error) {
+      console.error('[ERROR] Failed to clear the paste stack:', error);
+    }
+  }
+
   async clearNonPinned() {
     try {
       // The backend emits `history-cleared` after clearing (the tray's "clear"
@@ -397,7 +534,12 @@ class ClipboardStore {
     try {
       // `plain` (⌥Enter) forces a plain-text paste. The backend ignores it for
       // non-text clips, so it is passed through without a frontend type branch.
-      await invoke('paste_clip', { id: item.id, mode, plain: options.plain ?? false });
+      const outcome = await invoke<PasteOutcome>('paste_clip', {
+        id: item.id,
+        mode,
+        plain: options.plain ?? false,
+      });
+      this.reportPasteOutcome(outcome, mode);
     } catch (error) {
       console.error('[ERROR] Failed to use clip:', error);
       toastStore.add(this.pasteFailureMessage(mode), 'error');
@@ -409,12 +551,30 @@ class ClipboardStore {
    * mode 'default' honors the auto-paste setting, 'opposite' (⌘Enter) inverts it.
    * So the action is a paste iff `(mode === 'default') === autoPaste` — the same
    * mapping the footer hints use — which decides whether to say "paste" or "copy".
+   * A type-out ('type', ⇧Enter) has its own message.
    */
   private pasteFailureMessage(mode: PasteMode): string {
+    if (mode === 'type') return i18n.t.typeFailed;
     const isPaste = (mode === 'default') === this.autoPaste;
     return isPaste ? i18n.t.pasteFailed : i18n.t.copyFailed;
   }
 
+  /**
+   * Toasts the outcomes worth a word: this session (e.g. Wayland without a
+   * virtual keyboard or uinput access) could not simulate the paste or the
+   * typing, or the user stopped a type-out with the cancel shortcut.
+   */
+  private reportPasteOutcome(outcome: PasteOutcome | null, mode: PasteMode) {
+    if (outcome?.status === 'pasteUnsupported') {
+      console.warn('[WARN] Paste unsupported:', outcome.reason);
+      // Typing never touches the clipboard, so there is nothing to paste by hand.
+      const message = mode === 'type' ? i18n.t.typingUnsupported : i18n.t.pasteUnsupported;
+      toastStore.add(message, 'info');
+    } else if (outcome?.status === 'typed' && outcome.cancelled) {
+      toastStore.add(i18n.t.typingCancelled, 'info');
+    }
+  }
+
   isSelected(id: string): boolean {
     return this.selectedIds.has(id);
   }
@@ -435,16 +595,23 @@ class ClipboardStore {
 
   /**
    * Merge the multi-selected clips (in selection order) into a single clipboard
-   * write and paste them, newline-separated (task #13). No-op when nothing is
-   * selected; clears the selection once the paste is dispatched.
+   * write and paste them (task #13), laid out per `format` or, when omitted, the
+   * `mergeFormat` setting. No-op when nothing is selected; clears the selection
+   * once the paste is dispatched.
    */
-  async useSelectedClips(mode: PasteMode = 'default') {
+  async useSelectedClips(mode: PasteMode = 'default', format?: MergeFormat) {
     const ids = [...this.selectedIds];
     if (ids.length === 0) return;
 
     try {
-      await invoke('paste_clips', { ids, mode, separator: '\n' });
+      const outcome = await invoke<PasteOutcome>('paste_clips', {
+        ids,
+        mode,
+        separator: '\n',
+        ...(format ? { format } : {}),
+      });
       this.clearSelection();
+      this.reportPasteOutcome(outcome, mode);
     } catch (error) {
       console.error('[ERROR] Failed to merge-paste clips:', error);
       toastStore.add(this.pasteFailureMessage(mode), 'error');
@@ -518,11 +685,35 @@ class ClipboardStore {
   private async reloadFromBackend() {
     await this.loadHistory({ showLoading: false });
 
-    if (this.searchQuery.trim()) {
+    if (this.groupFilter !== null) {
+      await this.loadGroupItems();
+    }
+
+    // Pick up pin and label changes from the reloaded lists.
+    if (this.similarTo !== null) {
+      this.similarItems = this.similarItems.map((item) => this.findItem(item.id) ?? item);
+    }
+
+    if (this.searchQuery.trim() || this.kindFilter !== null) {
       await this.search(this.searchQuery, { silent: true });
     }
   }
 
+  private async loadGroupItems() {
+    const group = this.groupFilter;
+    if (group === null || !hasTauriRuntime()) return;
+
+    try {
+      const items = await invoke<ClipItem[]>('get_group_clips', { group });
+      // The group may have been cleared (QuickBar hidden) while loading.
+      if (this.groupFilter === group) {
+        this.groupItems = items;
+      }
+    } catch (error) {
+      console.error('Failed to load group clips:', error);
+    }
+  }
+
   private applyIncomingItem(incoming: ClipItem) {
     this.recordIncomingItem(incoming);
 
@@ -545,10 +736,24 @@ class ClipboardStore {
     this.recentItems = this.recentItems.filter((item) => item.id !== id);
     this.pinnedItems = this.pinnedItems.filter((item) => item.id !== id);
     this.searchResults = this.searchResults.filter((item) => item.id !== id);
+    this.groupItems = this.groupItems.filter((item) => item.id !== id);
+    this.similarItems = this.similarItems.filter((item) => item.id !== id);
     this.isLoading = false;
     this.isSearchPending = false;
   }
 
+  private updateClipLocally(updated: ClipItem) {
+    const replace = (items: ClipItem[]) =>
+      items.map((item) => (item.id === updated.id ? updated : item));
+
+    this.fullClipCache.delete(updated.id);
+    this.recentItems = replace(this.recentItems);
+    this.pinnedItems = replace(this.pinnedItems);
+    this.searchResults = replace(this.searchResults);
+    this.groupItems = replace(this.groupItems);
+    this.similarItems = replace(this.similarItems);
+  }
+
   private recordIncomingItem(item: ClipItem) {
     this.incomingRevision += 1;
     this.incomingEvents.push({ revision: this.incomingRevision, item });
@@ -8,6 +8,21 @@ export type { Locale };
  */
 export type ContentType = 'text' | 'image' | 'files';
 
+/**
+ * What a text clip holds, classified at capture time
+ */
+export type TextKind =
+  | 'url'
+  | 'email'
+  | 'phone'
+  | 'color'
+  | 'path'
+  | 'json'
+  | 'code'
+  | 'number'
+  | 'date'
+  | 'plain';
+
 /**
  * Clipboard item from backend
  */
@@ -26,6 +41,197 @@ export interface ClipItem {
   sourceApp: string | null;
   /** Whether a text clip carries an HTML (rich-text) companion. */
   hasHtml: boolean;
+  /** Whether a text clip carries an RTF companion. */
+  hasRtf: boolean;
+  /** Classified kind of a text clip; null for images, files and unclassified clips. */
+  textKind: TextKind | null;
+  /** Best-guess language when `textKind` is 'code'. */
+  codeLanguage: string | null;
+  /** Detected secrets in this text clip were replaced with a placeholder at capture. */
+  isRedacted: boolean;
+  /** Unix time (seconds) at which this sensitive clip is deleted; null for ordinary clips. */
+  expiresAt: number | null;
+}
+
+/**
+ * A user-defined secret rule; `name` is what gets reported when it matches.
+ */
+export interface SecretPattern {
+  name: string;
+  pattern: string;
+}
+
+export type SecretAction = 'skip' | 'redact' | 'expire';
+
+/**
+ * Payload of the `secret-skipped` event emitted when a copy is not captured.
+ */
+export interface SecretSkippedEvent {
+  rule: string;
+}
+
+/**
+ * Local time of day in minutes after midnight; an `end` before `start` wraps past midnight.
+ */
+export interface TimeWindow {
+  start: number;
+  end: number;
+}
+
+export type RuleAction =
+  | { type: 'skip' }
+  | { type: 'pin' }
+  | { type: 'label'; label: string }
+  | { type: 'group'; group: string }
+  | { type: 'sensitive' };
+
+/**
+ * An ordered capture rule: the action applies when every condition that is set holds.
+ */
+export interface CaptureRule {
+  name: string;
+  enabled: boolean;
+  sourceApp: string | null;
+  /** Regex over the clip text (path list for files); never matches images. */
+  contentPattern: string | null;
+  contentType: ContentType | null;
+  minBytes: number | null;
+  maxBytes: number | null;
+  timeWindow: TimeWindow | null;
+  action: RuleAction;
+}
+
+/**
+ * A command run on every new clip: content on stdin, metadata in `CLIPMAN_*` env vars. With
+ * `transform`, non-empty stdout replaces a text clip's text; exit status 3 deletes the clip.
+ */
+export interface CaptureHook {
+  name: string;
+  enabled: boolean;
+  command: string;
+  sourceApp: string | null;
+  contentType: ContentType | null;
+  transform: boolean;
+  timeoutSecs: number;
+}
+
+/**
+ * `get_statistics`: counts of recorded capture, paste and skip events since `since`
+ * (unix seconds), and the bytes the stored history takes now.
+ */
+export interface ClipStatistics {
+  since: number;
+  clipsPerDay: { day: string; count: number }[];
+  clipsPerApp: { app: string | null; count: number }[];
+  contentTypes: { contentType: ContentType; count: number }[];
+  topPasted: { clip: ClipItem; pastes: number }[];
+  secretsSkipped: number;
+  oversizedSkipped: number;
+  storedClips: number;
+  storage: {
+    text: number;
+    html: number;
+    images: number;
+    thumbnails: number;
+    other: number;
+    database: number;
+  };
+}
+
+export type PluginCapability = 'veto' | 'transform' | 'classify' | 'annotate' | 'pasteTransform';
+
+/**
+ * A plugin directory under `<data dir>/plugins`; `error` says why it did not load.
+ */
+export interface PluginInfo {
+  id: string;
+  name: string;
+  version: string;
+  description: string;
+  capabilities: PluginCapability[];
+  error: string | null;
+}
+
+export interface PluginsOverview {
+  dir: string;
+  plugins: PluginInfo[];
+}
+
+/**
+ * How merge-paste lays out the selected clips' text. `template` fills `{index}` (from 1),
+ * `{content}`, `{source_app}` and `{timestamp}` per clip.
+ */
+export type MergeFormat =
+  | { type: 'plain' }
+  | { type: 'numberedList' }
+  | { type: 'markdownBullets' }
+  | { type: 'csvRow' }
+  | { type: 'csvColumn' }
+  | { type: 'jsonArray' }
+  | { type: 'template'; template: string };
+
+/**
+ * Keys that paste into one app, e.g. `{ app: 'kitty', keys: 'Ctrl+Shift+V' }`. `app` is the
+ * executable name on Windows, the app name on macOS and the window class on X11, compared
+ * case-insensitively.
+ */
+export interface PasteKeyProfile {
+  app: string;
+  keys: string;
+}
+
+export type ScreenCorner = 'topLeft' | 'topRight' | 'bottomLeft' | 'bottomRight';
+
+/**
+ * Where the QuickBar opens, always on the monitor under the cursor. `caret` falls back to the
+ * cursor where the platform does not expose the text caret (everywhere but Windows).
+ */
+export type QuickBarPlacement =
+  | { type: 'center' }
+  | { type: 'cursor' }
+  | { type: 'caret' }
+  | { type: 'lastPosition' }
+  | { type: 'corner'; corner: ScreenCorner };
+
+/**
+ * What an action shortcut does. `pasteRecent` counts from 1, in the tray's order;
+ * `pastePinnedSlot` counts from 1 in the pinned panel's order;
+ * `pasteStackNext` pastes the oldest clip queued with `push_paste_stack`.
+ */
+export type ShortcutAction =
+  | { type: 'pasteRecent'; index: number }
+  | { type: 'pastePinnedSlot'; slot: number }
+  | { type: 'pastePlainText' }
+  | { type: 'togglePause' }
+  | { type: 'openSearch'; query: string }
+  | { type: 'openGroup'; group: string }
+  | { type: 'pasteStackNext' };
+
+export interface ActionShortcut {
+  shortcut: string;
+  action: ShortcutAction;
+}
+
+/**
+ * Top-left of the QuickBar relative to its monitor's work area, in logical px.
+ */
+export interface QuickBarOffset {
+  x: number;
+  y: number;
+}
+
+/**
+ * Opt-in cleanups applied to captured text before it is deduplicated and stored.
+ */
+export interface TextNormalizers {
+  /** Trailing whitespace on each line and blank lines around the text; indentation is kept. */
+  trimWhitespace: boolean;
+  stripAnsiEscapes: boolean;
+  /** `utm_*` and click-id parameters in links. */
+  stripTrackingParams: boolean;
+  /** CRLF to LF. */
+  normalizeLineEndings: boolean;
+  stripZeroWidth: boolean;
 }
 
 /**
@@ -34,8 +240,33 @@ export interface ClipItem {
 export interface Settings {
   globalShortcut: string;
   autoPaste: boolean;
+  /** Put the previous clipboard back after a simulated paste. */
+  restoreClipboardAfterPaste: boolean;
+  /** Milliseconds (100–10000) the pasted clip stays on the clipboard before the restore. */
+  restoreClipboardDelayMs: number;
+  /** Milliseconds between keystrokes of a type-out paste. */
+  typePasteCharDelayMs: number;
+  /** Longest clip, in characters, a type-out paste accepts. */
+  typePasteMaxChars: number;
+  /** Global shortcut that stops a type-out paste; registered only while typing. */
+  typePasteCancelShortcut: string;
+  /** Default layout of a merge-paste. */
+  mergeFormat: MergeFormat;
+  /** Stack an all-image merge-paste into one image instead of refusing it. */
+  mergeImages: boolean;
+  /** First match wins; other apps paste with Ctrl+V (Cmd+V on macOS). */
+  pasteKeyProfiles: PasteKeyProfile[];
+  quickbarPlacement: QuickBarPlacement;
+  /** Logical px, 420–820; smaller screens shrink the QuickBar further. */
+  quickbarWidth: number;
+  /** Logical px, 280–600. */
+  quickbarHeight: number;
+  /** Written by the backend as the QuickBar closes; saving settings never changes it. */
+  quickbarLastPosition: QuickBarOffset | null;
   ignoreConcealed: boolean;
   pinnedShortcut: string | null;
+  /** Every shortcut, including the main and pinned ones, must use distinct keys. */
+  actionShortcuts: ActionShortcut[];
   maxHistoryItems: number;
   trayTextLength: number;
   maxPinnedInTray: number;
@@ -45,21 +276,86 @@ export interface Settings {
   locale: Locale;
   /** App names whose copies are never captured (matched case-insensitively). */
   ignoredApps: string[];
+  /** Keep concealed/transient copies briefly instead of skipping them. */
+  concealedAsSensitive: boolean;
+  /** Keep copies from ignored apps briefly instead of skipping them. */
+  ignoredAppsAsSensitive: boolean;
+  /** Seconds a sensitive clip is kept before it is deleted and cleared from the clipboard. */
+  sensitiveClipTtlSecs: number;
+  /** Evaluated in order on every capture that passed the built-in checks. */
+  captureRules: CaptureRule[];
   /** Skip capturing Text clips that look like a high-confidence secret. */
   skipSecrets: boolean;
+  /** With `skipSecrets` on: drop the whole clip, or store it with secrets redacted. */
+  secretAction: SecretAction;
+  /** Extra named regexes treated as secrets alongside the built-in patterns. */
+  customSecretPatterns: SecretPattern[];
+  /** Also treat long random-looking tokens as secrets. */
+  entropyDetection: boolean;
+  /** Bits of entropy per character (3–6) at which a token counts as random. */
+  entropyThreshold: number;
   /** Text/Files clips larger than this many bytes are skipped at capture time. */
   maxTextBytes: number;
   /** Images whose longest side exceeds this many pixels are downsampled; 0 disables downscaling. */
   maxImageDimension: number;
+  textNormalizers: TextNormalizers;
   /** Capture is fully paused (toggled from the tray menu; not shown in Settings UI). */
   capturePaused: boolean;
+  /** Keep a timed tray pause ("for 1 hour") across app restarts. */
+  persistTimedPause: boolean;
+  /** Deadline (Unix seconds) of a persisted timed pause; owned by the tray, not the Settings UI. */
+  pausedUntil: number | null;
+  /** Store every offered clipboard format with each clip and restore them all on paste. */
+  fullFidelityCapture: boolean;
+  /** A captured image visually identical to a stored one refreshes it instead of adding a clip. */
+  collapseSimilarImages: boolean;
+  /** Max perceptual-hash distance (bits, 0–16) at which two images count as the same. */
+  similarImageThreshold: number;
+  /** Serve the local HTTP/JSON automation API on 127.0.0.1. */
+  apiEnabled: boolean;
+  /** Loopback port of the automation API (1024 or above). */
+  apiPort: number;
+  /** Bearer token every API request must carry; generated when the API is first enabled. */
+  apiToken: string;
+  /** Run in order on every new clip. */
+  captureHooks: CaptureHook[];
+  /** Hook processes allowed to run at once. */
+  maxConcurrentHooks: number;
+  /** Load WebAssembly plugins from the data directory and run them at capture and paste. */
+  pluginsEnabled: boolean;
 }
 
-// The frontend only ever issues 'default' (honor the auto-paste setting) or
-// 'opposite' (⌘Enter swap). The Rust `PasteMode` enum additionally accepts
-// 'paste'/'copy', but nothing in the UI emits them — narrowing here makes the
-// paste/copy failure toast branch on a real, exhaustive set of modes.
-export type PasteMode = 'default' | 'opposite';
+// The frontend only ever issues 'default' (honor the auto-paste setting),
+// 'opposite' (⌘Enter swap) or 'type' (⇧Enter, keystrokes instead of the
+// clipboard). The Rust `PasteMode` enum additionally accepts 'paste'/'copy',
+// but nothing in the UI emits them — narrowing here makes the paste/copy
+// failure toast branch on a real, exhaustive set of modes.
+export type PasteMode = 'default' | 'opposite' | 'type';
+
+export type PasteUnsupportedReason =
+  | 'accessibilityPermissionMissing'
+  | 'noDisplay'
+  | 'waylandInputUnavailable'
+  | 'inputFailed'
+  | 'typingUnavailable';
+
+/**
+ * Result of `paste_clip` / `paste_clips`; on `pasteUnsupported` the clip is on the clipboard only.
+ */
+export type PasteOutcome =
+  | { status: 'pasted' }
+  | { status: 'copied' }
+  | { status: 'typed'; cancelled: boolean }
+  | { status: 'pasteUnsupported'; reason: PasteUnsupportedReason };
+
+export type PasteBackend = 'native' | 'x11' | 'virtualKeyboard' | 'uinput';
+
+/**
+ * Result of `get_paste_support`: whether auto-paste can work in this session.
+ */
+export type PasteSupport =
+  | { status: 'supported'; backend: PasteBackend }
+  | { status: 'unsupported'; reason: PasteUnsupportedReason };
 
 export type ReorderDirection = 'up' | 'down';
 
@@ -1,4 +1,4 @@
-import type { ClipItem } from '$lib/types';
+import type { ClipItem, TextKind } from '$lib/types';
 
 const MAX_DECODE_CACHE_SIZE = 1000;
 
@@ -15,6 +15,13 @@ interface DisplayItemsOptions {
   searchResults: readonly ClipItem[];
   recentItems: readonly ClipItem[];
   pinnedItems: readonly ClipItem[];
+  /** When set, only clips of this group are shown, taken from `groupItems` unless searching. */
+  groupFilter?: string | null;
+  groupItems?: readonly ClipItem[];
+  /** When set, these images are shown in their own (nearest-first) order unless searching. */
+  similarItems?: readonly ClipItem[] | null;
+  /** When set, `searchResults` hold the clips of this kind and are shown even for an empty query. */
+  kindFilter?: TextKind | null;
 }
 
 export function getRecentDisplayItems({
@@ -22,10 +29,21 @@ export function getRecentDisplayItems({
   searchResults,
   recentItems,
   pinnedItems,
+  groupFilter = null,
+  groupItems = [],
+  similarItems = null,
+  kindFilter = null,
 }: DisplayItemsOptions) {
-  const items = activeSearchQuery.trim()
-    ? searchResults
-    : mergeItemsById([...recentItems, ...pinnedItems]);
+  let items: readonly ClipItem[];
+  if (activeSearchQuery.trim() || kindFilter !== null) {
+    items = filterByGroup(searchResults, groupFilter);
+  } else if (similarItems !== null) {
+    return [...similarItems];
+  } else if (groupFilter !== null) {
+    items = groupItems;
+  } else {
+    items = mergeItemsById([...recentItems, ...pinnedItems]);
+  }
 
   return [...items].sort(compareTimestampDesc);
 }
@@ -34,14 +52,29 @@ export function getPinnedDisplayItems({
   activeSearchQuery,
   searchResults,
   pinnedItems,
+  groupFilter = null,
+  groupItems = [],
+  similarItems = null,
+  kindFilter = null,
 }: DisplayItemsOptions) {
-  const items = activeSearchQuery.trim()
-    ? searchResults.filter((item) => item.isPinned)
-    : pinnedItems;
+  let items: readonly ClipItem[];
+  if (activeSearchQuery.trim() || kindFilter !== null) {
+    items = filterByGroup(searchResults, groupFilter).filter((item) => item.isPinned);
+  } else if (similarItems !== null) {
+    return similarItems.filter((item) => item.isPinned);
+  } else if (groupFilter !== null) {
+    items = groupItems.filter((item) => item.isPinned);
+  } else {
+    items = pinnedItems;
+  }
 
   return [...items].sort(comparePinOrder);
 }
 
+function filterByGroup(items: readonly ClipItem[], groupFilter: string | null) {
+  return groupFilter === null ? items : items.filter((item) => item.groupName === groupFilter);
+}
+
 function compareTimestampDesc(a: ClipItem, b: ClipItem) {
   return b.timestamp - a.timestamp;
 }
@@ -10,8 +10,35 @@ export function createDefaultSettings(): Settings {
   return {
     globalShortcut: 'CommandOrControl+Shift+V',
     autoPaste: true,
+    restoreClipboardAfterPaste: false,
+    restoreClipboardDelayMs: 500,
+    typePasteCharDelayMs: 10,
+    typePasteMaxChars: 5000,
+    typePasteCancelShortcut: 'Escape',
+    mergeFormat: { type: 'plain' },
+    mergeImages: false,
+    pasteKeyProfiles: [
+      { app: 'gnome-terminal', keys: 'Ctrl+Shift+V' },
+      { app: 'gnome-terminal-server', keys: 'Ctrl+Shift+V' },
+      { app: 'ptyxis', keys: 'Ctrl+Shift+V' },
+      { app: 'konsole', keys: 'Ctrl+Shift+V' },
+      { app: 'xfce4-terminal', keys: 'Ctrl+Shift+V' },
+      { app: 'mate-terminal', keys: 'Ctrl+Shift+V' },
+      { app: 'lxterminal', keys: 'Ctrl+Shift+V' },
+      { app: 'tilix', keys: 'Ctrl+Shift+V' },
+      { app: 'terminator', keys: 'Ctrl+Shift+V' },
+      { app: 'alacritty', keys: 'Ctrl+Shift+V' },
+      { app: 'kitty', keys: 'Ctrl+Shift+V' },
+      { app: 'org.wezfurlong.wezterm', keys: 'Ctrl+Shift+V' },
+      { app: 'mintty', keys: 'Shift+Insert' },
+    ],
+    quickbarPlacement: { type: 'center' },
+    quickbarWidth: 820,
+    quickbarHeight: 600,
+    quickbarLastPosition: null,
     ignoreConcealed: true,
     pinnedShortcut: null,
+    actionShortcuts: [],
     maxHistoryItems: 100,
     trayTextLength: 70,
     maxPinnedInTray: 5,
@@ -20,10 +47,36 @@ export function createDefaultSettings(): Settings {
     enableAutostart: false,
     locale: 'zh-CN',
     ignoredApps: [],
+    concealedAsSensitive: false,
+    ignoredAppsAsSensitive: false,
+    sensitiveClipTtlSecs: 30,
+    captureRules: [],
     skipSecrets: true,
+    secretAction: 'skip',
+    customSecretPatterns: [],
+    entropyDetection: false,
+    entropyThreshold: 4.5,
     maxTextBytes: 2000000,
     maxImageDimension: 4096,
+    textNormalizers: {
+      trimWhitespace: false,
+      stripAnsiEscapes: false,
+      stripTrackingParams: false,
+      normalizeLineEndings: false,
+      stripZeroWidth: false,
+    },
     capturePaused: false,
+    persistTimedPause: false,
+    pausedUntil: null,
+    fullFidelityCapture: false,
+    collapseSimilarImages: false,
+    similarImageThreshold: 5,
+    apiEnabled: false,
+    apiPort: 17321,
+    apiToken: '',
+    captureHooks: [],
+    maxConcurrentHooks: 2,
+    pluginsEnabled: false,
   };
 }
 
@@ -4,6 +4,7 @@ import {
   comparePinOrder,
   decodeClipText,
   decodeFilePaths,
+  getPinnedDisplayItems,
   getRecentDisplayItems,
 } from '../../src/lib/utils/clip-items';
 import type { ClipItem } from '../../src/lib/types';
@@ -24,6 +25,11 @@ function clip(overrides: Partial<ClipItem>): ClipItem {
     groupName: null,
     sourceApp: null,
     hasHtml: false,
+    hasRtf: false,
+    textKind: null,
+    codeLanguage: null,
+    isRedacted: false,
+    expiresAt: null,
     ...overrides,
   };
 }
@@ -171,4 +177,54 @@ describe('clip item helpers', () => {
 
     expect(items.map((item) => item.id)).toEqual(['recent-match', 'pinned-match']);
   });
+
+  test('a group filter shows only that group, searching within it', () => {
+    const options = {
+      activeSearchQuery: '',
+      searchResults: [
+        clip({ id: 'other-match', groupName: 'other', timestamp: 30 }),
+        clip({ id: 'ticket-match', groupName: 'tickets', timestamp: 20 }),
+      ],
+      recentItems: [clip({ id: 'recent', timestamp: 40 })],
+      pinnedItems: [],
+      groupFilter: 'tickets',
+      groupItems: [
+        clip({ id: 'ticket-old', groupName: 'tickets', timestamp: 10 }),
+        clip({ id: 'ticket-pinned', groupName: 'tickets', isPinned: true, timestamp: 15 }),
+      ],
+    };
+
+    expect(getRecentDisplayItems(options).map((item) => item.id)).toEqual([
+      'ticket-pinned',
+      'ticket-old',
+    ]);
+    expect(getPinnedDisplayItems(options).map((item) => item.id)).toEqual(['ticket-pinned']);
+    expect(
+      getRecentDisplayItems({ ...options, activeSearchQuery: 'match' }).map((item) => item.id)
+    ).toEqual(['ticket-match']);
+  });
+
+  test('similar images keep their nearest-first order until a search', () => {
+    const options = {
+      activeSearchQuery: '',
+      searchResults: [clip({ id: 'match', timestamp: 30 })],
+      recentItems: [clip({ id: 'recent', timestamp: 40 })],
+      pinnedItems: [],
+      similarItems: [
+        clip({ id: 'shown', contentType: 'image', timestamp: 10 }),
+        clip({ id: 'nearest', contentType: 'image', timestamp: 5 }),
+        clip({ id: 'pinned', contentType: 'image', isPinned: true, timestamp: 20 }),
+      ],
+    };
+
+    expect(getRecentDisplayItems(options).map((item) => item.id)).toEqual([
+      'shown',
+      'nearest',
+      'pinned',
+    ]);
+    expect(getPinnedDisplayItems(options).map((item) => item.id)).toEqual(['pinned']);
+    expect(
+      getRecentDisplayItems({ ...options, activeSearchQuery: 'match' }).map((item) => item.id)
+    ).toEqual(['match']);
+  });
 });
@@ -72,6 +72,11 @@ function clip(overrides: Partial<ClipItem>): ClipItem {
     groupName: null,
     sourceApp: null,
     hasHtml: false,
+    hasRtf: false,
+    textKind: null,
+    codeLanguage: null,
+    isRedacted: false,
+    expiresAt: null,
     ...overrides,
   };
 }
@@ -96,6 +101,10 @@ function resetStore() {
   clipboardStore.maxHistoryItems = 100;
   clipboardStore.autoPaste = true;
   clipboardStore.selectedIds.clear();
+  clipboardStore.clearGroupFilter();
+  clipboardStore.clearSimilarImages();
+  clipboardStore.kindFilter = null;
+  clipboardStore.pasteStackCount = 0;
   toastStore.toasts = [];
 }
 
@@ -318,6 +327,36 @@ describe('clipboard store races', () => {
     expect(toastStore.toasts.at(-1)?.message).toBe(i18n.t.pasteFailed);
   });
 
+  test('useClip tells the user when the session cannot simulate paste', async () => {
+    installTauriInvoke((cmd) =>
+      cmd === 'paste_clip'
+        ? { status: 'pasteUnsupported', reason: 'waylandInputUnavailable' }
+        : null
+    );
+
+    await clipboardStore.useClip(clip({ id: 'c1' }), 'default');
+
+    expect(toastStore.toasts.at(-1)?.message).toBe(i18n.t.pasteUnsupported);
+    expect(toastStore.toasts.at(-1)?.type).toBe('info');
+  });
+
+  test('useClip reports a cancelled or failed type-out', async () => {
+    let typed: unknown = { status: 'typed', cancelled: true };
+    installTauriInvoke((cmd) => {
+      if (cmd !== 'paste_clip') return null;
+      if (typed instanceof Error) throw typed;
+      return typed;
+    });
+
+    await clipboardStore.useClip(clip({ id: 'c1' }), 'type');
+    expect(toastStore.toasts.at(-1)?.message).toBe(i18n.t.typingCancelled);
+
+    typed = new Error('Clip is too long to type');
+    await clipboardStore.useClip(clip({ id: 'c1' }), 'type');
+    expect(toastStore.toasts.at(-1)?.message).toBe(i18n.t.typeFailed);
+    expect(toastStore.toasts.at(-1)?.type).toBe('error');
+  });
+
   test('useSelectedClips toasts on merge-paste failure and keeps the selection', async () => {
     installTauriInvoke((cmd) => {
       if (cmd === 'paste_clips') throw new Error('boom');
@@ -422,6 +461,103 @@ describe('clipboard store races', () => {
     expect(pastes[0].args).toEqual({ ids: ['x'], mode: 'opposite', separator: '\n' });
   });
 
+  test('useSelectedClips sends an explicit merge format only when given', async () => {
+    const calls: Array<{ cmd: string; args?: Record<string, unknown> }> = [];
+    installTauriInvoke((cmd, args) => {
+      calls.push({ cmd, args });
+      return null;
+    });
+
+    clipboardStore.toggleSelected('a');
+    clipboardStore.toggleSelected('b');
+    await clipboardStore.useSelectedClips('default', { type: 'csvRow' });
+
+    const pastes = calls.filter((entry) => entry.cmd === 'paste_clips');
+    expect(pastes[0].args).toEqual({
+      ids: ['a', 'b'],
+      mode: 'default',
+      separator: '\n',
+      format: { type: 'csvRow' },
+    });
+  });
+
+  test('pushToPasteStack queues ids in selection order and records the stack size', async () => {
+    const calls: Array<{ cmd: string; args?: Record<string, unknown> }> = [];
+    installTauriInvoke((cmd, args) => {
+      calls.push({ cmd, args });
+      return cmd === 'push_paste_stack' ? 3 : null;
+    });
+
+    clipboardStore.toggleSelected('b');
+    clipboardStore.toggleSelected('a');
+    await clipboardStore.pushToPasteStack([...clipboardStore.selectedIds]);
+
+    expect(calls).toEqual([{ cmd: 'push_paste_stack', args: { ids: ['b', 'a'] } }]);
+    expect(clipboardStore.pasteStackCount).toBe(3);
+    expect(clipboardStore.selectedIds.size).toBe(0);
+  });
+
+  test('openGroup loads the group clips and clearing the filter drops them', async () => {
+    installTauriInvoke((cmd, args) => {
+      if (cmd === 'get_group_clips' && args?.group === 'tickets') {
+        return [clip({ id: 'ticket', groupName: 'tickets' })];
+      }
+      return null;
+    });
+
+    await clipboardStore.openGroup('tickets');
+    expect(clipboardStore.groupFilter).toBe('tickets');
+    expect(clipboardStore.groupItems.map((item) => item.id)).toEqual(['ticket']);
+
+    clipboardStore.clearGroupFilter();
+    expect(clipboardStore.groupFilter).toBeNull();
+    expect(clipboardStore.groupItems).toEqual([]);
+  });
+
+  test('a kind filter lists that kind until the filter is lifted', async () => {
+    const calls: Array<Record<string, unknown> | undefined> = [];
+    installTauriInvoke((cmd, args) => {
+      if (cmd === 'search_clips') {
+        calls.push(args);
+        return args?.kind === 'url' ? [clip({ id: 'link', textKind: 'url' })] : [];
+      }
+      if (cmd === 'get_recent_clips') return [clip({ id: 'recent' })];
+      if (cmd === 'get_pinned_clips') return [];
+      return null;
+    });
+
+    await clipboardStore.setKindFilter('url');
+    expect(calls).toEqual([{ query: '', kind: 'url' }]);
+    expect(clipboardStore.searchResults.map((item) => item.id)).toEqual(['link']);
+
+    await clipboardStore.setKindFilter(null);
+    expect(clipboardStore.kindFilter).toBeNull();
+    expect(clipboardStore.searchResults).toEqual([]);
+    expect(clipboardStore.recentItems.map((item) => item.id)).toEqual(['recent']);
+    expect(calls).toHaveLength(1);
+  });
+
+  test('showSimilarImages lists the image before the ones that look like it', async () => {
+    const shown = clip({ id: 'shot', contentType: 'image' });
+    installTauriInvoke((cmd, args) => {
+      if (cmd === 'get_similar_images' && args?.id === 'shot') {
+        return [clip({ id: 'shot-again', contentType: 'image' })];
+      }
+      return null;
+    });
+
+    await clipboardStore.showSimilarImages(clip({ id: 'note' }));
+    expect(clipboardStore.similarTo).toBeNull();
+
+    await clipboardStore.showSimilarImages(shown);
+    expect(clipboardStore.similarTo).toBe('shot');
+    expect(clipboardStore.similarItems.map((item) => item.id)).toEqual(['shot', 'shot-again']);
+
+    clipboardStore.clearSimilarImages();
+    expect(clipboardStore.similarTo).toBeNull();
+    expect(clipboardStore.similarItems).toEqual([]);
+  });
+
   test('deleting a clip drops it from the multi-selection', async () => {
     installTauriInvoke((cmd) => {
       if (cmd === 'delete_clip') return null;